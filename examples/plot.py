@@ -42,15 +42,22 @@ if __name__ == "__main__":
     t = data[0]
     ys = data[1:]
 
-    with open(args.input_file, "r") as f_result:
+    with open(args.input_file, "r", encoding="utf-8") as f_result:
         ylabels = f_result.readline().split("\t")[1:]
 
     assert len(ys) == len(ylabels)
-    fig, axs = plt.subplots(math.ceil(len(ys) / 2), 2, layout="constrained")
+    # Columns labelled "δ..." hold the error bar of the preceding column.
+    series = []
+    for y, ylabel in zip(ys, ylabels):
+        if ylabel.startswith("δ") and series:
+            series[-1][2] = y
+        else:
+            series.append([y, ylabel, None])
+    fig, axs = plt.subplots(math.ceil(len(series) / 2), 2, layout="constrained")
     axs = np.array(axs).flatten()
 
-    for ax, y, ylabel in zip(axs, ys, ylabels):
-        ax.plot(t, y, marker=".")
+    for ax, (y, ylabel, yerr) in zip(axs, series):
+        ax.errorbar(t, y, yerr=yerr, marker=".")
         ax.set_xlabel("T")
         ax.set_ylabel(ylabel)
 
@@ -47,10 +47,15 @@ class Spinmc:
         model: Literal["ising", "xy", "heisenberg"],
         equilibration_steps: int,
         measurement_steps: int,
-        algorithm: Literal["wolff", "metropolis"],
+        algorithm: Literal["wolff", "metropolis", "checkerboard"],
         num_threads: int,
         temperatures: list[float] | npt.NDArray[np.floating],
         boltzmann_constand: float | None = None,
+        seed: int | None = None,
+        auto_equilibration: bool | None = None,
+        measurement_target_error: float | None = None,
+        target_observables: list[str] | None = None,
+        replicas_per_temperature: int | None = None,
     ):
         self.__save_dict("simulation")
         self.params_dict["simulation"]["temperatures"] = [
@@ -70,6 +75,17 @@ class Spinmc:
         self.params_dict["exchange"].append(self.params_dict.pop("exchange_tmp"))
         return self
 
+    def add_order_parameter(
+        self,
+        weights: list[float],
+        wavevector: list[float] | None = None,
+    ):
+        self.__save_dict("order_parameter_tmp")
+        self.params_dict.setdefault("order_parameter", []).append(
+            self.params_dict.pop("order_parameter_tmp")
+        )
+        return self
+
     def set_anisotropy(
         self,
         axis: list[list[float]],
@@ -107,6 +123,66 @@ class Spinmc:
         self.__save_dict("snapshot")
         return self
 
+    def set_parallel_tempering(
+        self,
+        ladder: Literal["fixed", "acceptance", "round_trip"] = "fixed",
+        adapt_interval: int = 1000,
+        savefile: str = "parallel_tempering.txt",
+        anisotropy_scales: list[float] | None = None,
+    ):
+        self.__save_dict("parallel_tempering")
+        return self
+
+    def set_time_series(
+        self,
+        equilibration_interval: int = 0,
+        save_directory: str = "time_series",
+    ):
+        self.__save_dict("time_series")
+        return self
+
+    def set_correlation(
+        self,
+        shells: int = 3,
+        savefile: str = "correlation.txt",
+    ):
+        self.__save_dict("correlation")
+        return self
+
+    def set_structure_factor(
+        self,
+        q_path: list[list[float]] | None = None,
+        path_points: int = 20,
+        full_grid: bool = False,
+        interval: int = 1,
+        save_directory: str = "structure_factor",
+    ):
+        self.__save_dict("structure_factor")
+        return self
+
+    def set_topological_charge(
+        self,
+        savefile: str = "topological_charge.txt",
+        density_map: bool = False,
+        density_directory: str = "topological_density",
+    ):
+        self.__save_dict("topological_charge")
+        return self
+
+    def set_reweighting(
+        self,
+        temperatures: list[float] | npt.NDArray[np.floating],
+        method: Literal["single_histogram", "multi_histogram"] = "multi_histogram",
+        energy_bins: int = 1000,
+        savefile: str = "reweighted.txt",
+        samples_directory: str | None = None,
+    ):
+        self.__save_dict("reweighting")
+        self.params_dict["reweighting"]["temperatures"] = [
+            float(i) for i in self.params_dict["reweighting"]["temperatures"]
+        ]
+        return self
+
     def run(self):
         from ._spinmc import run_from_py  # pyright: ignore[reportUnknownVariableType]
         import rtoml
@@ -20,7 +20,7 @@ enum Commands {
 }
 fn main() -> Result<()> {
     use colored::*;
-    use spinmc::runner::run;
+    use spinmc::runner::{install_interrupt_handler, run};
     use std::path::Path;
 
     let subscriber = FmtSubscriber::builder()
@@ -30,6 +30,7 @@ fn main() -> Result<()> {
 
     let args = Args::parse();
     if let Some(Commands::Run { input }) = &args.command {
+        install_interrupt_handler()?;
         let content = std::fs::read_to_string(input)?;
         let cwd = std::env::current_dir()?;
         if let Some(parent) = Path::new(input)
@@ -1,101 +1,26 @@
 use super::*;
+use crate::lattice::NeighborTable;
 use crate::spin::{HeisenbergSpin, IsingSpin, SpinState};
 
-fn make_calc_input_with_exchange(spins: &[IsingSpin; 2], j: f64) -> CalcInput<IsingSpin> {
-    CalcInput {
-        magnitude: 1.0,
-        exchange_neighbors: Some(vec![
-            (&spins[0] as *const IsingSpin, j),
-            (&spins[1] as *const IsingSpin, j),
-        ]),
-        exchanges: vec![j, j],
-        exchange_neighbor_index: vec![0, 1],
-        ..Default::default()
-    }
-}
-
-fn make_calc_input_no_exchange() -> CalcInput<IsingSpin> {
-    CalcInput::default()
+/// Site 0 bonded with strength `j` to sites 0 and 1.
+fn exchange_table(j: f64) -> NeighborTable {
+    let mut table = NeighborTable::default();
+    table.push_site([(0, j, 0), (1, j, 0)]).unwrap();
+    table
 }
 
 // --- CalcInput ---
 
 #[test]
 fn calc_input_default() {
-    let ci: CalcInput<IsingSpin> = Default::default();
+    let ci = CalcInput::default();
     assert_eq!(ci.magnitude, 0.0);
-    assert!(ci.exchange_neighbor_index.is_empty());
-    assert!(ci.exchanges.is_empty());
-    assert!(ci.exchange_neighbors.is_none());
     assert!(ci.dm_neighbors.is_none());
     assert!(ci.magnetic_field.is_none());
     assert!(ci.easy_axis.is_none());
     assert_eq!(ci.anisotropy, (0.0, [0.0, 0.0, 1.0]));
 }
 
-#[test]
-fn validate_exchange_neighbor_none_ok() {
-    let ci: CalcInput<IsingSpin> = Default::default();
-    assert!(ci.validate_exchange_neighbor().is_ok());
-}
-
-#[test]
-fn validate_exchange_neighbor_unique_ok() {
-    let spins = [
-        IsingSpin::along_z(1.0).unwrap(),
-        IsingSpin::along_z(1.0).unwrap(),
-    ];
-    let ci = CalcInput {
-        magnitude: 1.0,
-        exchange_neighbors: Some(vec![
-            (&spins[0] as *const IsingSpin, 1.0),
-            (&spins[1] as *const IsingSpin, 1.0),
-        ]),
-        exchanges: vec![1.0, 1.0],
-        exchange_neighbor_index: vec![0, 1],
-        ..Default::default()
-    };
-    assert!(ci.validate_exchange_neighbor().is_ok());
-}
-
-#[test]
-fn validate_exchange_neighbor_duplicate_error() {
-    let spins = [
-        IsingSpin::along_z(1.0).unwrap(),
-        IsingSpin::along_z(1.0).unwrap(),
-    ];
-    let ci = CalcInput {
-        magnitude: 1.0,
-        exchange_neighbors: Some(vec![
-            (&spins[0] as *const IsingSpin, 1.0),
-            (&spins[0] as *const IsingSpin, 2.0),
-        ]),
-        exchanges: vec![1.0, 2.0],
-        exchange_neighbor_index: vec![0, 1],
-        ..Default::default()
-    };
-    assert!(ci.validate_exchange_neighbor().is_err());
-}
-
-#[test]
-fn validate_exchange_neighbor_length_mismatch_error() {
-    let spins = [
-        IsingSpin::along_z(1.0).unwrap(),
-        IsingSpin::along_z(1.0).unwrap(),
-    ];
-    let ci = CalcInput {
-        magnitude: 1.0,
-        exchange_neighbors: Some(vec![
-            (&spins[0] as *const IsingSpin, 1.0),
-            (&spins[1] as *const IsingSpin, 1.0),
-        ]),
-        exchanges: vec![1.0], // too short
-        exchange_neighbor_index: vec![0, 1],
-        ..Default::default()
-    };
-    assert!(ci.validate_exchange_neighbor().is_err());
-}
-
 // --- exchange_energy (with 1/2 factor) ---
 
 #[test]
@@ -104,8 +29,7 @@ fn exchange_energy_ferromagnetic() {
         IsingSpin::along_z(1.0).unwrap(),
         IsingSpin::along_z(1.0).unwrap(),
     ];
-    let ci = make_calc_input_with_exchange(&spins, 2.0);
-    let result = exchange_energy(&spins[0], &ci);
+    let result = exchange_energy(&spins[0], exchange_table(2.0).bonds(0), &spins);
     // Two neighbors both parallel, each -j*dot = -2.0*1 = -2.0, sum = -4.0, /2 = -2.0
     assert!((result + 2.0).abs() < 1e-10);
 }
@@ -116,8 +40,7 @@ fn exchange_energy_antiferromagnetic() {
         IsingSpin::along_z(1.0).unwrap(),
         IsingSpin::along_z(-1.0).unwrap(),
     ];
-    let ci = make_calc_input_with_exchange(&spins, 2.0);
-    let result = exchange_energy(&spins[0], &ci);
+    let result = exchange_energy(&spins[0], exchange_table(2.0).bonds(0), &spins);
     // One neighbor parallel (-2*1=-2), one antiparallel (-2*(-1)=2), sum=0, /2=0
     assert!((result - 0.0).abs() < 1e-10);
 }
@@ -125,8 +48,7 @@ fn exchange_energy_antiferromagnetic() {
 #[test]
 fn exchange_energy_no_neighbors_returns_zero() {
     let spin = IsingSpin::along_z(1.0).unwrap();
-    let ci = make_calc_input_no_exchange();
-    assert_eq!(exchange_energy(&spin, &ci), 0.0);
+    assert_eq!(exchange_energy(&spin, Bonds::default(), &[spin]), 0.0);
 }
 
 // --- local_exchange_energy (no 1/2 factor) ---
@@ -137,8 +59,7 @@ fn local_exchange_energy_ferromagnetic() {
         IsingSpin::along_z(1.0).unwrap(),
         IsingSpin::along_z(1.0).unwrap(),
     ];
-    let ci = make_calc_input_with_exchange(&spins, 2.0);
-    let result = local_exchange_energy(&spins[0], &ci);
+    let result = local_exchange_energy(&spins[0], exchange_table(2.0).bonds(0), &spins);
     // Two neighbors parallel: -4.0 total (no /2)
     assert!((result + 4.0).abs() < 1e-10);
 }
@@ -146,8 +67,7 @@ fn local_exchange_energy_ferromagnetic() {
 #[test]
 fn local_exchange_energy_no_neighbors_returns_zero() {
     let spin = IsingSpin::along_z(1.0).unwrap();
-    let ci = make_calc_input_no_exchange();
-    assert_eq!(local_exchange_energy(&spin, &ci), 0.0);
+    assert_eq!(local_exchange_energy(&spin, Bonds::default(), &[spin]), 0.0);
 }
 
 #[test]
@@ -156,9 +76,9 @@ fn exchange_vs_local_differs_by_factor_two() {
         IsingSpin::along_z(1.0).unwrap(),
         IsingSpin::along_z(1.0).unwrap(),
     ];
-    let ci = make_calc_input_with_exchange(&spins, 2.0);
-    let e_tot = exchange_energy(&spins[0], &ci);
-    let e_loc = local_exchange_energy(&spins[0], &ci);
+    let table = exchange_table(2.0);
+    let e_tot = exchange_energy(&spins[0], table.bonds(0), &spins);
+    let e_loc = local_exchange_energy(&spins[0], table.bonds(0), &spins);
     assert!((2.0 * e_tot - e_loc).abs() < 1e-10);
 }
 
@@ -228,6 +148,7 @@ fn make_ham(exchange: bool, anisotropy: bool) -> Hamiltonian {
             zeeman_enable: false,
             dm_enable: false,
         },
+        anisotropy_scale: 1.0,
     }
 }
 
@@ -239,6 +160,7 @@ fn make_ham_full(exchange: bool, anisotropy: bool, zeeman: bool, dm: bool) -> Ha
             zeeman_enable: zeeman,
             dm_enable: dm,
         },
+        anisotropy_scale: 1.0,
     }
 }
 
@@ -246,14 +168,12 @@ fn make_ham_full(exchange: bool, anisotropy: bool, zeeman: bool, dm: bool) -> Ha
 fn hamiltonian_compute_all_disabled() {
     let ham = make_ham(false, false);
     let spin = IsingSpin::along_z(1.0).unwrap();
-    let ci = make_calc_input_with_exchange(
-        &[
-            IsingSpin::along_z(1.0).unwrap(),
-            IsingSpin::along_z(1.0).unwrap(),
-        ],
-        2.0,
+    let spins = [spin, IsingSpin::along_z(1.0).unwrap()];
+    let ci = CalcInput::default();
+    assert_eq!(
+        ham.compute(&spin, &ci, exchange_table(2.0).bonds(0), &spins),
+        0.0
     );
-    assert_eq!(ham.compute(&spin, &ci, &[]), 0.0);
 }
 
 #[test]
@@ -263,9 +183,10 @@ fn hamiltonian_compute_exchange_only() {
         IsingSpin::along_z(1.0).unwrap(),
         IsingSpin::along_z(1.0).unwrap(),
     ];
-    let ci = make_calc_input_with_exchange(&spins, 2.0);
-    let e_calc = ham.compute(&spins[0], &ci, &[]);
-    let e_direct = exchange_energy(&spins[0], &ci);
+    let table = exchange_table(2.0);
+    let ci = CalcInput::default();
+    let e_calc = ham.compute(&spins[0], &ci, table.bonds(0), &spins);
+    let e_direct = exchange_energy(&spins[0], table.bonds(0), &spins);
     assert!((e_calc - e_direct).abs() < 1e-10);
 }
 
@@ -277,7 +198,7 @@ fn hamiltonian_compute_anisotropy_only() {
         anisotropy: (3.0, [0.0, 0.0, 1.0]),
         ..Default::default()
     };
-    let e_calc = ham.compute(&spin, &ci, &[]);
+    let e_calc = ham.compute(&spin, &ci, Bonds::default(), &[spin]);
     let e_direct = anisotropy_energy(&spin, &ci);
     assert!((e_calc - e_direct).abs() < 1e-10);
 }
@@ -289,19 +210,15 @@ fn hamiltonian_compute_exchange_plus_anisotropy() {
         IsingSpin::along_z(1.0).unwrap(),
         IsingSpin::along_z(1.0).unwrap(),
     ];
+    let table = exchange_table(2.0);
     let ci = CalcInput {
         magnitude: 1.0,
-        exchange_neighbors: Some(vec![
-            (&spins[0] as *const IsingSpin, 2.0),
-            (&spins[1] as *const IsingSpin, 2.0),
-        ]),
-        exchanges: vec![2.0, 2.0],
-        exchange_neighbor_index: vec![0, 1],
         anisotropy: (3.0, [0.0, 0.0, 1.0]),
         ..Default::default()
     };
-    let e = ham.compute(&spins[0], &ci, &[]);
-    let expected = exchange_energy(&spins[0], &ci) + anisotropy_energy(&spins[0], &ci);
+    let e = ham.compute(&spins[0], &ci, table.bonds(0), &spins);
+    let expected =
+        exchange_energy(&spins[0], table.bonds(0), &spins) + anisotropy_energy(&spins[0], &ci);
     assert!((e - expected).abs() < 1e-10);
 }
 
@@ -312,8 +229,8 @@ fn hamiltonian_local_compute_uses_no_half_factor() {
         IsingSpin::along_z(1.0).unwrap(),
         IsingSpin::along_z(1.0).unwrap(),
     ];
-    let ci = make_calc_input_with_exchange(&spins, 2.0);
-    let e = ham.local_compute(&spins[0], &ci, &[]);
+    let ci = CalcInput::default();
+    let e = ham.local_compute(&spins[0], &ci, exchange_table(2.0).bonds(0), &spins);
     assert!((e + 4.0).abs() < 1e-10);
 }
 
@@ -337,7 +254,7 @@ fn hamiltonian_compute_zeeman_panics_until_implemented() {
     let spin = IsingSpin::along_z(1.0).unwrap();
     let ci = CalcInput::default();
 
-    ham.compute(&spin, &ci, &[]);
+    ham.compute(&spin, &ci, Bonds::default(), &[spin]);
 }
 
 #[test]
@@ -347,7 +264,7 @@ fn hamiltonian_compute_dm_panics_until_implemented() {
     let spin = IsingSpin::along_z(1.0).unwrap();
     let ci = CalcInput::default();
 
-    ham.compute(&spin, &ci, &[]);
+    ham.compute(&spin, &ci, Bonds::default(), &[spin]);
 }
 
 #[test]
@@ -357,7 +274,7 @@ fn hamiltonian_local_compute_zeeman_panics_until_implemented() {
     let spin = IsingSpin::along_z(1.0).unwrap();
     let ci = CalcInput::default();
 
-    ham.local_compute(&spin, &ci, &[]);
+    ham.local_compute(&spin, &ci, Bonds::default(), &[spin]);
 }
 
 #[test]
@@ -367,5 +284,5 @@ fn hamiltonian_local_compute_dm_panics_until_implemented() {
     let spin = IsingSpin::along_z(1.0).unwrap();
     let ci = CalcInput::default();
 
-    ham.local_compute(&spin, &ci, &[]);
+    ham.local_compute(&spin, &ci, Bonds::default(), &[spin]);
 }
@@ -1,28 +1,23 @@
-use crate::{config::Config, spin::SpinState};
-use std::collections::HashSet;
+use crate::{
+    config::Config,
+    lattice::Bonds,
+    spin::{Real, SpinState, widen},
+};
 
+/// Single-site parameters; pair couplings live in the grid's `NeighborTable`.
 #[derive(Clone, Debug)]
-pub struct CalcInput<S: SpinState> {
-    pub magnitude: f64,
-    pub exchange_neighbors: Option<Vec<(*const S, f64)>>,
-    pub exchanges: Vec<f64>,
-    pub exchange_neighbor_index: Vec<usize>,
-    pub dm_neighbors: Option<Vec<(usize, [f64; 3], f64)>>,
-    pub magnetic_field: Option<[f64; 3]>,
-    pub easy_axis: Option<[f64; 3]>,
-    pub anisotropy: (f64, [f64; 3]),
+pub struct CalcInput {
+    pub magnitude: Real,
+    pub dm_neighbors: Option<Vec<(usize, [Real; 3], Real)>>,
+    pub magnetic_field: Option<[Real; 3]>,
+    pub easy_axis: Option<[Real; 3]>,
+    pub anisotropy: (Real, [Real; 3]),
 }
 
-unsafe impl<S: SpinState> Send for CalcInput<S> {}
-unsafe impl<S: SpinState> Sync for CalcInput<S> {}
-
-impl<S: SpinState> Default for CalcInput<S> {
+impl Default for CalcInput {
     fn default() -> Self {
         CalcInput {
             magnitude: 0.0,
-            exchange_neighbor_index: vec![],
-            exchanges: vec![],
-            exchange_neighbors: None,
             dm_neighbors: None,
             magnetic_field: None,
             easy_axis: None,
@@ -31,89 +26,49 @@ impl<S: SpinState> Default for CalcInput<S> {
     }
 }
 
-impl<S: SpinState> CalcInput<S> {
-    pub fn validate_exchange_neighbor(&self) -> anyhow::Result<()> {
-        let pairs = &self.exchange_neighbors;
-        if let Some(vec) = pairs {
-            let mut seen = HashSet::new();
-            for (index, _) in vec {
-                if !seen.insert(index) {
-                    anyhow::bail!(
-                        "Duplicate neighbor indices found in your exchange coupling configuration. Please ensure all neighbor indices are unique."
-                    );
-                }
-            }
-
-            if vec.len() != self.exchange_neighbor_index.len() || vec.len() != self.exchanges.len()
-            {
-                anyhow::bail!(
-                    "unexpected exchange data length: exchange_ptr={}, exchange_neighbors={}, exchanges={}",
-                    vec.len(),
-                    self.exchange_neighbor_index.len(),
-                    self.exchanges.len()
-                );
-            }
-        }
-        Ok(())
-    }
-}
-
 /// Compute total exchange energy, with 1/2 factor to avoid double counting.
 /// Should only be used in total energy evaluation.
-fn exchange_energy<S: SpinState>(spin: &S, calc_input: &CalcInput<S>) -> f64 {
-    if let Some(list) = calc_input.exchange_neighbors.as_ref() {
-        let energy: f64 = list
-            .iter()
-            .map(|(n, j)| {
-                unsafe {
-                    let neighbor = &*(*n); // *n 是 *const S，解引用为 &S
-                    -j * spin.dot(neighbor)
-                }
-            })
-            .sum();
-        energy / 2.
-        // Each exchange interaction between sites i and j is counted twice
-        // (once from i → j and once from j → i), so we divide by 2 to avoid double-counting.
-    } else {
-        0.0
-    }
+fn exchange_energy<S: SpinState>(spin: &S, bonds: Bonds<'_>, spins: &[S]) -> f64 {
+    // Each exchange interaction between sites i and j is counted twice
+    // (once from i → j and once from j → i), so we divide by 2 to avoid double-counting.
+    local_exchange_energy(spin, bonds, spins) / 2.
 }
 
 /// Compute local exchange energy for one site. No 1/2 factor.
 /// Used in energy_diff or local site updates.
-fn local_exchange_energy<S: SpinState>(spin: &S, calc_input: &CalcInput<S>) -> f64 {
-    if let Some(list) = calc_input.exchange_neighbors.as_ref() {
-        list.iter()
-            .map(|(n, j)| {
-                unsafe {
-                    let neighbor = &*(*n); // *n 是 *const S，解引用为 &S
-                    -j * spin.dot(neighbor)
-                }
-            })
-            .sum()
-    } else {
-        0.0
-    }
+fn local_exchange_energy<S: SpinState>(spin: &S, bonds: Bonds<'_>, spins: &[S]) -> f64 {
+    bonds
+        .iter()
+        .map(|(neighbor, j)| -j * spin.dot(&spins[neighbor]))
+        .sum()
 }
 
-fn zeeman_energy<S: SpinState>(_: &S, _: &CalcInput<S>) -> f64 {
+fn zeeman_energy<S: SpinState>(_: &S, _: &CalcInput) -> f64 {
     unimplemented!();
 }
 
-fn anisotropy_energy<S: SpinState>(spin: &S, calc_input: &CalcInput<S>) -> f64 {
+fn anisotropy_energy<S: SpinState>(spin: &S, calc_input: &CalcInput) -> f64 {
     let (strength, axis) = calc_input.anisotropy;
 
     let spin_array = spin.to_array();
 
-    let dot = spin_array[0] * axis[0] + spin_array[1] * axis[1] + spin_array[2] * axis[2];
+    let dot: f64 = (0..3).map(|k| spin_array[k] * widen(axis[k])).sum();
 
-    -strength * dot * dot
+    -widen(strength) * dot * dot
 }
 
-fn dm_energy<S: SpinState>(_: &S, _: &CalcInput<S>, _: &[S]) -> f64 {
+fn dm_energy<S: SpinState>(_: &S, _: &CalcInput, _: &[S]) -> f64 {
     unimplemented!();
 }
 
+/// Energy of one site split by Hamiltonian term, with the same 1/2 factor on
+/// pair terms as `Hamiltonian::compute`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EnergyTerms {
+    pub exchange: f64,
+    pub anisotropy: f64,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct HamiltonianConfig {
     pub exchange_enable: bool,
@@ -125,6 +80,9 @@ pub struct HamiltonianConfig {
 #[derive(Clone, Debug)]
 pub struct Hamiltonian {
     pub config: HamiltonianConfig,
+    /// Factor on every single-ion anisotropy strength; the replica-exchange
+    /// parameter of anisotropy-scale parallel tempering.
+    pub anisotropy_scale: f64,
 }
 impl Hamiltonian {
     pub fn new(config: &Config) -> Self {
@@ -137,13 +95,22 @@ impl Hamiltonian {
             zeeman_enable: false,
             dm_enable: false,
         };
-        Self { config: ham_config }
+        Self {
+            config: ham_config,
+            anisotropy_scale: 1.0,
+        }
     }
 
-    pub fn compute<S: SpinState>(&self, spin: &S, calc_input: &CalcInput<S>, spins: &[S]) -> f64 {
+    pub fn compute<S: SpinState>(
+        &self,
+        spin: &S,
+        calc_input: &CalcInput,
+        bonds: Bonds<'_>,
+        spins: &[S],
+    ) -> f64 {
         let mut result = 0.0;
         if self.config.exchange_enable {
-            result += exchange_energy(spin, calc_input);
+            result += exchange_energy(spin, bonds, spins);
         }
 
         if self.config.zeeman_enable {
@@ -151,7 +118,7 @@ impl Hamiltonian {
         }
 
         if self.config.anisotropy_enable {
-            result += anisotropy_energy(spin, calc_input);
+            result += self.anisotropy_scale * anisotropy_energy(spin, calc_input);
         }
         if self.config.dm_enable {
             result += dm_energy(spin, calc_input, spins)
@@ -161,12 +128,13 @@ impl Hamiltonian {
     pub fn local_compute<S: SpinState>(
         &self,
         spin: &S,
-        calc_input: &CalcInput<S>,
+        calc_input: &CalcInput,
+        bonds: Bonds<'_>,
         spins: &[S],
     ) -> f64 {
         let mut result = 0.0;
         if self.config.exchange_enable {
-            result += local_exchange_energy(spin, calc_input);
+            result += local_exchange_energy(spin, bonds, spins);
         }
 
         if self.config.zeeman_enable {
@@ -174,7 +142,7 @@ impl Hamiltonian {
         }
 
         if self.config.anisotropy_enable {
-            result += anisotropy_energy(spin, calc_input);
+            result += self.anisotropy_scale * anisotropy_energy(spin, calc_input);
         }
         if self.config.dm_enable {
             result += dm_energy(spin, calc_input, spins)
@@ -182,7 +150,49 @@ impl Hamiltonian {
         result
     }
 
-    pub fn compute_anisotropy<S: SpinState>(&self, spin: &S, calc_input: &CalcInput<S>) -> f64 {
+    /// Per-term counterpart of `compute`. Zeeman and DM terms are not
+    /// implemented yet and are never enabled.
+    pub fn compute_terms<S: SpinState>(
+        &self,
+        spin: &S,
+        calc_input: &CalcInput,
+        bonds: Bonds<'_>,
+        spins: &[S],
+    ) -> EnergyTerms {
+        EnergyTerms {
+            exchange: if self.config.exchange_enable {
+                exchange_energy(spin, bonds, spins)
+            } else {
+                0.0
+            },
+            anisotropy: if self.config.anisotropy_enable {
+                self.anisotropy_scale * anisotropy_energy(spin, calc_input)
+            } else {
+                0.0
+            },
+        }
+    }
+
+    /// Add the exchange energy of one site, with the 1/2 factor, to the
+    /// entry of `energies` of the `[[exchange]]` entry of each bond.
+    pub fn accumulate_exchange_entries<S: SpinState>(
+        &self,
+        spin: &S,
+        bonds: Bonds<'_>,
+        spins: &[S],
+        energies: &mut [f64],
+    ) {
+        for ((neighbor, j), entry) in bonds.iter().zip(bonds.entries()) {
+            energies[entry] -= 0.5 * j * spin.dot(&spins[neighbor]);
+        }
+    }
+
+    pub fn compute_anisotropy<S: SpinState>(&self, spin: &S, calc_input: &CalcInput) -> f64 {
+        self.anisotropy_scale * anisotropy_energy(spin, calc_input)
+    }
+
+    /// Anisotropy energy at unit `anisotropy_scale`, the energy conjugate to it.
+    pub fn unscaled_anisotropy<S: SpinState>(&self, spin: &S, calc_input: &CalcInput) -> f64 {
         anisotropy_energy(spin, calc_input)
     }
 }
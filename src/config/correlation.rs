@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Correlation {
+    /// Distance shells per sublattice pair, counted as in `neighbor_order`.
+    #[serde(default = "default_shells")]
+    pub shells: usize,
+    #[serde(default = "default_savefile")]
+    pub savefile: String,
+}
+
+fn default_shells() -> usize {
+    3
+}
+
+fn default_savefile() -> String {
+    "correlation.txt".to_string()
+}
+
+impl Correlation {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.shells == 0 {
+            anyhow::bail!("correlation shells must be greater than zero");
+        }
+        if self.savefile.trim().is_empty() {
+            anyhow::bail!("correlation savefile must not be empty");
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Correlation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\nCorrelation Function G(r): Enable")?;
+        writeln!(f, "  Shells per Sublattice Pair: {}", self.shells)?;
+        writeln!(f, "  Savefile: {}", self.savefile)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "correlation_tests.rs"]
+mod tests;
@@ -0,0 +1,24 @@
+use super::*;
+
+#[test]
+fn defaults_use_three_shells() {
+    let correlation: Correlation = toml::from_str("").unwrap();
+    assert_eq!(correlation.shells, 3);
+    assert_eq!(correlation.savefile, "correlation.txt");
+    assert!(correlation.validate().is_ok());
+}
+
+#[test]
+fn validate_rejects_zero_shells_and_empty_savefile() {
+    let correlation = Correlation {
+        shells: 0,
+        savefile: "correlation.txt".into(),
+    };
+    assert!(correlation.validate().is_err());
+
+    let correlation = Correlation {
+        shells: 2,
+        savefile: " ".into(),
+    };
+    assert!(correlation.validate().is_err());
+}
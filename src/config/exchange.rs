@@ -25,6 +25,7 @@ pub struct ParsedExchange {
     pub to_sub: usize,
     pub offset: [isize; 3],
     pub strength: f64,
+    pub entry: usize, // index of the [[exchange]] entry it came from, set by Config::new
 }
 
 impl Exchange {
@@ -49,6 +50,7 @@ impl Exchange {
                         to_sub,
                         offset: *offset,
                         strength: self.strength,
+                        entry: 0,
                     });
                 }
             }
@@ -73,6 +75,7 @@ impl Exchange {
                         to_sub: neighbor.to,
                         offset: neighbor.offset,
                         strength: self.strength,
+                        entry: 0,
                     });
                 }
             }
@@ -99,6 +102,7 @@ impl Exchange {
                         to_sub: distance.neighbor.to,
                         offset: distance.neighbor.offset,
                         strength: self.strength,
+                        entry: 0,
                     });
                 }
             }
@@ -492,6 +492,7 @@ fn display_parsed_exchange() {
         to_sub: 1,
         offset: [1, 0, -1],
         strength: 0.5,
+        entry: 0,
     };
     let s = format!("{p}");
     assert!(s.contains("0.5"));
@@ -38,6 +38,13 @@ impl Grid {
         }
         Ok(())
     }
+
+    /// Axes that are periodic and longer than one cell.
+    pub fn periodic_axes(&self) -> Vec<usize> {
+        (0..3)
+            .filter(|&axis| self.periodic_boundary[axis] && self.dimensions[axis] > 1)
+            .collect()
+    }
 }
 impl fmt::Display for Grid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
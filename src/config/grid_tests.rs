@@ -90,3 +90,14 @@ fn display() {
     assert!(s.contains("Grid"));
     assert!(s.contains("2, 2, 1"));
 }
+
+#[test]
+fn periodic_axes_skip_open_and_single_cell_axes() {
+    let g = Grid {
+        dimensions: [4, 1, 4],
+        sublattices: 1,
+        spin_magnitudes: vec![1.0],
+        periodic_boundary: [true, true, false],
+    };
+    assert_eq!(g.periodic_axes(), vec![0]);
+}
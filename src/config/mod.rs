@@ -2,18 +2,32 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 mod anisotropy;
+mod correlation;
 mod exchange;
 mod grid;
+mod order_parameter;
 mod output;
+mod parallel_tempering;
+mod reweighting;
 mod simulation;
 mod structure;
+mod structure_factor;
+mod time_series;
+mod topological_charge;
 
 pub use crate::lattice::Structure;
 pub use anisotropy::{Anisotropy, ParsedAnisotropy};
+pub use correlation::Correlation;
 pub use exchange::{Exchange, ParsedExchange};
 pub use grid::Grid;
+pub use order_parameter::OrderParameter;
 pub use output::Output;
+pub use parallel_tempering::{Ladder, ParallelTempering};
+pub use reweighting::{Reweighting, ReweightingMethod};
 pub use simulation::Simulation;
+pub use structure_factor::StructureFactor;
+pub use time_series::TimeSeries;
+pub use topological_charge::TopologicalCharge;
 
 #[cfg(feature = "snapshots")]
 mod snapshots;
@@ -33,6 +47,12 @@ pub struct Config {
     pub output: Output,
     #[cfg(feature = "snapshots")]
     pub snapshots: Option<Snapshots>,
+    pub reweighting: Option<Reweighting>,
+    pub parallel_tempering: Option<ParallelTempering>,
+    pub time_series: Option<TimeSeries>,
+    pub correlation: Option<Correlation>,
+    pub structure_factor: Option<StructureFactor>,
+    pub topological_charge: Option<TopologicalCharge>,
 
     pub exchange: Vec<Exchange>,
     #[serde(skip)]
@@ -41,14 +61,17 @@ pub struct Config {
     pub anisotropy: Option<Anisotropy>,
     #[serde(skip)]
     pub parsed_anisotropy: Vec<ParsedAnisotropy>,
+
+    #[serde(default)]
+    pub order_parameter: Vec<OrderParameter>,
 }
 
 impl Config {
     pub fn new(content: &str) -> anyhow::Result<Self> {
         let mut config: Config = toml::from_str(content)?;
         config.validate()?;
-        for exchange in &config.exchange {
-            let exchange_params = exchange.parse(
+        for (entry, exchange) in config.exchange.iter().enumerate() {
+            let mut exchange_params = exchange.parse(
                 &config
                     .structure
                     .as_ref()
@@ -56,6 +79,9 @@ impl Config {
                     .transpose()?,
                 config.grid.periodic_boundary,
             )?;
+            for exchange_param in &mut exchange_params {
+                exchange_param.entry = entry;
+            }
             config.parsed_exchange.extend(exchange_params);
         }
         if let Some(anisotropy) = &config.anisotropy {
@@ -73,7 +99,123 @@ impl Config {
         if let Some(stru) = &self.structure {
             stru.validate(self.grid.sublattices)?;
         }
+        for order_parameter in &self.order_parameter {
+            order_parameter.validate(self.grid.sublattices)?;
+        }
         self.output.validate(self.grid.sublattices)?;
+        if self.simulation.measurement_target_error.is_some() {
+            if !self.output.error_bars {
+                anyhow::bail!("measurement_target_error requires output.error_bars = true");
+            }
+            for observable in &self.simulation.target_observables {
+                let enabled = match observable {
+                    TargetObservable::Energy => self.output.energy,
+                    TargetObservable::HeatCapacity => self.output.heat_capacity,
+                    TargetObservable::Magnetization => self.output.magnetization,
+                    TargetObservable::Susceptibility => self.output.susceptibility,
+                    TargetObservable::MagnetizationAbs => self.output.magnetization_abs,
+                    TargetObservable::SusceptibilityAbs => self.output.susceptibility_abs,
+                    TargetObservable::BinderCumulant => self.output.binder_cumulant,
+                };
+                if !enabled {
+                    anyhow::bail!(
+                        "target observable {observable:?} must also be enabled in [output]"
+                    );
+                }
+            }
+        }
+        if let Some(reweighting) = &mut self.reweighting {
+            reweighting.validate()?;
+        }
+        let scales = self
+            .parallel_tempering
+            .as_ref()
+            .map_or(1, |pt| pt.anisotropy_scales.len().max(1));
+        if self.simulation.pt_interval > 0 && self.simulation.temperatures.len() * scales < 2 {
+            anyhow::bail!(
+                "parallel tempering requires at least two temperatures or anisotropy scales when pt_interval is greater than zero"
+            );
+        }
+        if self.simulation.replicas_per_temperature > 1 && self.simulation.pt_interval > 0 {
+            anyhow::bail!(
+                "replicas_per_temperature greater than one requires pt_interval = 0, replicas are independent chains"
+            );
+        }
+        if let Some(parallel_tempering) = &self.parallel_tempering {
+            parallel_tempering.validate()?;
+            if self.simulation.pt_interval == 0 {
+                anyhow::bail!("parallel_tempering requires pt_interval greater than zero");
+            }
+            if parallel_tempering.ladder != Ladder::Fixed
+                && !self.simulation.temperatures.is_sorted_by(|a, b| a < b)
+            {
+                anyhow::bail!(
+                    "an adaptive parallel_tempering ladder requires strictly increasing temperatures"
+                );
+            }
+            if !parallel_tempering.anisotropy_scales.is_empty() {
+                if self.anisotropy.is_none() {
+                    anyhow::bail!("anisotropy_scales requires an [anisotropy] section");
+                }
+                if self.simulation.temperatures.contains(&0.0) {
+                    anyhow::bail!("anisotropy_scales requires temperatures greater than zero");
+                }
+            }
+            if scales > 1 {
+                if parallel_tempering.ladder != Ladder::Fixed {
+                    anyhow::bail!(
+                        "an adaptive parallel_tempering ladder requires at most one anisotropy scale"
+                    );
+                }
+                if self.reweighting.is_some()
+                    || self.correlation.is_some()
+                    || self.topological_charge.is_some()
+                {
+                    anyhow::bail!(
+                        "reweighting, correlation and topological_charge write one row per temperature and require at most one anisotropy scale"
+                    );
+                }
+            }
+        }
+        if let Some(time_series) = &self.time_series {
+            time_series.validate()?;
+        }
+        if let Some(correlation) = &self.correlation {
+            correlation.validate()?;
+            if self.structure.is_none() {
+                anyhow::bail!("correlation requires a [structure] section to find distance shells");
+            }
+        }
+        if let Some(structure_factor) = &self.structure_factor {
+            structure_factor.validate()?;
+        }
+        if let Some(topological_charge) = &self.topological_charge {
+            topological_charge.validate()?;
+            if !matches!(self.simulation.model, Model::Heisenberg) {
+                anyhow::bail!("topological_charge requires the heisenberg model");
+            }
+        }
+        if self.output.correlation_length && self.grid.periodic_axes().is_empty() {
+            anyhow::bail!("correlation_length requires a periodic axis with more than one cell");
+        }
+        if (self.output.vortex_density || self.output.helicity_modulus)
+            && !matches!(self.simulation.model, Model::Xy)
+        {
+            anyhow::bail!("vortex_density and helicity_modulus require the xy model");
+        }
+        if self.output.helicity_modulus && self.grid.periodic_axes().is_empty() {
+            anyhow::bail!("helicity_modulus requires a periodic axis with more than one cell");
+        }
+        if (self.output.cluster_size || self.output.improved_estimators)
+            && !matches!(self.simulation.algorithm, Algorithm::Wolff)
+        {
+            anyhow::bail!("cluster_size and improved_estimators require the wolff algorithm");
+        }
+        // Anisotropy makes the cluster flip a Metropolis test, and the
+        // estimators would weight clusters whose flip was rejected.
+        if self.output.improved_estimators && self.anisotropy.is_some() {
+            anyhow::bail!("improved_estimators cannot be used with [anisotropy]");
+        }
         #[cfg(feature = "snapshots")]
         if let Some(snap) = &self.snapshots {
             snap.validate()?;
@@ -114,10 +256,37 @@ impl fmt::Display for Config {
                 write!(f, "  ion{i:<4}| {anisotropy}")?;
             }
         }
+        if !self.order_parameter.is_empty() {
+            writeln!(
+                f,
+                "\nOrder Parameters [ M_w = Σ w_i s_i, w_i = w_sub cos(2π q·R) ]:"
+            )?;
+            for (i, order_parameter) in self.order_parameter.iter().enumerate() {
+                writeln!(f, "  {i}:{order_parameter}")?;
+            }
+        }
         #[cfg(feature = "snapshots")]
         if let Some(snapshots) = &self.snapshots {
             writeln!(f, "{snapshots}")?;
         }
+        if let Some(reweighting) = &self.reweighting {
+            writeln!(f, "{reweighting}")?;
+        }
+        if let Some(parallel_tempering) = &self.parallel_tempering {
+            writeln!(f, "{parallel_tempering}")?;
+        }
+        if let Some(time_series) = &self.time_series {
+            writeln!(f, "{time_series}")?;
+        }
+        if let Some(correlation) = &self.correlation {
+            writeln!(f, "{correlation}")?;
+        }
+        if let Some(structure_factor) = &self.structure_factor {
+            writeln!(f, "{structure_factor}")?;
+        }
+        if let Some(topological_charge) = &self.topological_charge {
+            writeln!(f, "{topological_charge}")?;
+        }
 
         Ok(())
     }
@@ -127,6 +296,8 @@ impl fmt::Display for Config {
 #[serde(rename_all = "snake_case")]
 pub enum Algorithm {
     Metropolis,
+    /// Metropolis updating non-interacting sites of one lattice in parallel.
+    Checkerboard,
     Wolff,
 }
 
@@ -139,6 +310,19 @@ pub enum InitialState {
     Z,
 }
 
+/// Observables whose standard error `measurement_target_error` bounds.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetObservable {
+    Energy,
+    HeatCapacity,
+    Magnetization,
+    Susceptibility,
+    MagnetizationAbs,
+    SusceptibilityAbs,
+    BinderCumulant,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum Model {
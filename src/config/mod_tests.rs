@@ -33,6 +33,38 @@ energy = true
     assert!(config.parsed_anisotropy.is_empty());
 }
 
+#[test]
+fn config_new_reads_seed() {
+    let toml = r#"
+[simulation]
+initial_state = "random"
+model = "ising"
+equilibration_steps = 100
+measurement_steps = 1000
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+seed = 12345
+
+[grid]
+dimensions = [2, 2, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0]]
+strength = 1.0
+
+[output]
+energy = true
+"#;
+    let config = Config::new(toml).unwrap();
+    assert_eq!(config.simulation.seed, 12345);
+}
+
 #[test]
 fn config_new_with_anisotropy() {
     let toml = r#"
@@ -249,3 +281,441 @@ energy = true
     assert!(s.contains("ion0"));
     assert!(s.contains("ion1"));
 }
+
+fn target_error_toml(output: &str) -> String {
+    format!(
+        r#"
+[simulation]
+initial_state = "random"
+model = "ising"
+equilibration_steps = 100
+measurement_steps = 1000
+measurement_target_error = 0.01
+target_observables = ["energy", "heat_capacity"]
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [2, 2, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0]]
+strength = 1.0
+
+[output]
+{output}
+"#
+    )
+}
+
+#[test]
+fn config_new_measurement_target_error_requires_error_bars_and_observables() {
+    let err = Config::new(&target_error_toml("energy = true\nheat_capacity = true"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("error_bars"), "{err}");
+
+    let err = Config::new(&target_error_toml("energy = true\nerror_bars = true"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("HeatCapacity"), "{err}");
+
+    let config = Config::new(&target_error_toml(
+        "energy = true\nheat_capacity = true\nerror_bars = true",
+    ))
+    .unwrap();
+    assert_eq!(config.simulation.measurement_target_error, Some(0.01));
+    assert_eq!(
+        config.simulation.target_observables,
+        vec![TargetObservable::Energy, TargetObservable::HeatCapacity]
+    );
+}
+
+fn correlation_toml(periodic_boundary: &str, extra: &str) -> String {
+    format!(
+        r#"
+[simulation]
+initial_state = "random"
+model = "ising"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = {periodic_boundary}
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0]]
+strength = 1.0
+
+[output]
+energy = true
+correlation_length = true
+
+{extra}
+"#
+    )
+}
+
+#[test]
+fn config_new_correlation_requires_structure_and_periodic_axis() {
+    let err = Config::new(&correlation_toml("[false, false, true]", ""))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("periodic axis"), "{err}");
+
+    let err = Config::new(&correlation_toml("[true, true, false]", "[correlation]"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("[structure]"), "{err}");
+
+    let config = Config::new(&correlation_toml(
+        "[true, true, false]",
+        "[correlation]\nshells = 2\n\n[structure]\ncell = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 10.0]]\npositions = [[0.0, 0.0, 0.0]]",
+    ))
+    .unwrap();
+    assert_eq!(config.correlation.unwrap().shells, 2);
+}
+
+#[test]
+fn config_new_topological_charge_requires_heisenberg() {
+    let toml = |model: &str| {
+        format!(
+            r#"
+[simulation]
+initial_state = "random"
+model = "{model}"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0]]
+strength = 1.0
+
+[output]
+energy = true
+
+[topological_charge]
+"#
+        )
+    };
+    let err = Config::new(&toml("ising")).unwrap_err().to_string();
+    assert!(err.contains("heisenberg"), "{err}");
+
+    let config = Config::new(&toml("heisenberg")).unwrap();
+    assert_eq!(
+        config.topological_charge.unwrap().savefile,
+        "topological_charge.txt"
+    );
+}
+
+#[test]
+fn config_new_bkt_observables_require_xy() {
+    let toml = |model: &str| {
+        format!(
+            r#"
+[simulation]
+initial_state = "x"
+model = "{model}"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0]]
+strength = 1.0
+
+[output]
+vortex_density = true
+helicity_modulus = true
+"#
+        )
+    };
+    let err = Config::new(&toml("heisenberg")).unwrap_err().to_string();
+    assert!(err.contains("xy model"), "{err}");
+
+    let config = Config::new(&toml("xy")).unwrap();
+    assert!(config.output.vortex_density && config.output.helicity_modulus);
+}
+
+#[test]
+fn config_new_cluster_observables_require_wolff() {
+    let toml = |algorithm: &str| {
+        format!(
+            r#"
+[simulation]
+initial_state = "x"
+model = "ising"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "{algorithm}"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0]]
+strength = 1.0
+
+[output]
+cluster_size = true
+improved_estimators = true
+"#
+        )
+    };
+    let err = Config::new(&toml("metropolis")).unwrap_err().to_string();
+    assert!(err.contains("wolff"), "{err}");
+
+    let config = Config::new(&toml("wolff")).unwrap();
+    assert!(config.output.cluster_size && config.output.improved_estimators);
+
+    let anisotropy = "\n[anisotropy]\naxis = [[0.0, 0.0, 1.0]]\nstrength = [0.1]\n";
+    let err = Config::new(&(toml("wolff") + anisotropy))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("[anisotropy]"), "{err}");
+}
+
+#[test]
+fn config_new_parallel_tempering_requires_pt_and_increasing_ladder() {
+    let toml = |pt_interval: usize, temperatures: &str, ladder: &str| {
+        format!(
+            r#"
+[simulation]
+initial_state = "x"
+model = "ising"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = {temperatures}
+num_threads = 1
+pt_interval = {pt_interval}
+algorithm = "metropolis"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0]]
+strength = 1.0
+
+[output]
+energy = true
+
+[parallel_tempering]
+ladder = "{ladder}"
+"#
+        )
+    };
+    let err = Config::new(&toml(0, "[1.0, 2.0]", "fixed"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("pt_interval"), "{err}");
+
+    let err = Config::new(&toml(1, "[2.0, 1.0]", "round_trip"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("increasing"), "{err}");
+
+    // A fixed ladder only reports statistics, in any order.
+    assert!(Config::new(&toml(1, "[2.0, 1.0]", "fixed")).is_ok());
+    let config = Config::new(&toml(1, "[1.0, 2.0]", "acceptance")).unwrap();
+    assert_eq!(
+        config.parallel_tempering.unwrap().ladder,
+        crate::config::Ladder::Acceptance
+    );
+}
+
+#[test]
+fn config_new_anisotropy_scales_need_anisotropy_and_a_fixed_ladder() {
+    let toml = |temperatures: &str, anisotropy: &str, tempering: &str| {
+        format!(
+            r#"
+[simulation]
+initial_state = "random"
+model = "heisenberg"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = {temperatures}
+num_threads = 1
+pt_interval = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0]]
+strength = 1.0
+{anisotropy}
+[output]
+energy = true
+
+[parallel_tempering]
+{tempering}
+"#
+        )
+    };
+    let anisotropy = "\n[anisotropy]\naxis = [[0.0, 0.0, 1.0]]\nstrength = [1.0]\n";
+    let scales = "anisotropy_scales = [0.5, 1.0]";
+
+    // Two scales are enough to temper at a single temperature.
+    let config = Config::new(&toml("[1.0]", anisotropy, scales)).unwrap();
+    assert_eq!(
+        config.parallel_tempering.unwrap().anisotropy_scales,
+        [0.5, 1.0]
+    );
+    let err = Config::new(&toml("[1.0]", anisotropy, "anisotropy_scales = [1.0]"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("at least two"), "{err}");
+
+    let err = Config::new(&toml("[1.0]", "", scales))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("[anisotropy]"), "{err}");
+
+    let err = Config::new(&toml("[0.0, 1.0]", anisotropy, scales))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("greater than zero"), "{err}");
+
+    let err = Config::new(&toml(
+        "[1.0, 2.0]",
+        anisotropy,
+        &format!("{scales}\nladder = \"acceptance\""),
+    ))
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("at most one anisotropy scale"), "{err}");
+}
+
+#[test]
+fn config_new_replicas_per_temperature_require_independent_runs() {
+    let toml = |pt_interval: usize| {
+        format!(
+            r#"
+[simulation]
+initial_state = "random"
+model = "ising"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0, 2.0]
+num_threads = 1
+pt_interval = {pt_interval}
+replicas_per_temperature = 4
+algorithm = "metropolis"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0]]
+strength = 1.0
+
+[output]
+energy = true
+"#
+        )
+    };
+    let config = Config::new(&toml(0)).unwrap();
+    assert_eq!(config.simulation.replicas_per_temperature, 4);
+    let err = Config::new(&toml(1)).unwrap_err().to_string();
+    assert!(err.contains("replicas_per_temperature"), "{err}");
+}
+
+#[test]
+fn config_new_order_parameter_weights_match_sublattices() {
+    let toml = |weights: &str| {
+        format!(
+            r#"
+[simulation]
+initial_state = "random"
+model = "ising"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [2, 2, 1]
+sublattices = 2
+spin_magnitudes = [1.0, 1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 1
+offsets = [[0, 0, 0]]
+strength = 1.0
+
+[output]
+energy = true
+
+[[order_parameter]]
+weights = {weights}
+"#
+        )
+    };
+    assert!(Config::new(&toml("[1.0]")).is_err());
+    let config = Config::new(&toml("[1.0, -1.0]")).unwrap();
+    assert_eq!(config.order_parameter.len(), 1);
+    assert_eq!(config.order_parameter[0].wavevector, [0.0; 3]);
+    assert!(format!("{config}").contains("Order Parameters"));
+}
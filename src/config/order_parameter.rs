@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A weighted magnetization M_w = Σ_i w_i s_i with
+/// w_i = weights[sublattice] · cos(2π q · R), R being the cell coordinates of
+/// site i. Weights of ±1 give Néel order; a wavevector such as [0.5, 0, 0]
+/// gives stripe or zigzag patterns.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct OrderParameter {
+    pub weights: Vec<f64>,
+    /// Modulation wavevector in reciprocal cell units.
+    #[serde(default)]
+    pub wavevector: [f64; 3],
+}
+
+impl OrderParameter {
+    pub fn validate(&self, sublattices: usize) -> anyhow::Result<()> {
+        if self.weights.len() != sublattices {
+            anyhow::bail!(
+                "order_parameter weights length ({}) does not match sublattices ({sublattices})",
+                self.weights.len()
+            );
+        }
+        if self.weights.iter().any(|w| !w.is_finite()) {
+            anyhow::bail!("order_parameter weights must be finite");
+        }
+        if self.weights.iter().all(|w| *w == 0.0) {
+            anyhow::bail!("order_parameter weights must not all be zero");
+        }
+        if self.wavevector.iter().any(|q| !q.is_finite()) {
+            anyhow::bail!("order_parameter wavevector must be finite");
+        }
+        Ok(())
+    }
+
+    /// Weight of the site on `sublattice` in cell `coord`.
+    pub fn weight(&self, sublattice: usize, coord: [usize; 3]) -> f64 {
+        let phase: f64 = (0..3).map(|k| self.wavevector[k] * coord[k] as f64).sum();
+        self.weights[sublattice] * (2.0 * std::f64::consts::PI * phase).cos()
+    }
+}
+
+impl fmt::Display for OrderParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "  Weights: {:?}, Wavevector: {:?}",
+            self.weights, self.wavevector
+        )
+    }
+}
+
+#[cfg(test)]
+#[path = "order_parameter_tests.rs"]
+mod tests;
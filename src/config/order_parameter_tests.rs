@@ -0,0 +1,63 @@
+use super::*;
+
+fn order_parameter(weights: Vec<f64>, wavevector: [f64; 3]) -> OrderParameter {
+    OrderParameter {
+        weights,
+        wavevector,
+    }
+}
+
+#[test]
+fn validate_ok() {
+    assert!(
+        order_parameter(vec![1.0, -1.0], [0.0; 3])
+            .validate(2)
+            .is_ok()
+    );
+}
+
+#[test]
+fn validate_weights_length_mismatch_errors() {
+    let err = order_parameter(vec![1.0], [0.0; 3])
+        .validate(2)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("does not match"), "{err}");
+}
+
+#[test]
+fn validate_all_zero_weights_errors() {
+    assert!(
+        order_parameter(vec![0.0, 0.0], [0.0; 3])
+            .validate(2)
+            .is_err()
+    );
+}
+
+#[test]
+fn validate_non_finite_errors() {
+    assert!(
+        order_parameter(vec![f64::NAN], [0.0; 3])
+            .validate(1)
+            .is_err()
+    );
+    assert!(
+        order_parameter(vec![1.0], [f64::INFINITY, 0.0, 0.0])
+            .validate(1)
+            .is_err()
+    );
+}
+
+#[test]
+fn weight_alternates_with_half_wavevector() {
+    let stripe = order_parameter(vec![2.0], [0.5, 0.0, 0.0]);
+    assert!((stripe.weight(0, [0, 3, 1]) - 2.0).abs() < 1e-12);
+    assert!((stripe.weight(0, [1, 3, 1]) + 2.0).abs() < 1e-12);
+    assert!((stripe.weight(0, [2, 0, 0]) - 2.0).abs() < 1e-12);
+}
+
+#[test]
+fn display_lists_weights() {
+    let s = format!("{}", order_parameter(vec![1.0, -1.0], [0.0; 3]));
+    assert!(s.contains("[1.0, -1.0]"));
+}
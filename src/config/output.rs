@@ -11,6 +11,10 @@ pub struct Output {
     #[serde(default = "default_false")]
     pub heat_capacity: bool,
     #[serde(default = "default_false")]
+    pub energy_terms: bool,
+    #[serde(default = "default_false")]
+    pub energy_by_exchange: bool,
+    #[serde(default = "default_false")]
     pub magnetization: bool,
     #[serde(default = "default_false")]
     pub susceptibility: bool,
@@ -26,6 +30,20 @@ pub struct Output {
     pub group_magnetization_abs: bool,
     #[serde(default = "default_false")]
     pub group_susceptibility_abs: bool,
+    #[serde(default = "default_false")]
+    pub binder_cumulant: bool,
+    #[serde(default = "default_false")]
+    pub group_binder_cumulant: bool,
+    #[serde(default = "default_false")]
+    pub correlation_length: bool,
+    #[serde(default = "default_false")]
+    pub vortex_density: bool,
+    #[serde(default = "default_false")]
+    pub helicity_modulus: bool,
+    #[serde(default = "default_false")]
+    pub cluster_size: bool,
+    #[serde(default = "default_false")]
+    pub improved_estimators: bool,
     #[serde(default)]
     pub group: Vec<Vec<usize>>,
     #[serde(default = "default_stats_interval")]
@@ -34,6 +52,12 @@ pub struct Output {
     pub progress_bar: bool,
     #[serde(default)]
     pub progress_log_interval: usize,
+    #[serde(default = "default_false")]
+    pub error_bars: bool,
+    #[serde(default = "default_error_blocks")]
+    pub error_blocks: usize,
+    #[serde(default = "default_false")]
+    pub autocorrelation: bool,
 }
 
 fn default_false() -> bool {
@@ -52,11 +76,37 @@ fn default_stats_interval() -> usize {
     1
 }
 
+fn default_error_blocks() -> usize {
+    32
+}
+
 impl Output {
     pub fn validate(&self, sublattices: usize) -> anyhow::Result<()> {
-        if let (false, false, false, false, false, false, false, false, false, false) = (
+        if let (
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+        ) = (
             self.energy,
             self.heat_capacity,
+            self.energy_terms,
+            self.energy_by_exchange,
             self.magnetization,
             self.susceptibility,
             self.magnetization_abs,
@@ -65,6 +115,13 @@ impl Output {
             self.group_susceptibility,
             self.group_magnetization_abs,
             self.group_susceptibility_abs,
+            self.binder_cumulant,
+            self.group_binder_cumulant,
+            self.correlation_length,
+            self.vortex_density,
+            self.helicity_modulus,
+            self.cluster_size,
+            self.improved_estimators,
         ) {
             anyhow::bail!("No output fields specified: Please enable at least one observable.")
         }
@@ -77,10 +134,18 @@ impl Output {
             anyhow::bail!("stats_interval must be greater than zero");
         }
 
+        if self.error_bars && self.error_blocks < 2 {
+            anyhow::bail!(
+                "error_blocks ({}) must be at least 2 when error_bars is enabled",
+                self.error_blocks
+            );
+        }
+
         let group_observable_enabled = self.group_magnetization
             || self.group_susceptibility
             || self.group_magnetization_abs
-            || self.group_susceptibility_abs;
+            || self.group_susceptibility_abs
+            || self.group_binder_cumulant;
 
         if group_observable_enabled && self.group.is_empty() {
             anyhow::bail!("group output requires at least one group");
@@ -110,6 +175,21 @@ impl fmt::Display for Output {
         writeln!(f, "  stats_interval: {}", self.stats_interval)?;
         writeln!(f, "  Progress Bar: {}", self.progress_bar)?;
         writeln!(f, "  Progress Log Interval: {}", self.progress_log_interval)?;
+        if self.error_bars {
+            writeln!(
+                f,
+                "  Error Bars: binning + jackknife, {}-{} blocks",
+                self.error_blocks,
+                2 * self.error_blocks
+            )?;
+        } else {
+            writeln!(f, "  Error Bars: false")?;
+        }
+        writeln!(
+            f,
+            "  Autocorrelation Times [τ_E, τ_|M|, in measurements]: {}",
+            self.autocorrelation
+        )?;
 
         writeln!(f, "  Energy [E = <H> / N ]: {}", self.energy)?;
         writeln!(
@@ -117,6 +197,16 @@ impl fmt::Display for Output {
             "  Heat Capacity [ C = (⟨E²⟩ - ⟨E⟩²) / (N kB T²) ]: {}",
             self.heat_capacity
         )?;
+        writeln!(
+            f,
+            "  Energy Terms [ E_ex, E_ani = <H_term> / N ]: {}",
+            self.energy_terms
+        )?;
+        writeln!(
+            f,
+            "  Energy per [[exchange]] Entry [ E_J = <H_J> / N ]: {}",
+            self.energy_by_exchange
+        )?;
         writeln!(
             f,
             "  Magnetization [ M = ⟨Σ s_i⟩ / N ] : {}",
@@ -137,6 +227,36 @@ impl fmt::Display for Output {
             "  susceptibility_abs [  χ(|M|) = (⟨|M|²⟩ - ⟨|M|⟩²) / (N kB T) ]: {}",
             self.susceptibility_abs
         )?;
+        writeln!(
+            f,
+            "  Binder Cumulant [ U4 = 1 - n⟨M⁴⟩ / ((n+2)⟨M²⟩²) ]: {}",
+            self.binder_cumulant
+        )?;
+        writeln!(
+            f,
+            "  Correlation Length [ ξ = √(S(0)/S(q_min) - 1) / (2 sin(π/L)) ]: {}",
+            self.correlation_length
+        )?;
+        writeln!(
+            f,
+            "  Vortex Density [ ρ_v = ⟨Σ_p |w_p|⟩ / N_p ]: {}",
+            self.vortex_density
+        )?;
+        writeln!(
+            f,
+            "  Helicity Modulus [ Υ = (⟨∂²E/∂φ²⟩ - β⟨(∂E/∂φ)²⟩) / N ]: {}",
+            self.helicity_modulus
+        )?;
+        writeln!(
+            f,
+            "  Mean Cluster Size [ ⟨|C|⟩, in sites ]: {}",
+            self.cluster_size
+        )?;
+        writeln!(
+            f,
+            "  Improved Estimators [ χ_C = n⟨(Σ_C S·r)² / |C|⟩ / k_BT, ξ_C ]: {}",
+            self.improved_estimators
+        )?;
         writeln!(f, "  Group Magnetization: {}", self.group_magnetization)?;
         writeln!(f, "  Group Susceptibility: {}", self.group_susceptibility)?;
         writeln!(
@@ -149,10 +269,12 @@ impl fmt::Display for Output {
             "  Group |Susceptibility|: {}",
             self.group_susceptibility_abs
         )?;
+        writeln!(f, "  Group Binder Cumulant: {}", self.group_binder_cumulant)?;
         if self.group_magnetization
             || self.group_susceptibility
             || self.group_magnetization_abs
             || self.group_susceptibility_abs
+            || self.group_binder_cumulant
         {
             writeln!(f, "  Groups:")?;
             for (i, group) in self.group.iter().enumerate() {
@@ -5,6 +5,8 @@ fn output_with_energy() -> Output {
         savefile: "result.txt".into(),
         energy: true,
         heat_capacity: false,
+        energy_terms: false,
+        energy_by_exchange: false,
         magnetization: false,
         susceptibility: false,
         magnetization_abs: false,
@@ -13,10 +15,20 @@ fn output_with_energy() -> Output {
         group_susceptibility: false,
         group_magnetization_abs: false,
         group_susceptibility_abs: false,
+        binder_cumulant: false,
+        group_binder_cumulant: false,
+        correlation_length: false,
+        vortex_density: false,
+        helicity_modulus: false,
+        cluster_size: false,
+        improved_estimators: false,
         group: vec![],
         stats_interval: 1,
         progress_bar: true,
         progress_log_interval: 0,
+        error_bars: false,
+        error_blocks: 32,
+        autocorrelation: false,
     }
 }
 
@@ -52,6 +64,17 @@ fn validate_zero_stats_interval_errors() {
     assert!(err.contains("greater than zero"));
 }
 
+#[test]
+fn validate_error_bars_require_two_blocks() {
+    let mut o = output_with_energy();
+    o.error_bars = true;
+    o.error_blocks = 1;
+    let err = o.validate(1).unwrap_err().to_string();
+    assert!(err.contains("error_blocks"));
+    o.error_blocks = 2;
+    assert!(o.validate(1).is_ok());
+}
+
 #[test]
 fn validate_group_observable_requires_groups() {
     let mut o = output_with_energy();
@@ -94,6 +117,8 @@ fn display() {
         savefile: "out.txt".into(),
         energy: true,
         heat_capacity: false,
+        energy_terms: false,
+        energy_by_exchange: false,
         magnetization: false,
         susceptibility: false,
         magnetization_abs: false,
@@ -102,10 +127,20 @@ fn display() {
         group_susceptibility: false,
         group_magnetization_abs: false,
         group_susceptibility_abs: false,
+        binder_cumulant: false,
+        group_binder_cumulant: false,
+        correlation_length: false,
+        vortex_density: false,
+        helicity_modulus: false,
+        cluster_size: false,
+        improved_estimators: false,
         group: vec![],
         stats_interval: 1,
         progress_bar: true,
         progress_log_interval: 0,
+        error_bars: false,
+        error_blocks: 32,
+        autocorrelation: false,
     };
     let s = format!("{o}");
     assert!(s.contains("out.txt"));
@@ -118,6 +153,8 @@ fn display_all_observables_and_groups() {
         savefile: "out.txt".into(),
         energy: true,
         heat_capacity: true,
+        energy_terms: false,
+        energy_by_exchange: false,
         magnetization: true,
         susceptibility: true,
         magnetization_abs: true,
@@ -126,10 +163,20 @@ fn display_all_observables_and_groups() {
         group_susceptibility: true,
         group_magnetization_abs: true,
         group_susceptibility_abs: true,
+        binder_cumulant: true,
+        group_binder_cumulant: true,
+        correlation_length: false,
+        vortex_density: false,
+        helicity_modulus: false,
+        cluster_size: false,
+        improved_estimators: false,
         group: vec![vec![0], vec![1, 2]],
         stats_interval: 3,
         progress_bar: false,
         progress_log_interval: 7,
+        error_bars: true,
+        error_blocks: 16,
+        autocorrelation: true,
     };
     let s = format!("{o}");
     for expected in [
@@ -142,6 +189,8 @@ fn display_all_observables_and_groups() {
         "Group Susceptibility",
         "Group |Magnetization|",
         "Group |Susceptibility|",
+        "16-32 blocks",
+        "Autocorrelation Times",
         "Group 0",
         "Group 1",
     ] {
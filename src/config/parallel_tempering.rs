@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ParallelTempering {
+    /// How the temperatures between the two fixed ends are tuned during
+    /// equilibration.
+    #[serde(default)]
+    pub ladder: Ladder,
+    /// Sweeps of swap statistics behind each ladder update.
+    #[serde(default = "default_adapt_interval")]
+    pub adapt_interval: usize,
+    #[serde(default = "default_savefile")]
+    pub savefile: String,
+    /// Factors on every anisotropy strength, a second replica-exchange
+    /// dimension: each temperature is simulated at each scale.
+    #[serde(default)]
+    pub anisotropy_scales: Vec<f64>,
+}
+
+/// Feedback-optimised ladders follow Katzgraber et al., J. Stat. Mech. (2006) P03018.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Ladder {
+    #[default]
+    Fixed,
+    /// Equal swap acceptance between every neighbouring pair.
+    Acceptance,
+    /// Maximal round trips between the lowest and highest temperature.
+    RoundTrip,
+}
+
+fn default_adapt_interval() -> usize {
+    1000
+}
+
+fn default_savefile() -> String {
+    "parallel_tempering.txt".to_string()
+}
+
+impl ParallelTempering {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.adapt_interval == 0 {
+            anyhow::bail!("parallel_tempering adapt_interval must be greater than zero");
+        }
+        if self.savefile.trim().is_empty() {
+            anyhow::bail!("parallel_tempering savefile must not be empty");
+        }
+        for (index, scale) in self.anisotropy_scales.iter().enumerate() {
+            if !scale.is_finite() {
+                anyhow::bail!("anisotropy_scales[{index}] ({scale}) must be finite");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ParallelTempering {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\nParallel Tempering Statistics: Enable")?;
+        match self.ladder {
+            Ladder::Fixed => writeln!(f, "  Ladder: fixed")?,
+            Ladder::Acceptance => writeln!(
+                f,
+                "  Ladder: equal acceptance, updated every {} sweeps of equilibration",
+                self.adapt_interval
+            )?,
+            Ladder::RoundTrip => writeln!(
+                f,
+                "  Ladder: maximal round trips, updated every {} sweeps of equilibration",
+                self.adapt_interval
+            )?,
+        }
+        if !self.anisotropy_scales.is_empty() {
+            write!(f, "  Anisotropy Scales:\n  ")?;
+            for scale in &self.anisotropy_scales {
+                write!(f, "{scale:.4}   ")?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "  Savefile: {}", self.savefile)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "parallel_tempering_tests.rs"]
+mod tests;
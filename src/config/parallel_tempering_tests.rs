@@ -0,0 +1,48 @@
+use super::*;
+
+#[test]
+fn defaults_keep_the_ladder_fixed() {
+    let parallel_tempering: ParallelTempering = toml::from_str("").unwrap();
+    assert_eq!(parallel_tempering.ladder, Ladder::Fixed);
+    assert_eq!(parallel_tempering.adapt_interval, 1000);
+    assert_eq!(parallel_tempering.savefile, "parallel_tempering.txt");
+    assert!(parallel_tempering.anisotropy_scales.is_empty());
+    assert!(parallel_tempering.validate().is_ok());
+}
+
+#[test]
+fn ladder_names_parse() {
+    let parallel_tempering: ParallelTempering = toml::from_str(r#"ladder = "round_trip""#).unwrap();
+    assert_eq!(parallel_tempering.ladder, Ladder::RoundTrip);
+    let parallel_tempering: ParallelTempering = toml::from_str(r#"ladder = "acceptance""#).unwrap();
+    assert_eq!(parallel_tempering.ladder, Ladder::Acceptance);
+    assert!(toml::from_str::<ParallelTempering>(r#"ladder = "optimal""#).is_err());
+}
+
+#[test]
+fn validate_rejects_zero_interval_and_empty_savefile() {
+    let parallel_tempering = ParallelTempering {
+        ladder: Ladder::Acceptance,
+        adapt_interval: 0,
+        savefile: "parallel_tempering.txt".into(),
+        anisotropy_scales: vec![],
+    };
+    assert!(parallel_tempering.validate().is_err());
+
+    let parallel_tempering = ParallelTempering {
+        ladder: Ladder::Fixed,
+        adapt_interval: 100,
+        savefile: " ".into(),
+        anisotropy_scales: vec![],
+    };
+    assert!(parallel_tempering.validate().is_err());
+
+    let parallel_tempering = ParallelTempering {
+        ladder: Ladder::Fixed,
+        adapt_interval: 100,
+        savefile: "parallel_tempering.txt".into(),
+        anisotropy_scales: vec![1.0, f64::NAN],
+    };
+    let err = parallel_tempering.validate().unwrap_err().to_string();
+    assert!(err.contains("anisotropy_scales[1]"), "{err}");
+}
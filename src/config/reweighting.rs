@@ -0,0 +1,114 @@
+use super::simulation::TemperatureRange;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Reweighting {
+    #[serde(default)]
+    pub temperatures: Vec<f64>,
+
+    #[serde(default)]
+    pub temperature_range: Vec<TemperatureRange>,
+
+    #[serde(default = "default_method")]
+    pub method: ReweightingMethod,
+
+    /// Number of energy bins used to solve the multi-histogram equations.
+    #[serde(default = "default_energy_bins")]
+    pub energy_bins: usize,
+
+    #[serde(default = "default_savefile")]
+    pub savefile: String,
+
+    /// Directory receiving the raw (E, |M|) series behind the reweighting,
+    /// one `T_<T>.csv` per temperature. Not written when unset.
+    #[serde(default)]
+    pub samples_directory: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReweightingMethod {
+    /// Ferrenberg–Swendsen: reweight the nearest simulated temperature only.
+    SingleHistogram,
+    /// WHAM: combine the histograms of every simulated temperature.
+    MultiHistogram,
+}
+
+fn default_method() -> ReweightingMethod {
+    ReweightingMethod::MultiHistogram
+}
+fn default_energy_bins() -> usize {
+    1000
+}
+fn default_savefile() -> String {
+    "reweighted.txt".to_string()
+}
+
+impl Reweighting {
+    pub fn validate(&mut self) -> anyhow::Result<()> {
+        if self.energy_bins == 0 {
+            anyhow::bail!("reweighting energy_bins must be greater than zero");
+        }
+        if self
+            .samples_directory
+            .as_ref()
+            .is_some_and(|dir| dir.trim().is_empty())
+        {
+            anyhow::bail!("reweighting samples_directory must not be empty");
+        }
+        match (
+            self.temperatures.is_empty(),
+            self.temperature_range.is_empty(),
+        ) {
+            (true, true) => {
+                anyhow::bail!(
+                    "reweighting requires either 'temperatures' or 'temperature_range' to be specified"
+                );
+            }
+            (false, false) => {
+                anyhow::bail!(
+                    "Only one of 'temperatures' or 'temperature_range' can be specified for reweighting, not both"
+                )
+            }
+            (true, false) => {
+                for tem_range in &self.temperature_range {
+                    self.temperatures.extend(tem_range.expand()?);
+                }
+            }
+            (false, true) => {}
+        }
+        for (index, temperature) in self.temperatures.iter().enumerate() {
+            if !temperature.is_finite() || *temperature <= 0.0 {
+                anyhow::bail!(
+                    "reweighting temperatures[{index}] ({temperature}) must be finite and positive"
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Reweighting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\nReweighting:")?;
+        writeln!(f, "  Method: {:?}", self.method)?;
+        if self.method == ReweightingMethod::MultiHistogram {
+            writeln!(f, "  Energy Bins: {}", self.energy_bins)?;
+        }
+        writeln!(f, "  Savefile: {}", self.savefile)?;
+        if let Some(dir) = &self.samples_directory {
+            writeln!(f, "  Samples Directory: {dir}")?;
+        }
+        write!(f, "  Temperatures (K):\n  ")?;
+        for t in &self.temperatures {
+            write!(f, "{t:.4}   ")?;
+        }
+        writeln!(f)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "reweighting_tests.rs"]
+mod tests;
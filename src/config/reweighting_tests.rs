@@ -0,0 +1,80 @@
+use super::*;
+
+fn reweighting(temperatures: Vec<f64>, temperature_range: Vec<TemperatureRange>) -> Reweighting {
+    Reweighting {
+        temperatures,
+        temperature_range,
+        method: ReweightingMethod::MultiHistogram,
+        energy_bins: 100,
+        savefile: "reweighted.txt".into(),
+        samples_directory: None,
+    }
+}
+
+#[test]
+fn validate_expands_temperature_range() {
+    let mut r = reweighting(
+        vec![],
+        vec![TemperatureRange {
+            start: 1.0,
+            end: 2.0,
+            step: 0.5,
+        }],
+    );
+    r.validate().unwrap();
+    assert_eq!(r.temperatures, vec![1.0, 1.5, 2.0]);
+}
+
+#[test]
+fn validate_requires_exactly_one_temperature_source() {
+    let mut none = reweighting(vec![], vec![]);
+    assert!(none.validate().is_err());
+
+    let mut both = reweighting(
+        vec![1.0],
+        vec![TemperatureRange {
+            start: 1.0,
+            end: 2.0,
+            step: 0.5,
+        }],
+    );
+    assert!(both.validate().is_err());
+}
+
+#[test]
+fn validate_rejects_non_positive_temperatures() {
+    for t in [0.0, -1.0, f64::NAN] {
+        let mut r = reweighting(vec![1.0, t], vec![]);
+        let err = r.validate().unwrap_err().to_string();
+        assert!(err.contains("temperatures[1]"), "{err}");
+    }
+}
+
+#[test]
+fn validate_rejects_zero_energy_bins() {
+    let mut r = reweighting(vec![1.0], vec![]);
+    r.energy_bins = 0;
+    assert!(r.validate().is_err());
+}
+
+#[test]
+fn method_parses_snake_case() {
+    let r: Reweighting = toml::from_str(
+        r#"
+temperatures = [1.0]
+method = "single_histogram"
+"#,
+    )
+    .unwrap();
+    assert_eq!(r.method, ReweightingMethod::SingleHistogram);
+    assert_eq!(r.energy_bins, 1000);
+    assert_eq!(r.savefile, "reweighted.txt");
+}
+
+#[test]
+fn validate_rejects_empty_samples_directory() {
+    let mut r = reweighting(vec![1.0], vec![]);
+    r.samples_directory = Some(" ".into());
+    let err = r.validate().unwrap_err().to_string();
+    assert!(err.contains("samples_directory"), "{err}");
+}
@@ -1,4 +1,5 @@
-use super::{Algorithm, InitialState, Model};
+use super::{Algorithm, InitialState, Model, TargetObservable};
+use rand::RngExt;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -9,6 +10,19 @@ pub struct Simulation {
     pub equilibration_steps: usize,
     pub measurement_steps: usize,
 
+    /// Switch to measurement once E and |M| are stationary; `equilibration_steps`
+    /// is then the maximum number of equilibration sweeps.
+    #[serde(default)]
+    pub auto_equilibration: bool,
+
+    /// Keep measuring until the standard error of every `target_observables`
+    /// entry is at most this; `measurement_steps` is then the maximum.
+    #[serde(default)]
+    pub measurement_target_error: Option<f64>,
+
+    #[serde(default = "default_target_observables")]
+    pub target_observables: Vec<TargetObservable>,
+
     #[serde(default)]
     pub temperatures: Vec<f64>,
 
@@ -20,13 +34,32 @@ pub struct Simulation {
     #[serde(default = "default_pt_interval")]
     pub pt_interval: usize,
 
+    /// Independent chains per temperature, each with its own RNG streams.
+    /// Their results are averaged, with errors from the replica scatter.
+    #[serde(default = "default_replicas_per_temperature")]
+    pub replicas_per_temperature: usize,
+
     pub algorithm: Algorithm,
     #[serde(default = "default_boltzmann_constant")]
     pub boltzmann_constant: f64,
+
+    /// Master seed from which every RNG stream of the run is derived.
+    /// Drawn from the OS when not given, and recorded in the output file.
+    #[serde(default = "default_seed")]
+    pub seed: u64,
 }
 fn default_pt_interval() -> usize {
     0
 }
+fn default_replicas_per_temperature() -> usize {
+    1
+}
+fn default_target_observables() -> Vec<TargetObservable> {
+    vec![TargetObservable::Energy]
+}
+fn default_seed() -> u64 {
+    rand::rng().random()
+}
 fn default_boltzmann_constant() -> f64 {
     8.617333262145e-5 // eV/K
 }
@@ -38,6 +71,40 @@ pub struct TemperatureRange {
     pub step: f64,
 }
 
+impl TemperatureRange {
+    /// Validate the range and list its temperatures, `end` included.
+    pub fn expand(&self) -> anyhow::Result<Vec<f64>> {
+        let (start, end, step) = (self.start, self.end, self.step);
+        if !start.is_finite() {
+            anyhow::bail!("temperature_range start ({start}) must be finite");
+        }
+        if !end.is_finite() {
+            anyhow::bail!("temperature_range end ({end}) must be finite");
+        }
+        if !step.is_finite() {
+            anyhow::bail!("temperature_range step ({step}) must be finite");
+        }
+        if start < 0.0 {
+            anyhow::bail!("temperature_range start ({start}) must be non-negative");
+        }
+        if step <= 0.0 {
+            anyhow::bail!("temperature_range step ({step}) must be positive");
+        }
+        if start > end {
+            anyhow::bail!(
+                "temperature_range start ({start}) must be less than or equal to end ({end})"
+            );
+        }
+        let mut temperatures = Vec::new();
+        let mut t = start;
+        while t <= end + 1e-8 {
+            temperatures.push(t);
+            t += step;
+        }
+        Ok(temperatures)
+    }
+}
+
 impl Simulation {
     pub fn validate(&mut self) -> anyhow::Result<()> {
         if self
@@ -50,9 +117,29 @@ impl Simulation {
         if self.measurement_steps == 0 {
             anyhow::bail!("measurement_steps must be greater than zero");
         }
+        if let Some(target) = self.measurement_target_error {
+            if !target.is_finite() || target <= 0.0 {
+                anyhow::bail!(
+                    "measurement_target_error ({target}) must be finite and greater than zero"
+                );
+            }
+            if self.target_observables.is_empty() {
+                anyhow::bail!(
+                    "target_observables must not be empty when measurement_target_error is set"
+                );
+            }
+        }
+        if self.auto_equilibration && self.equilibration_steps == 0 {
+            anyhow::bail!(
+                "equilibration_steps must be greater than zero when auto_equilibration is enabled, it caps the equilibration length"
+            );
+        }
         if self.num_threads == 0 {
             anyhow::bail!("num_threads must be greater than zero");
         }
+        if self.replicas_per_temperature == 0 {
+            anyhow::bail!("replicas_per_temperature must be greater than zero");
+        }
         if !self.boltzmann_constant.is_finite() || self.boltzmann_constant <= 0.0 {
             anyhow::bail!(
                 "boltzmann_constant ({}) must be finite and greater than zero",
@@ -74,37 +161,7 @@ impl Simulation {
             }
             (true, false) => {
                 for tem_range in &self.temperature_range {
-                    let (start, end, step) = (tem_range.start, tem_range.end, tem_range.step);
-                    if !start.is_finite() {
-                        anyhow::bail!("temperature_range start ({start}) must be finite");
-                    }
-                    if !end.is_finite() {
-                        anyhow::bail!("temperature_range end ({end}) must be finite");
-                    }
-                    if !step.is_finite() {
-                        anyhow::bail!("temperature_range step ({step}) must be finite");
-                    }
-                    if start < 0.0 {
-                        anyhow::bail!("temperature_range start ({start}) must be non-negative");
-                    }
-                    if step <= 0.0 {
-                        anyhow::bail!("temperature_range step ({step}) must be positive");
-                    }
-                    if start > end {
-                        anyhow::bail!(
-                            "temperature_range start ({start}) must be less than or equal to end ({end})"
-                        );
-                    }
-                    let mut t = start;
-                    while t <= end + 1e-8 {
-                        self.temperatures.push(t);
-                        t += step;
-                    }
-                }
-                if self.pt_interval > 0 && self.temperatures.len() < 2 {
-                    anyhow::bail!(
-                        "parallel tempering requires at least two temperatures when pt_interval is greater than zero"
-                    );
+                    self.temperatures.extend(tem_range.expand()?);
                 }
                 Ok(())
             }
@@ -116,11 +173,6 @@ impl Simulation {
                         );
                     }
                 }
-                if self.pt_interval > 0 && self.temperatures.len() < 2 {
-                    anyhow::bail!(
-                        "parallel tempering requires at least two temperatures when pt_interval is greater than zero"
-                    );
-                }
                 Ok(())
             }
         }
@@ -132,15 +184,39 @@ impl fmt::Display for Simulation {
         writeln!(f, "\nSimulation Parameters:")?;
         writeln!(f, "  Initial State: {:?}", self.initial_state)?;
         writeln!(f, "  Model: {:?}", self.model)?;
-        writeln!(f, "  Equilibration Steps: {}", self.equilibration_steps)?;
-        writeln!(f, "  Simulation Steps: {}", self.measurement_steps)?;
+        if self.auto_equilibration {
+            writeln!(
+                f,
+                "  Equilibration Steps: automatic, at most {}",
+                self.equilibration_steps
+            )?;
+        } else {
+            writeln!(f, "  Equilibration Steps: {}", self.equilibration_steps)?;
+        }
+        if let Some(target) = self.measurement_target_error {
+            writeln!(
+                f,
+                "  Simulation Steps: until error of {:?} <= {target}, at most {}",
+                self.target_observables, self.measurement_steps
+            )?;
+        } else {
+            writeln!(f, "  Simulation Steps: {}", self.measurement_steps)?;
+        }
         writeln!(
             f,
             "  Boltzmann Constant (kB): {} (eV/K)",
             self.boltzmann_constant
         )?;
         writeln!(f, "  Algorithm: {:?}", self.algorithm)?;
+        writeln!(f, "  Seed: {}", self.seed)?;
         writeln!(f, "  Threads: {}", self.num_threads)?;
+        if self.replicas_per_temperature > 1 {
+            writeln!(
+                f,
+                "  Replicas per Temperature: {}",
+                self.replicas_per_temperature
+            )?;
+        }
         if self.pt_interval > 0 {
             writeln!(
                 f,
@@ -6,12 +6,17 @@ fn simulation_with_temperature_range(start: f64, end: f64, step: f64) -> Simulat
         model: Model::Ising,
         equilibration_steps: 100,
         measurement_steps: 1000,
+        auto_equilibration: false,
+        measurement_target_error: None,
+        target_observables: vec![TargetObservable::Energy],
         temperatures: vec![],
         temperature_range: vec![TemperatureRange { start, end, step }],
         num_threads: 1,
         pt_interval: 0,
+        replicas_per_temperature: 1,
         algorithm: Algorithm::Metropolis,
         boltzmann_constant: 1.0,
+        seed: 0,
     }
 }
 
@@ -21,12 +26,17 @@ fn simulation_with_temperatures(temperatures: Vec<f64>) -> Simulation {
         model: Model::Ising,
         equilibration_steps: 100,
         measurement_steps: 1000,
+        auto_equilibration: false,
+        measurement_target_error: None,
+        target_observables: vec![TargetObservable::Energy],
         temperatures,
         temperature_range: vec![],
         num_threads: 1,
         pt_interval: 0,
+        replicas_per_temperature: 1,
         algorithm: Algorithm::Metropolis,
         boltzmann_constant: 1.0,
+        seed: 0,
     }
 }
 
@@ -59,12 +69,17 @@ fn validate_neither_specified_errors() {
         model: Model::Ising,
         equilibration_steps: 100,
         measurement_steps: 1000,
+        auto_equilibration: false,
+        measurement_target_error: None,
+        target_observables: vec![TargetObservable::Energy],
         temperatures: vec![],
         temperature_range: vec![],
         num_threads: 1,
         pt_interval: 0,
+        replicas_per_temperature: 1,
         algorithm: Algorithm::Metropolis,
         boltzmann_constant: 1.0,
+        seed: 0,
     };
     let err = s.validate().unwrap_err().to_string();
     assert!(err.contains("Either 'temperatures' or 'temperature_range'"));
@@ -77,6 +92,9 @@ fn validate_both_specified_errors() {
         model: Model::Ising,
         equilibration_steps: 100,
         measurement_steps: 1000,
+        auto_equilibration: false,
+        measurement_target_error: None,
+        target_observables: vec![TargetObservable::Energy],
         temperatures: vec![1.0],
         temperature_range: vec![TemperatureRange {
             start: 1.0,
@@ -85,8 +103,10 @@ fn validate_both_specified_errors() {
         }],
         num_threads: 1,
         pt_interval: 0,
+        replicas_per_temperature: 1,
         algorithm: Algorithm::Metropolis,
         boltzmann_constant: 1.0,
+        seed: 0,
     };
     let err = s.validate().unwrap_err().to_string();
     assert!(err.contains("Only one of 'temperatures' or 'temperature_range'"));
@@ -181,6 +201,14 @@ fn validate_zero_num_threads_errors() {
     assert!(err.contains("greater than zero"));
 }
 
+#[test]
+fn validate_zero_replicas_per_temperature_errors() {
+    let mut s = simulation_with_temperatures(vec![1.0]);
+    s.replicas_per_temperature = 0;
+    let err = s.validate().unwrap_err().to_string();
+    assert!(err.contains("replicas_per_temperature"), "{err}");
+}
+
 #[test]
 fn validate_non_positive_boltzmann_constant_errors() {
     let mut s = simulation_with_temperatures(vec![1.0]);
@@ -212,15 +240,6 @@ fn validate_step_count_overflow_errors() {
     assert!(err.contains("too large"));
 }
 
-#[test]
-fn validate_parallel_tempering_requires_multiple_temperatures() {
-    let mut s = simulation_with_temperatures(vec![1.0]);
-    s.pt_interval = 10;
-    let err = s.validate().unwrap_err().to_string();
-    assert!(err.contains("parallel tempering"));
-    assert!(err.contains("at least two temperatures"));
-}
-
 #[test]
 fn validate_parallel_tempering_allows_multiple_temperatures() {
     let mut s = simulation_with_temperatures(vec![1.0, 2.0]);
@@ -235,18 +254,24 @@ fn display() {
         model: Model::Heisenberg,
         equilibration_steps: 100,
         measurement_steps: 1000,
+        auto_equilibration: false,
+        measurement_target_error: None,
+        target_observables: vec![TargetObservable::Energy],
         temperatures: vec![1.0, 2.0],
         temperature_range: vec![],
         num_threads: 4,
         pt_interval: 0,
+        replicas_per_temperature: 1,
         algorithm: Algorithm::Wolff,
         boltzmann_constant: 1.0,
+        seed: 42,
     };
     s.validate().unwrap();
     let output = format!("{s}");
     assert!(output.contains("Simulation"));
     assert!(output.contains("Heisenberg"));
     assert!(output.contains("Wolff"));
+    assert!(output.contains("Seed: 42"));
 }
 
 #[test]
@@ -256,14 +281,48 @@ fn display_parallel_tempering_enabled() {
         model: Model::Ising,
         equilibration_steps: 10,
         measurement_steps: 20,
+        auto_equilibration: false,
+        measurement_target_error: None,
+        target_observables: vec![TargetObservable::Energy],
         temperatures: vec![1.0],
         temperature_range: vec![],
         num_threads: 2,
         pt_interval: 5,
+        replicas_per_temperature: 1,
         algorithm: Algorithm::Metropolis,
         boltzmann_constant: 1.0,
+        seed: 0,
     };
     let output = format!("{s}");
     assert!(output.contains("enabled"));
     assert!(output.contains("swap every 5"));
 }
+
+#[test]
+fn validate_auto_equilibration_requires_cap() {
+    let mut s = simulation_with_temperatures(vec![1.0]);
+    s.auto_equilibration = true;
+    s.equilibration_steps = 0;
+    let err = s.validate().unwrap_err().to_string();
+    assert!(err.contains("auto_equilibration"), "{err}");
+
+    s.equilibration_steps = 1000;
+    assert!(s.validate().is_ok());
+    assert!(format!("{s}").contains("automatic, at most 1000"));
+}
+
+#[test]
+fn validate_measurement_target_error_must_be_positive() {
+    for target in [0.0, -1e-3, f64::NAN] {
+        let mut s = simulation_with_temperatures(vec![1.0]);
+        s.measurement_target_error = Some(target);
+        assert!(s.validate().is_err(), "target {target} accepted");
+    }
+    let mut s = simulation_with_temperatures(vec![1.0]);
+    s.measurement_target_error = Some(1e-3);
+    assert!(s.validate().is_ok());
+    assert!(format!("{s}").contains("until error of [Energy] <= 0.001"));
+
+    s.target_observables.clear();
+    assert!(s.validate().is_err());
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct StructureFactor {
+    /// Path vertices in reciprocal lattice units (h, k, l).
+    #[serde(default)]
+    pub q_path: Vec<[f64; 3]>,
+    /// Points per path segment, the end vertex excluded.
+    #[serde(default = "default_path_points")]
+    pub path_points: usize,
+    /// Every wavevector (h/L1, k/L2, l/L3) allowed by the grid instead of a
+    /// path, evaluated with an FFT in O(N log N) rather than O(N) per q point.
+    #[serde(default)]
+    pub full_grid: bool,
+    /// Recorded measurements between S(q) evaluations.
+    #[serde(default = "default_interval")]
+    pub interval: usize,
+    #[serde(default = "default_save_dir")]
+    pub save_directory: String,
+}
+
+fn default_path_points() -> usize {
+    20
+}
+
+fn default_interval() -> usize {
+    1
+}
+
+fn default_save_dir() -> String {
+    "structure_factor".to_string()
+}
+
+impl StructureFactor {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        match (self.q_path.is_empty(), self.full_grid) {
+            (true, false) => {
+                anyhow::bail!("structure_factor requires either 'q_path' or 'full_grid = true'")
+            }
+            (false, true) => {
+                anyhow::bail!(
+                    "Only one of 'q_path' or 'full_grid' can be specified for structure_factor, not both"
+                )
+            }
+            _ => {}
+        }
+        for (index, q) in self.q_path.iter().enumerate() {
+            if q.iter().any(|component| !component.is_finite()) {
+                anyhow::bail!("structure_factor q_path[{index}] ({q:?}) must be finite");
+            }
+        }
+        if self.path_points == 0 {
+            anyhow::bail!("structure_factor path_points must be greater than zero");
+        }
+        if self.interval == 0 {
+            anyhow::bail!("structure_factor interval must be greater than zero");
+        }
+        if self.save_directory.trim().is_empty() {
+            anyhow::bail!("structure_factor save_directory must not be empty");
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for StructureFactor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\nStructure Factor S(q): Enable")?;
+        if self.full_grid {
+            writeln!(f, "  q Points: full grid")?;
+        } else {
+            write!(f, "  q Path (r.l.u.):")?;
+            for q in &self.q_path {
+                write!(f, " ({}, {}, {})", q[0], q[1], q[2])?;
+            }
+            writeln!(f)?;
+            writeln!(f, "  Points per Segment: {}", self.path_points)?;
+        }
+        writeln!(f, "  Interval: {} measurements", self.interval)?;
+        writeln!(f, "  Structure Factor Directory: {}", self.save_directory)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "structure_factor_tests.rs"]
+mod tests;
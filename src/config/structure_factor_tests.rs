@@ -0,0 +1,42 @@
+use super::*;
+
+#[test]
+fn defaults_with_full_grid() {
+    let sf: StructureFactor = toml::from_str("full_grid = true").unwrap();
+    assert!(sf.q_path.is_empty());
+    assert_eq!(sf.path_points, 20);
+    assert_eq!(sf.interval, 1);
+    assert_eq!(sf.save_directory, "structure_factor");
+    assert!(sf.validate().is_ok());
+}
+
+#[test]
+fn validate_requires_exactly_one_of_path_and_grid() {
+    let sf: StructureFactor = toml::from_str("").unwrap();
+    assert!(sf.validate().is_err());
+
+    let sf: StructureFactor =
+        toml::from_str("full_grid = true\nq_path = [[0.0, 0.0, 0.0], [0.5, 0.0, 0.0]]").unwrap();
+    assert!(sf.validate().is_err());
+
+    let sf: StructureFactor =
+        toml::from_str("q_path = [[0.0, 0.0, 0.0], [0.5, 0.0, 0.0]]").unwrap();
+    assert!(sf.validate().is_ok());
+}
+
+#[test]
+fn validate_rejects_bad_values() {
+    let path = "q_path = [[0.0, 0.0, 0.0], [0.5, 0.0, 0.0]]";
+    for extra in ["path_points = 0", "interval = 0", "save_directory = \" \""] {
+        let sf: StructureFactor = toml::from_str(&format!("{path}\n{extra}")).unwrap();
+        assert!(sf.validate().is_err(), "{extra}");
+    }
+    let sf = StructureFactor {
+        q_path: vec![[f64::NAN, 0.0, 0.0]],
+        path_points: 1,
+        full_grid: false,
+        interval: 1,
+        save_directory: "sq".into(),
+    };
+    assert!(sf.validate().is_err());
+}
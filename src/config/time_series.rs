@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TimeSeries {
+    /// Sweeps between rows during equilibration; 0 records measurements only.
+    #[serde(default)]
+    pub equilibration_interval: usize,
+    #[serde(default = "default_save_dir")]
+    pub save_directory: String,
+}
+
+fn default_save_dir() -> String {
+    "time_series".to_string()
+}
+
+impl TimeSeries {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.save_directory.trim().is_empty() {
+            anyhow::bail!("time_series save_directory must not be empty");
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TimeSeries {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\nTime Series: Enable")?;
+        writeln!(
+            f,
+            "  Equilibration Interval: {} steps",
+            self.equilibration_interval
+        )?;
+        writeln!(f, "  Measurement Interval: every recorded step")?;
+        writeln!(f, "  Time Series Directory: {}", self.save_directory)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "time_series_tests.rs"]
+mod tests;
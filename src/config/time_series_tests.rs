@@ -0,0 +1,18 @@
+use super::*;
+
+#[test]
+fn defaults_record_measurements_only() {
+    let ts: TimeSeries = toml::from_str("").unwrap();
+    assert_eq!(ts.equilibration_interval, 0);
+    assert_eq!(ts.save_directory, "time_series");
+    assert!(ts.validate().is_ok());
+}
+
+#[test]
+fn validate_rejects_empty_save_directory() {
+    let ts = TimeSeries {
+        equilibration_interval: 10,
+        save_directory: "  ".into(),
+    };
+    assert!(ts.validate().is_err());
+}
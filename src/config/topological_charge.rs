@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TopologicalCharge {
+    #[serde(default = "default_savefile")]
+    pub savefile: String,
+    /// Also write the average charge density per site, one file per temperature.
+    #[serde(default)]
+    pub density_map: bool,
+    #[serde(default = "default_density_dir")]
+    pub density_directory: String,
+}
+
+fn default_savefile() -> String {
+    "topological_charge.txt".to_string()
+}
+
+fn default_density_dir() -> String {
+    "topological_density".to_string()
+}
+
+impl TopologicalCharge {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.savefile.trim().is_empty() {
+            anyhow::bail!("topological_charge savefile must not be empty");
+        }
+        if self.density_map && self.density_directory.trim().is_empty() {
+            anyhow::bail!("topological_charge density_directory must not be empty");
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TopologicalCharge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\nTopological Charge: Enable")?;
+        writeln!(f, "  Savefile: {}", self.savefile)?;
+        if self.density_map {
+            writeln!(f, "  Density Map Directory: {}", self.density_directory)?;
+        } else {
+            writeln!(f, "  Density Map: false")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "topological_charge_tests.rs"]
+mod tests;
@@ -0,0 +1,24 @@
+use super::*;
+
+#[test]
+fn defaults_write_charges_only() {
+    let tc: TopologicalCharge = toml::from_str("").unwrap();
+    assert_eq!(tc.savefile, "topological_charge.txt");
+    assert!(!tc.density_map);
+    assert_eq!(tc.density_directory, "topological_density");
+    assert!(tc.validate().is_ok());
+}
+
+#[test]
+fn validate_rejects_empty_paths() {
+    let tc: TopologicalCharge = toml::from_str("savefile = \"\"").unwrap();
+    assert!(tc.validate().is_err());
+
+    let tc: TopologicalCharge =
+        toml::from_str("density_map = true\ndensity_directory = \" \"").unwrap();
+    assert!(tc.validate().is_err());
+
+    // The directory is only used with density_map.
+    let tc: TopologicalCharge = toml::from_str("density_directory = \"\"").unwrap();
+    assert!(tc.validate().is_ok());
+}
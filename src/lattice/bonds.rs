@@ -0,0 +1,143 @@
+use crate::spin::{Real, widen};
+
+/// Exchange bonds of every site in compressed sparse row form: the bonds of
+/// site i are entries `offsets[i]..offsets[i + 1]` of the other arrays. One
+/// table serves all sites and all `[[exchange]]` entries of a grid.
+#[derive(Clone, Debug)]
+pub struct NeighborTable {
+    offsets: Vec<usize>,
+    neighbors: Vec<u32>,
+    couplings: Vec<Real>,
+    entries: Vec<u32>, // [[exchange]] entry of each bond
+}
+
+/// The bonds of one site.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Bonds<'a> {
+    pub neighbors: &'a [u32],
+    pub couplings: &'a [Real],
+    pub entries: &'a [u32],
+}
+
+impl Default for NeighborTable {
+    fn default() -> Self {
+        Self {
+            offsets: vec![0],
+            neighbors: Vec::new(),
+            couplings: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl NeighborTable {
+    /// Appends the bonds `(neighbor, coupling, entry)` of the next site.
+    pub fn push_site(
+        &mut self,
+        bonds: impl IntoIterator<Item = (usize, f64, usize)>,
+    ) -> anyhow::Result<()> {
+        let start = self.neighbors.len();
+        for (neighbor, coupling, entry) in bonds {
+            let neighbor = u32::try_from(neighbor)
+                .map_err(|_| anyhow::anyhow!("site index {neighbor} exceeds the u32 range"))?;
+            if self.neighbors[start..].contains(&neighbor) {
+                anyhow::bail!(
+                    "Duplicate neighbor indices found in your exchange coupling configuration. Please ensure all neighbor indices are unique."
+                );
+            }
+            self.neighbors.push(neighbor);
+            self.couplings.push(coupling as Real);
+            self.entries.push(entry as u32);
+        }
+        self.offsets.push(self.neighbors.len());
+        Ok(())
+    }
+
+    /// Number of sites.
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn bonds(&self, site: usize) -> Bonds<'_> {
+        let range = self.offsets[site]..self.offsets[site + 1];
+        Bonds {
+            neighbors: &self.neighbors[range.clone()],
+            couplings: &self.couplings[range.clone()],
+            entries: &self.entries[range],
+        }
+    }
+
+    /// Sites grouped by a greedy coloring of the symmetrized bond graph in
+    /// site order: sites of one color share no bond. Gives the two-color
+    /// checkerboard on bipartite lattices with even extents.
+    pub fn colors(&self) -> Vec<Vec<usize>> {
+        // The incoming bonds of every site, as a transposed copy of the rows.
+        let mut reverse_offsets = vec![0; self.len() + 1];
+        for &neighbor in &self.neighbors {
+            reverse_offsets[neighbor as usize + 1] += 1;
+        }
+        for site in 0..self.len() {
+            reverse_offsets[site + 1] += reverse_offsets[site];
+        }
+        let mut next = reverse_offsets.clone();
+        let mut reverse = vec![0; self.neighbors.len()];
+        for site in 0..self.len() {
+            for &neighbor in self.bonds(site).neighbors {
+                reverse[next[neighbor as usize]] = site as u32;
+                next[neighbor as usize] += 1;
+            }
+        }
+
+        let mut site_colors = vec![usize::MAX; self.len()];
+        let mut colors: Vec<Vec<usize>> = Vec::new();
+        let mut taken = Vec::new();
+        for site in 0..self.len() {
+            taken.clear();
+            taken.resize(colors.len() + 1, false);
+            let incoming = &reverse[reverse_offsets[site]..reverse_offsets[site + 1]];
+            for &neighbor in self.bonds(site).neighbors.iter().chain(incoming) {
+                if site_colors[neighbor as usize] < taken.len() {
+                    taken[site_colors[neighbor as usize]] = true;
+                }
+            }
+            let color = taken.iter().position(|t| !t).unwrap_or(colors.len());
+            if color == colors.len() {
+                colors.push(Vec::new());
+            }
+            site_colors[site] = color;
+            colors[color].push(site);
+        }
+        colors
+    }
+}
+
+impl<'a> Bonds<'a> {
+    pub fn len(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.neighbors.is_empty()
+    }
+
+    /// `(neighbor, coupling)` of every bond.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, f64)> + 'a {
+        self.neighbors
+            .iter()
+            .zip(self.couplings)
+            .map(|(n, j)| (*n as usize, widen(*j)))
+    }
+
+    /// `[[exchange]]` entry of every bond, in the order of `iter`.
+    pub fn entries(&self) -> impl Iterator<Item = usize> + 'a {
+        self.entries.iter().map(|e| *e as usize)
+    }
+}
+
+#[cfg(test)]
+#[path = "bonds_tests.rs"]
+mod tests;
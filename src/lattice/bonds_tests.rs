@@ -0,0 +1,40 @@
+use super::*;
+
+#[test]
+fn neighbor_table_rows_follow_push_order() {
+    let mut table = NeighborTable::default();
+    assert!(table.is_empty());
+    table.push_site([(1, 1.0, 0), (2, -0.5, 1)]).unwrap();
+    table.push_site([]).unwrap();
+    table.push_site([(0, 2.0, 0)]).unwrap();
+
+    assert_eq!(table.len(), 3);
+    assert_eq!(
+        table.bonds(0).iter().collect::<Vec<_>>(),
+        vec![(1, 1.0), (2, -0.5)]
+    );
+    assert_eq!(table.bonds(0).entries().collect::<Vec<_>>(), vec![0, 1]);
+    assert!(table.bonds(1).is_empty());
+    assert_eq!(table.bonds(2).len(), 1);
+    assert_eq!(table.bonds(2).iter().next(), Some((0, 2.0)));
+}
+
+#[test]
+fn neighbor_table_rejects_duplicate_neighbors() {
+    let mut table = NeighborTable::default();
+    let err = table
+        .push_site([(1, 1.0, 0), (1, 1.0, 1)])
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Duplicate neighbor"), "{err}");
+}
+
+#[test]
+fn neighbor_table_colors_follow_bonds_in_either_direction() {
+    // A one-way path 0 → 1 → 2 plus the self-bond of site 2.
+    let mut table = NeighborTable::default();
+    table.push_site([(1, 1.0, 0)]).unwrap();
+    table.push_site([(2, 1.0, 0)]).unwrap();
+    table.push_site([(2, 1.0, 0)]).unwrap();
+    assert_eq!(table.colors(), vec![vec![0, 2], vec![1]]);
+}
@@ -1,18 +1,40 @@
-use crate::calculators::{CalcInput, Hamiltonian};
+use super::{Bonds, NeighborTable};
+use crate::calculators::{CalcInput, EnergyTerms, Hamiltonian};
 use crate::config::{Config, InitialState};
-use crate::spin::SpinState;
+use crate::spin::{Real, SpinState};
 use itertools::{iproduct, zip_eq};
 use tracing::debug;
 
+/// Full recomputation of the running totals happens after this many sweeps'
+/// worth of single-spin updates, bounding the floating-point drift.
+const RESYNC_SWEEPS: usize = 100;
+
+/// Energy and spin sums kept up to date from the ΔE and Δs of accepted moves,
+/// so measurements do not have to walk the whole lattice. Held in f64 even
+/// when spins are stored in f32.
+#[derive(Clone, Default)]
+pub struct RunningTotals {
+    pub energy: f64,
+    pub spin_sum: [f64; 3],
+    pub group_sums: Vec<[f64; 3]>,
+    /// For every sublattice, the output groups it belongs to.
+    pub sublattice_groups: Vec<Vec<usize>>,
+    pub updates: usize,
+}
+
+#[derive(Clone)]
 pub struct Grid<S: SpinState, R: rand::Rng> {
     pub spins: Vec<S>,
     pub size: usize,
     pub dim: [usize; 3],
     pub num_sublattices: usize,
-    pub calc_inputs: Vec<CalcInput<S>>,
+    pub calc_inputs: Vec<CalcInput>,
+    pub neighbors: NeighborTable,
     pub rng: R,
     pub hamiltonian: Hamiltonian,
     pub group_index: Vec<Vec<usize>>,
+    pub order_weights: Vec<Vec<f64>>, // per order parameter, the weight w_i of every site
+    pub totals: RunningTotals,
 }
 
 impl<S: SpinState, R: rand::Rng> Grid<S, R> {
@@ -38,7 +60,17 @@ impl<S: SpinState, R: rand::Rng> Grid<S, R> {
             group_index.push(indexs);
         }
 
-        let mut calc_inputs: Vec<CalcInput<S>> = vec![];
+        let order_weights = config
+            .order_parameter
+            .iter()
+            .map(|order_parameter| {
+                iproduct!(0..num_sublattices, 0..dim[0], 0..dim[1], 0..dim[2])
+                    .map(|(sub, x, y, z)| order_parameter.weight(sub, [x, y, z]))
+                    .collect()
+            })
+            .collect();
+
+        let mut calc_inputs: Vec<CalcInput> = vec![];
         for magnitude in &config.grid.spin_magnitudes {
             let new_spin = match config.simulation.initial_state {
                 InitialState::Random => S::random(&mut rng, *magnitude),
@@ -49,7 +81,7 @@ impl<S: SpinState, R: rand::Rng> Grid<S, R> {
             spins.extend(std::iter::repeat_n(new_spin, total_sites));
             calc_inputs.extend(std::iter::repeat_n(
                 CalcInput {
-                    magnitude: *magnitude,
+                    magnitude: *magnitude as Real,
                     ..Default::default()
                 },
                 total_sites,
@@ -57,14 +89,17 @@ impl<S: SpinState, R: rand::Rng> Grid<S, R> {
         }
 
         let hamiltonian = Hamiltonian::new(config);
+        let mut neighbors = NeighborTable::default();
 
+        // Sites are visited in index order, as the rows of `neighbors` require.
         for (sublattice, x, y, z) in iproduct!(0..num_sublattices, 0..dim[0], 0..dim[1], 0..dim[2])
         {
             let index = coord_to_index([x as isize, y as isize, z as isize], sublattice, dim);
 
+            debug_assert_eq!(index, neighbors.len());
             let calc_input = &mut calc_inputs[index];
 
-            let mut exchange_neighbors = vec![];
+            let mut bonds = vec![];
             for exchange in &config.parsed_exchange {
                 if exchange.from_sub == sublattice {
                     let offset_coord = [
@@ -81,49 +116,187 @@ impl<S: SpinState, R: rand::Rng> Grid<S, R> {
                         config.grid.periodic_boundary,
                     );
                     if let Some(offset_index) = offset_index_opt {
-                        exchange_neighbors
-                            .push((&spins[offset_index] as *const S, exchange.strength));
-                        calc_input.exchange_neighbor_index.push(offset_index);
-                        calc_input.exchanges.push(exchange.strength);
+                        bonds.push((offset_index, exchange.strength, exchange.entry));
                     }
                 }
             }
 
             if !&config.parsed_anisotropy.is_empty() {
+                let anisotropy = &config.parsed_anisotropy[sublattice];
                 calc_input.anisotropy = (
-                    config.parsed_anisotropy[sublattice].strength,
-                    config.parsed_anisotropy[sublattice].axis,
+                    anisotropy.strength as Real,
+                    anisotropy.axis.map(|x| x as Real),
                 );
                 debug!("{:?}", calc_input.anisotropy);
             }
-            calc_input.exchange_neighbors = Some(exchange_neighbors);
-            calc_input.validate_exchange_neighbor()?;
+            neighbors.push_site(bonds)?;
         }
 
-        Ok(Self {
+        let mut sublattice_groups = vec![Vec::new(); num_sublattices];
+        for (group, sub_lattice_group) in config.output.group.iter().enumerate() {
+            for sub_lattice in sub_lattice_group {
+                sublattice_groups[*sub_lattice].push(group);
+            }
+        }
+
+        let mut grid = Self {
             dim,
             num_sublattices,
             rng,
             size: total_sites * num_sublattices,
             spins,
             calc_inputs,
+            neighbors,
             hamiltonian,
             group_index,
-        })
+            order_weights,
+            totals: RunningTotals {
+                sublattice_groups,
+                ..Default::default()
+            },
+        };
+        grid.resync();
+        Ok(grid)
+    }
+
+    /// Running total energy, equal to `total_energy()` up to rounding.
+    pub fn energy(&self) -> f64 {
+        self.totals.energy
+    }
+
+    /// Running magnetization, equal to `total_spin_vector()` up to rounding.
+    /// Returned in f64 so observables built on it keep full precision.
+    pub fn spin_sum(&self) -> [f64; 3] {
+        self.totals.spin_sum
+    }
+
+    /// Running magnetization of output group `index`.
+    pub fn group_spin_sum(&self, index: usize) -> [f64; 3] {
+        self.totals.group_sums[index]
+    }
+
+    /// Replaces spin `index`, updating the running spin sums. The caller adds
+    /// the energy change with `add_energy`.
+    pub fn set_spin(&mut self, index: usize, spin: S) {
+        let (new, old) = (spin.to_array(), self.spins[index].to_array());
+        let delta: [f64; 3] = std::array::from_fn(|k| new[k] - old[k]);
+        self.spins[index] = spin;
+        add_assign(&mut self.totals.spin_sum, delta);
+        let sub = index / (self.dim[0] * self.dim[1] * self.dim[2]);
+        for group in &self.totals.sublattice_groups[sub] {
+            add_assign(&mut self.totals.group_sums[*group], delta);
+        }
+        self.totals.updates += 1;
+    }
+
+    pub fn add_energy(&mut self, delta_e: f64) {
+        self.totals.energy += delta_e;
+    }
+
+    /// Recomputes the running totals from scratch.
+    pub fn resync(&mut self) {
+        self.totals.energy = self.total_energy();
+        self.totals.spin_sum = sum_arrays(self.spins.iter().map(|spin| (spin, 1.0)));
+        self.totals.group_sums = self
+            .group_index
+            .iter()
+            .map(|sites| sum_arrays(sites.iter().map(|i| (&self.spins[*i], 1.0))))
+            .collect();
+        self.totals.updates = 0;
+    }
+
+    /// Resyncs once enough updates have accumulated since the last one.
+    pub fn resync_if_due(&mut self) {
+        if self.totals.updates >= RESYNC_SWEEPS * self.size {
+            self.resync();
+        }
     }
 
     pub fn total_energy(&self) -> f64 {
         zip_eq(self.spins.iter(), self.calc_inputs.iter())
-            .map(|(spin, calc_input)| spin.energy(calc_input, &self.hamiltonian, &self.spins))
+            .enumerate()
+            .map(|(i, (spin, calc_input))| {
+                spin.energy(calc_input, self.bonds(i), &self.hamiltonian, &self.spins)
+            })
             .sum::<f64>()
     }
 
-    pub fn partial_spin_vector(&self, index: usize) -> S {
-        self.group_index[index].iter().map(|i| self.spins[*i]).sum()
+    pub fn bonds(&self, site: usize) -> Bonds<'_> {
+        self.neighbors.bonds(site)
     }
 
-    pub fn total_spin_vector(&self) -> S {
-        self.spins.iter().copied().sum()
+    pub fn energy_terms(&self) -> EnergyTerms {
+        zip_eq(self.spins.iter(), self.calc_inputs.iter())
+            .enumerate()
+            .fold(EnergyTerms::default(), |total, (i, (spin, calc_input))| {
+                let terms =
+                    self.hamiltonian
+                        .compute_terms(spin, calc_input, self.bonds(i), &self.spins);
+                EnergyTerms {
+                    exchange: total.exchange + terms.exchange,
+                    anisotropy: total.anisotropy + terms.anisotropy,
+                }
+            })
+    }
+
+    /// Anisotropy energy at unit `Hamiltonian::anisotropy_scale`.
+    pub fn unscaled_anisotropy_energy(&self) -> f64 {
+        if !self.hamiltonian.config.anisotropy_enable {
+            return 0.0;
+        }
+        zip_eq(self.spins.iter(), self.calc_inputs.iter())
+            .map(|(spin, calc_input)| self.hamiltonian.unscaled_anisotropy(spin, calc_input))
+            .sum()
+    }
+
+    /// Rescales every single-ion anisotropy strength, moving the running
+    /// energy along.
+    pub fn set_anisotropy_scale(&mut self, scale: f64) {
+        let change = scale - self.hamiltonian.anisotropy_scale;
+        self.totals.energy += change * self.unscaled_anisotropy_energy();
+        self.hamiltonian.anisotropy_scale = scale;
+    }
+
+    /// Exchange energy of the bonds of each of the `entries` `[[exchange]]` entries.
+    pub fn exchange_entry_energies(&self, entries: usize) -> Vec<f64> {
+        let mut energies = vec![0.0; entries];
+        for (i, spin) in self.spins.iter().enumerate() {
+            self.hamiltonian.accumulate_exchange_entries(
+                spin,
+                self.bonds(i),
+                &self.spins,
+                &mut energies,
+            );
+        }
+        energies
+    }
+
+    pub fn partial_spin_vector(&self, index: usize) -> [f64; 3] {
+        sum_arrays(
+            self.group_index[index]
+                .iter()
+                .map(|i| (&self.spins[*i], 1.0)),
+        )
+    }
+
+    /// Weighted magnetization M_w = Σ_i w_i s_i of order parameter `index`.
+    pub fn order_parameter_vector(&self, index: usize) -> [f64; 3] {
+        sum_arrays(zip_eq(
+            &self.spins,
+            self.order_weights[index].iter().copied(),
+        ))
+    }
+
+    /// Σ_i |w_i| of every order parameter, the normalisation of M_w.
+    pub fn order_sizes(&self) -> Vec<f64> {
+        self.order_weights
+            .iter()
+            .map(|weights| weights.iter().map(|w| w.abs()).sum())
+            .collect()
+    }
+
+    pub fn total_spin_vector(&self) -> [f64; 3] {
+        sum_arrays(self.spins.iter().map(|spin| (spin, 1.0)))
     }
     pub fn get_spin_by_coord(&self, sub: usize, x: isize, y: isize, z: isize) -> Option<&S> {
         if sub >= self.num_sublattices
@@ -176,7 +349,7 @@ fn coord_to_index(coord: [isize; 3], sublattice: usize, dim: [usize; 3]) -> usiz
     sublattice * (dim[0] * dim[1] * dim[2]) + x * (dim[1] * dim[2]) + y * dim[2] + z
 }
 
-fn safe_coord_to_index(
+pub(crate) fn safe_coord_to_index(
     mut coord: [isize; 3],
     sublattice: usize,
     dim: [usize; 3],
@@ -200,6 +373,21 @@ fn safe_coord_to_index(
     Some(coord_to_index(coord, sublattice, dim))
 }
 
+/// Σ_i w_i s_i accumulated in f64, whatever precision spins are stored in.
+fn sum_arrays<'a, S: SpinState>(terms: impl Iterator<Item = (&'a S, f64)>) -> [f64; 3] {
+    let mut sum = [0.0; 3];
+    for (spin, weight) in terms {
+        add_assign(&mut sum, spin.to_array().map(|x| x * weight));
+    }
+    sum
+}
+
+fn add_assign(sum: &mut [f64; 3], delta: [f64; 3]) {
+    for (total, d) in sum.iter_mut().zip(delta) {
+        *total += d;
+    }
+}
+
 #[cfg(test)]
 #[path = "grid_tests.rs"]
 mod tests;
@@ -1,7 +1,7 @@
 use super::*;
 use crate::calculators::{CalcInput, Hamiltonian, HamiltonianConfig};
 use crate::config::Config;
-use crate::spin::{IsingSpin, SpinState};
+use crate::spin::{HeisenbergSpin, IsingSpin, SpinState};
 use rand::SeedableRng;
 use rand::rngs::SmallRng;
 
@@ -80,6 +80,14 @@ fn safe_coord_to_index_partial_pbc() {
 
 // --- Grid helper ---
 
+/// Sites 0 and 1 bonded to each other with strength `j`.
+fn pair_table(j: f64) -> NeighborTable {
+    let mut table = NeighborTable::default();
+    table.push_site([(1, j, 0)]).unwrap();
+    table.push_site([(0, j, 0)]).unwrap();
+    table
+}
+
 fn make_ising_grid_2x1_with_exchange(j: f64) -> Grid<IsingSpin, SmallRng> {
     let dim = [2usize, 1, 1];
     let size = 2;
@@ -87,27 +95,14 @@ fn make_ising_grid_2x1_with_exchange(j: f64) -> Grid<IsingSpin, SmallRng> {
         IsingSpin::along_z(1.0).unwrap(),
         IsingSpin::along_z(1.0).unwrap(),
     ];
-    // SAFETY: raw pointers obtained here stay valid because `spins` Vec's heap allocation
-    // doesn't move when the Vec is moved into the Grid below.
-    let p0: *const IsingSpin = &spins[0];
-    let p1: *const IsingSpin = &spins[1];
-
     let calc_inputs = vec![
         CalcInput {
             magnitude: 1.0,
-            exchange_neighbors: Some(vec![(p1, j)]),
-            exchanges: vec![j],
-            exchange_neighbor_index: vec![1],
-            ..Default::default()
-        },
-        CalcInput {
-            magnitude: 1.0,
-            exchange_neighbors: Some(vec![(p0, j)]),
-            exchanges: vec![j],
-            exchange_neighbor_index: vec![0],
             ..Default::default()
-        },
+        };
+        2
     ];
+    let neighbors = pair_table(j);
 
     let hamiltonian = Hamiltonian {
         config: HamiltonianConfig {
@@ -116,6 +111,7 @@ fn make_ising_grid_2x1_with_exchange(j: f64) -> Grid<IsingSpin, SmallRng> {
             zeeman_enable: false,
             dm_enable: false,
         },
+        anisotropy_scale: 1.0,
     };
     let rng = SmallRng::seed_from_u64(0);
 
@@ -125,9 +121,12 @@ fn make_ising_grid_2x1_with_exchange(j: f64) -> Grid<IsingSpin, SmallRng> {
         dim,
         num_sublattices: 1,
         calc_inputs,
+        neighbors,
         rng,
         hamiltonian,
         group_index: vec![vec![0, 1]],
+        order_weights: vec![],
+        totals: RunningTotals::default(),
     }
 }
 
@@ -150,31 +149,21 @@ fn total_energy_antiferromagnetic_2_spins() {
         IsingSpin::along_z(1.0).unwrap(),
         IsingSpin::along_z(-1.0).unwrap(),
     ];
-    let p0: *const IsingSpin = &spins[0];
-    let p1: *const IsingSpin = &spins[1];
-
     let calc_inputs = vec![
         CalcInput {
             magnitude: 1.0,
-            exchange_neighbors: Some(vec![(p1, 1.0)]),
-            exchanges: vec![1.0],
-            exchange_neighbor_index: vec![1],
             ..Default::default()
-        },
-        CalcInput {
-            magnitude: 1.0,
-            exchange_neighbors: Some(vec![(p0, 1.0)]),
-            exchanges: vec![1.0],
-            exchange_neighbor_index: vec![0],
-            ..Default::default()
-        },
+        };
+        2
     ];
+    let neighbors = pair_table(1.0);
     let grid = Grid {
         spins,
         size,
         dim,
         num_sublattices: 1,
         calc_inputs,
+        neighbors,
         rng: SmallRng::seed_from_u64(0),
         hamiltonian: Hamiltonian {
             config: HamiltonianConfig {
@@ -183,8 +172,11 @@ fn total_energy_antiferromagnetic_2_spins() {
                 zeeman_enable: false,
                 dm_enable: false,
             },
+            anisotropy_scale: 1.0,
         },
         group_index: vec![vec![0, 1]],
+        order_weights: vec![],
+        totals: RunningTotals::default(),
     };
     let e = grid.total_energy();
     // Spin 0 (+1) sees neighbor (-1): -1 * (-1) / 2 = 0.5
@@ -212,6 +204,7 @@ fn total_spin_vector_all_up() {
         dim,
         num_sublattices: 1,
         calc_inputs: vec![],
+        neighbors: NeighborTable::default(),
         rng: SmallRng::seed_from_u64(0),
         hamiltonian: Hamiltonian {
             config: HamiltonianConfig {
@@ -220,11 +213,14 @@ fn total_spin_vector_all_up() {
                 zeeman_enable: false,
                 dm_enable: false,
             },
+            anisotropy_scale: 1.0,
         },
         group_index: vec![],
+        order_weights: vec![],
+        totals: RunningTotals::default(),
     };
     let total = grid.total_spin_vector();
-    assert_eq!(total.to_array(), [0.0, 0.0, 3.0]);
+    assert_eq!(total, [0.0, 0.0, 3.0]);
 }
 
 #[test]
@@ -241,6 +237,7 @@ fn total_spin_vector_with_negative() {
         dim,
         num_sublattices: 1,
         calc_inputs: vec![],
+        neighbors: NeighborTable::default(),
         rng: SmallRng::seed_from_u64(0),
         hamiltonian: Hamiltonian {
             config: HamiltonianConfig {
@@ -249,11 +246,14 @@ fn total_spin_vector_with_negative() {
                 zeeman_enable: false,
                 dm_enable: false,
             },
+            anisotropy_scale: 1.0,
         },
         group_index: vec![],
+        order_weights: vec![],
+        totals: RunningTotals::default(),
     };
     let total = grid.total_spin_vector();
-    assert_eq!(total.to_array(), [0.0, 0.0, 2.0]);
+    assert_eq!(total, [0.0, 0.0, 2.0]);
 }
 
 // --- partial_spin_vector ---
@@ -272,6 +272,7 @@ fn partial_spin_vector_group() {
         dim,
         num_sublattices: 1,
         calc_inputs: vec![],
+        neighbors: NeighborTable::default(),
         rng: SmallRng::seed_from_u64(0),
         hamiltonian: Hamiltonian {
             config: HamiltonianConfig {
@@ -280,12 +281,15 @@ fn partial_spin_vector_group() {
                 zeeman_enable: false,
                 dm_enable: false,
             },
+            anisotropy_scale: 1.0,
         },
         group_index: vec![vec![0], vec![1], vec![0, 1]],
+        order_weights: vec![],
+        totals: RunningTotals::default(),
     };
-    assert_eq!(grid.partial_spin_vector(0).to_array(), [0.0, 0.0, 1.0]);
-    assert_eq!(grid.partial_spin_vector(1).to_array(), [0.0, 0.0, 3.0]);
-    assert_eq!(grid.partial_spin_vector(2).to_array(), [0.0, 0.0, 4.0]);
+    assert_eq!(grid.partial_spin_vector(0), [0.0, 0.0, 1.0]);
+    assert_eq!(grid.partial_spin_vector(1), [0.0, 0.0, 3.0]);
+    assert_eq!(grid.partial_spin_vector(2), [0.0, 0.0, 4.0]);
 }
 
 // --- get_spin_by_coord ---
@@ -306,6 +310,7 @@ fn get_spin_by_coord_in_bounds() {
         dim,
         num_sublattices: 1,
         calc_inputs: vec![],
+        neighbors: NeighborTable::default(),
         rng: SmallRng::seed_from_u64(0),
         hamiltonian: Hamiltonian {
             config: HamiltonianConfig {
@@ -314,8 +319,11 @@ fn get_spin_by_coord_in_bounds() {
                 zeeman_enable: false,
                 dm_enable: false,
             },
+            anisotropy_scale: 1.0,
         },
         group_index: vec![],
+        order_weights: vec![],
+        totals: RunningTotals::default(),
     };
     let s = grid.get_spin_by_coord(0, 0, 1, 0);
     // index = 0*4 + 0*2 + 1*1 + 0 = 1 → spin value 2.0
@@ -338,6 +346,7 @@ fn get_spin_by_coord_out_of_bounds() {
         dim,
         num_sublattices: 1,
         calc_inputs: vec![],
+        neighbors: NeighborTable::default(),
         rng: SmallRng::seed_from_u64(0),
         hamiltonian: Hamiltonian {
             config: HamiltonianConfig {
@@ -346,8 +355,11 @@ fn get_spin_by_coord_out_of_bounds() {
                 zeeman_enable: false,
                 dm_enable: false,
             },
+            anisotropy_scale: 1.0,
         },
         group_index: vec![],
+        order_weights: vec![],
+        totals: RunningTotals::default(),
     };
     assert!(grid.get_spin_by_coord(0, 3, 0, 0).is_none());
 }
@@ -364,6 +376,7 @@ fn get_spin_by_coord_rejects_each_out_of_bounds_axis() {
         dim,
         num_sublattices: 1,
         calc_inputs: vec![],
+        neighbors: NeighborTable::default(),
         rng: SmallRng::seed_from_u64(0),
         hamiltonian: Hamiltonian {
             config: HamiltonianConfig {
@@ -372,8 +385,11 @@ fn get_spin_by_coord_rejects_each_out_of_bounds_axis() {
                 zeeman_enable: false,
                 dm_enable: false,
             },
+            anisotropy_scale: 1.0,
         },
         group_index: vec![],
+        order_weights: vec![],
+        totals: RunningTotals::default(),
     };
 
     assert!(grid.get_spin_by_coord(0, -1, 0, 0).is_none());
@@ -392,6 +408,7 @@ fn get_spin_by_coord_rejects_out_of_bounds_sublattice() {
         dim: [1, 1, 1],
         num_sublattices: 1,
         calc_inputs: vec![],
+        neighbors: NeighborTable::default(),
         rng: SmallRng::seed_from_u64(0),
         hamiltonian: Hamiltonian {
             config: HamiltonianConfig {
@@ -400,8 +417,11 @@ fn get_spin_by_coord_rejects_out_of_bounds_sublattice() {
                 zeeman_enable: false,
                 dm_enable: false,
             },
+            anisotropy_scale: 1.0,
         },
         group_index: vec![],
+        order_weights: vec![],
+        totals: RunningTotals::default(),
     };
 
     assert!(grid.get_spin_by_coord(1, 0, 0, 0).is_none());
@@ -418,8 +438,11 @@ fn grid_rng_accessible() {
         dim: [1, 1, 1],
         num_sublattices: 1,
         calc_inputs: vec![],
+        neighbors: NeighborTable::default(),
         rng,
         group_index: vec![],
+        order_weights: vec![],
+        totals: RunningTotals::default(),
         hamiltonian: Hamiltonian {
             config: HamiltonianConfig {
                 exchange_enable: false,
@@ -427,6 +450,7 @@ fn grid_rng_accessible() {
                 zeeman_enable: false,
                 dm_enable: false,
             },
+            anisotropy_scale: 1.0,
         },
     };
     // Just verify rng is still accessible after moving into Grid
@@ -483,11 +507,11 @@ fn grid_new_exchange_neighbors_built() {
     let rng = SmallRng::seed_from_u64(0);
     let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, rng).unwrap();
     // Every site has exactly one +x neighbor; x=1 wraps to x=0 because PBC is enabled.
-    for ci in &grid.calc_inputs {
-        let neighbors = ci.exchange_neighbors.as_ref().unwrap();
-        assert_eq!(neighbors.len(), 1);
-        assert_eq!(ci.exchange_neighbor_index.len(), 1);
-        assert_eq!(ci.exchanges, vec![1.0]);
+    assert_eq!(grid.neighbors.len(), grid.size);
+    for site in 0..grid.size {
+        let bonds = grid.bonds(site);
+        assert_eq!(bonds.len(), 1);
+        assert_eq!(bonds.couplings, &[1.0]);
     }
 }
 
@@ -714,8 +738,8 @@ energy = true
     let config = Config::new(toml).unwrap();
     let rng = SmallRng::seed_from_u64(0);
     let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, rng).unwrap();
-    assert_eq!(grid.calc_inputs[0].exchange_neighbor_index, vec![1]);
-    assert!(grid.calc_inputs[1].exchange_neighbor_index.is_empty());
+    assert_eq!(grid.bonds(0).iter().collect::<Vec<_>>(), vec![(1, 1.0)]);
+    assert!(grid.bonds(1).is_empty());
 }
 
 #[test]
@@ -808,7 +832,7 @@ fn grid_new_partial_spin_vector_works() {
     // With 1 group of 1 sublattice, partial_spin_vector should be accessible
     let v = grid.partial_spin_vector(0);
     // All spins are +1, 4 sites → total = 4.0 in z
-    assert!((v.to_array()[2] - 4.0).abs() < 1e-10);
+    assert!((v[2] - 4.0).abs() < 1e-10);
 }
 
 #[test]
@@ -817,7 +841,7 @@ fn grid_new_total_spin_vector_works() {
     let rng = SmallRng::seed_from_u64(0);
     let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, rng).unwrap();
     let total = grid.total_spin_vector();
-    assert!((total.to_array()[2] - 4.0).abs() < 1e-10);
+    assert!((total[2] - 4.0).abs() < 1e-10);
 }
 
 #[test]
@@ -881,3 +905,290 @@ fn spins_to_array_ising_values() {
         }
     }
 }
+
+#[test]
+fn grid_new_order_weights_and_vector() {
+    let toml = r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 100
+measurement_steps = 1000
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [2, 2, 1]
+sublattices = 2
+spin_magnitudes = [1.0, 1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 1
+offsets = [[0, 0, 0]]
+strength = 1.0
+
+[output]
+energy = true
+
+[[order_parameter]]
+weights = [1.0, -1.0]
+
+[[order_parameter]]
+weights = [1.0, 1.0]
+wavevector = [0.5, 0.0, 0.0]
+"#;
+    let config = Config::new(toml).unwrap();
+    let rng = SmallRng::seed_from_u64(0);
+    let mut grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, rng).unwrap();
+    assert_eq!(grid.order_weights.len(), 2);
+    assert_eq!(
+        grid.order_weights[0],
+        vec![1.0, 1.0, 1.0, 1.0, -1.0, -1.0, -1.0, -1.0]
+    );
+    let stripe: Vec<f64> = grid.order_weights[1].iter().map(|w| w.round()).collect();
+    assert_eq!(stripe, vec![1.0, 1.0, -1.0, -1.0, 1.0, 1.0, -1.0, -1.0]);
+
+    // Ferromagnetic state: no staggered moment.
+    assert_eq!(grid.order_parameter_vector(0), [0.0, 0.0, 0.0]);
+    // Flip sublattice 1: full Néel moment.
+    for spin in &mut grid.spins[4..] {
+        *spin = -*spin;
+    }
+    assert_eq!(grid.order_parameter_vector(0), [0.0, 0.0, 8.0]);
+}
+
+#[test]
+fn grid_energy_terms_and_entries_sum_to_total_energy() {
+    let toml = r#"
+[simulation]
+initial_state = "random"
+model = "heisenberg"
+equilibration_steps = 100
+measurement_steps = 1000
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [3, 3, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0]]
+strength = 1.0
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[0, 1, 0], [0, -1, 0]]
+strength = -0.5
+
+[anisotropy]
+axis = [[0.0, 0.0, 1.0]]
+strength = [0.3]
+
+[output]
+energy = true
+"#;
+    let config = Config::new(toml).unwrap();
+    assert_eq!(config.parsed_exchange[2].entry, 1);
+    let rng = SmallRng::seed_from_u64(7);
+    let grid: Grid<HeisenbergSpin, SmallRng> = Grid::new(&config, rng).unwrap();
+
+    let terms = grid.energy_terms();
+    let entries = grid.exchange_entry_energies(2);
+    assert!((terms.exchange + terms.anisotropy - grid.total_energy()).abs() < 1e-10);
+    assert!((entries[0] + entries[1] - terms.exchange).abs() < 1e-10);
+    assert!(terms.anisotropy < 0.0);
+    assert!(entries[0] != 0.0 && entries[1] != 0.0);
+}
+
+#[test]
+fn grid_set_anisotropy_scale_rescales_anisotropy_energy() {
+    let toml = r#"
+[simulation]
+initial_state = "random"
+model = "heisenberg"
+equilibration_steps = 100
+measurement_steps = 1000
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [3, 3, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0]]
+strength = 1.0
+
+[anisotropy]
+axis = [[0.0, 0.0, 1.0]]
+strength = [0.3]
+
+[output]
+energy = true
+"#;
+    let config = Config::new(toml).unwrap();
+    let mut grid: Grid<HeisenbergSpin, SmallRng> =
+        Grid::new(&config, SmallRng::seed_from_u64(3)).unwrap();
+    let unit = grid.energy_terms();
+    assert!((grid.unscaled_anisotropy_energy() - unit.anisotropy).abs() < 1e-10);
+
+    for scale in [2.5, 0.0, -1.0] {
+        grid.set_anisotropy_scale(scale);
+        let terms = grid.energy_terms();
+        assert!((terms.exchange - unit.exchange).abs() < 1e-10);
+        assert!((terms.anisotropy - scale * unit.anisotropy).abs() < 1e-10);
+        assert!((grid.energy() - grid.total_energy()).abs() < 1e-10);
+        assert!((grid.unscaled_anisotropy_energy() - unit.anisotropy).abs() < 1e-10);
+    }
+}
+
+#[test]
+fn grid_set_spin_updates_running_totals_and_resync_restores_them() {
+    let toml = r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [2, 2, 1]
+sublattices = 2
+spin_magnitudes = [1.0, 1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 1
+offsets = [[0, 0, 0]]
+strength = 1.0
+
+[[exchange]]
+from_sublattice = 1
+to_sublattice = 0
+offsets = [[0, 0, 0]]
+strength = 1.0
+
+[output]
+energy = true
+group = [[1], [0, 1]]
+"#;
+    let config = Config::new(toml).unwrap();
+    let mut grid: Grid<IsingSpin, SmallRng> =
+        Grid::new(&config, SmallRng::seed_from_u64(0)).unwrap();
+    assert!((grid.energy() + 4.0).abs() < 1e-12);
+    assert_eq!(grid.spin_sum(), [0.0, 0.0, 8.0]);
+
+    grid.set_spin(5, IsingSpin::along_z(-1.0).unwrap());
+    assert_eq!(grid.spin_sum(), [0.0, 0.0, 6.0]);
+    assert_eq!(grid.group_spin_sum(0), [0.0, 0.0, 2.0]);
+    assert_eq!(grid.group_spin_sum(1), [0.0, 0.0, 6.0]);
+    // The energy change is left to the caller.
+    assert!((grid.energy() + 4.0).abs() < 1e-12);
+    grid.resync();
+    assert!((grid.energy() + 2.0).abs() < 1e-12);
+    assert_eq!(grid.totals.updates, 0);
+}
+
+fn colored_grid(dimensions: &str, offsets: &str) -> Grid<IsingSpin, SmallRng> {
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "checkerboard"
+
+[grid]
+dimensions = {dimensions}
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = {offsets}
+strength = 1.0
+
+[output]
+energy = true
+"#
+    );
+    Grid::new(&Config::new(&toml).unwrap(), SmallRng::seed_from_u64(0)).unwrap()
+}
+
+fn assert_proper_coloring(grid: &Grid<IsingSpin, SmallRng>) {
+    let mut color_of = vec![usize::MAX; grid.size];
+    for (color, sites) in grid.neighbors.colors().iter().enumerate() {
+        for &site in sites {
+            assert_eq!(color_of[site], usize::MAX, "site {site} colored twice");
+            color_of[site] = color;
+        }
+    }
+    assert!(color_of.iter().all(|c| *c != usize::MAX));
+    for site in 0..grid.size {
+        for (neighbor, _) in grid.bonds(site).iter() {
+            assert!(neighbor == site || color_of[neighbor] != color_of[site]);
+        }
+    }
+}
+
+#[test]
+fn grid_colors_square_lattice_as_checkerboard() {
+    let grid = colored_grid(
+        "[4, 6, 1]",
+        "[[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]",
+    );
+    assert_eq!(grid.neighbors.colors().len(), 2);
+    assert_proper_coloring(&grid);
+}
+
+#[test]
+fn grid_colors_frustrated_lattices_properly() {
+    let triangular = colored_grid(
+        "[6, 6, 1]",
+        "[[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0], [1, -1, 0], [-1, 1, 0]]",
+    );
+    assert!(triangular.neighbors.colors().len() <= 4);
+    assert_proper_coloring(&triangular);
+
+    let odd_ring = colored_grid("[5, 1, 1]", "[[1, 0, 0], [-1, 0, 0]]");
+    assert_eq!(odd_ring.neighbors.colors().len(), 3);
+    assert_proper_coloring(&odd_ring);
+}
+
+#[test]
+fn grid_clone_is_independent_of_original() {
+    let config = minimal_config("");
+    let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, SmallRng::seed_from_u64(0)).unwrap();
+    let mut clone = grid.clone();
+    clone.set_spin(0, IsingSpin::along_z(-1.0).unwrap());
+    clone.resync();
+
+    assert_eq!(grid.spins[0].to_array(), [0.0, 0.0, 1.0]);
+    assert!((grid.total_energy() - grid.energy()).abs() < 1e-12);
+    // The clone's bonds read the clone's spins: flipping one site breaks two bonds.
+    assert!((clone.total_energy() - (grid.total_energy() + 2.0)).abs() < 1e-12);
+}
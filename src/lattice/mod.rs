@@ -1,6 +1,9 @@
+mod bonds;
 mod grid;
 mod neighbors;
 mod structure;
+pub use bonds::{Bonds, NeighborTable};
 pub use grid::Grid;
+pub(crate) use grid::safe_coord_to_index;
 pub use neighbors::Atoms;
 pub use structure::{FullStructure, Structure, StructureAtom, load_from_file};
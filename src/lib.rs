@@ -20,7 +20,7 @@ use tracing_subscriber::FmtSubscriber;
 #[cfg(feature = "python-extension")]
 #[pyfunction]
 fn run_from_py(content: &str) -> PyResult<()> {
-    let _ = ctrlc::set_handler(|| std::process::exit(2));
+    let _ = runner::install_interrupt_handler();
     let subscriber = FmtSubscriber::builder()
         .with_max_level(tracing::Level::INFO)
         .finish();
@@ -0,0 +1,138 @@
+use std::f64::consts::PI;
+
+use itertools::iproduct;
+
+use crate::config::Config;
+use crate::lattice::safe_coord_to_index;
+use crate::spin::SpinState;
+
+/// Elementary plaquettes (x, y) → (x+1, y) → (x+1, y+1) → (x, y+1) of the
+/// cell grid, one set per sublattice and z layer. The winding of the spin
+/// angle around a plaquette is +1 for a vortex and -1 for an antivortex.
+#[derive(Clone, Debug)]
+pub struct Plaquettes {
+    corners: Vec<[usize; 4]>,
+}
+
+impl Plaquettes {
+    pub fn new(config: &Config) -> Self {
+        let dim = config.grid.dimensions;
+        let sublattices = config.grid.sublattices;
+        let pbc = config.grid.periodic_boundary;
+        let corners = iproduct!(0..sublattices, 0..dim[0], 0..dim[1], 0..dim[2])
+            .filter_map(|(sub, x, y, z)| {
+                let site = |dx: isize, dy: isize| {
+                    safe_coord_to_index(
+                        [x as isize + dx, y as isize + dy, z as isize],
+                        sub,
+                        dim,
+                        sublattices,
+                        pbc,
+                    )
+                };
+                Some([site(0, 0)?, site(1, 0)?, site(1, 1)?, site(0, 1)?])
+            })
+            .collect();
+        Self { corners }
+    }
+
+    pub fn len(&self) -> usize {
+        self.corners.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.corners.is_empty()
+    }
+
+    /// Number of vortices plus antivortices, Σ_p |w_p|.
+    pub fn vortices<S: SpinState>(&self, spins: &[S]) -> usize {
+        let angles: Vec<f64> = spins
+            .iter()
+            .map(|spin| {
+                let [x, y, _] = spin.to_array();
+                y.atan2(x)
+            })
+            .collect();
+        self.corners
+            .iter()
+            .filter(|corners| {
+                let winding: f64 = (0..4)
+                    .map(|k| wrap(angles[corners[(k + 1) % 4]] - angles[corners[k]]))
+                    .sum();
+                winding.abs() > PI
+            })
+            .count()
+    }
+}
+
+/// Angle difference wrapped into [-π, π).
+fn wrap(delta: f64) -> f64 {
+    (delta + PI).rem_euclid(2.0 * PI) - PI
+}
+
+#[derive(Clone, Debug)]
+struct Bond {
+    from: usize,
+    to: usize,
+    strength: f64,
+    offset: [f64; 3],
+}
+
+/// Response of the energy to a uniform twist φ of the spin angle per cell
+/// along each periodic axis. A twist applied through the cell offsets of the
+/// bonds differs from one applied through the site positions only by a
+/// gauge transformation, so the helicity modulus does not depend on the
+/// positions of the sublattices.
+#[derive(Clone, Debug)]
+pub struct Helicity {
+    pub axes: Vec<usize>,
+    bonds: Vec<Bond>, // every exchange bond in both directions, as in the Hamiltonian
+}
+
+impl Helicity {
+    pub fn new(config: &Config, axes: Vec<usize>) -> Self {
+        let dim = config.grid.dimensions;
+        let sublattices = config.grid.sublattices;
+        let pbc = config.grid.periodic_boundary;
+        let mut bonds = Vec::new();
+        for (x, y, z) in iproduct!(0..dim[0], 0..dim[1], 0..dim[2]) {
+            let coord = [x as isize, y as isize, z as isize];
+            for exchange in &config.parsed_exchange {
+                let from = safe_coord_to_index(coord, exchange.from_sub, dim, sublattices, pbc);
+                let to_coord = std::array::from_fn(|k| coord[k] + exchange.offset[k]);
+                let to = safe_coord_to_index(to_coord, exchange.to_sub, dim, sublattices, pbc);
+                if let (Some(from), Some(to)) = (from, to) {
+                    bonds.push(Bond {
+                        from,
+                        to,
+                        strength: exchange.strength,
+                        offset: exchange.offset.map(|d| d as f64),
+                    });
+                }
+            }
+        }
+        Self { axes, bonds }
+    }
+
+    /// (∂²E/∂φ², ∂E/∂φ) at φ = 0 for each axis, with
+    /// E(φ) = -½ Σ_ij J_ij |S_i||S_j| cos(θ_i - θ_j - φ d_ij).
+    pub fn derivatives<S: SpinState>(&self, spins: &[S]) -> Vec<[f64; 2]> {
+        let mut derivatives = vec![[0.0; 2]; self.axes.len()];
+        for bond in &self.bonds {
+            let [xi, yi, _] = spins[bond.from].to_array();
+            let [xj, yj, _] = spins[bond.to].to_array();
+            let cos = xi * xj + yi * yj; // |S_i||S_j| cos(θ_i - θ_j)
+            let sin = yi * xj - xi * yj; // |S_i||S_j| sin(θ_i - θ_j)
+            for (derivative, &axis) in derivatives.iter_mut().zip(&self.axes) {
+                let d = bond.offset[axis];
+                derivative[0] += 0.5 * bond.strength * d * d * cos;
+                derivative[1] -= 0.5 * bond.strength * d * sin;
+            }
+        }
+        derivatives
+    }
+}
+
+#[cfg(test)]
+#[path = "bkt_tests.rs"]
+mod tests;
@@ -0,0 +1,94 @@
+use super::*;
+use crate::spin::{XYSpin, tolerance};
+
+const L: usize = 6;
+
+fn xy_config(periodic_boundary: &str) -> Config {
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "x"
+model = "xy"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [{L}, {L}, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = {periodic_boundary}
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+"#
+    );
+    Config::new(&toml).unwrap()
+}
+
+fn spin(theta: f64) -> XYSpin {
+    XYSpin::along_x(theta.cos()).unwrap() + XYSpin::along_y(theta.sin()).unwrap()
+}
+
+/// θ(x, y) from a function of the cell coordinates, index = L·x + y.
+fn spins(theta: impl Fn(f64, f64) -> f64) -> Vec<XYSpin> {
+    iproduct!(0..L, 0..L)
+        .map(|(x, y)| spin(theta(x as f64, y as f64)))
+        .collect()
+}
+
+#[test]
+fn plaquettes_cover_the_grid() {
+    assert_eq!(
+        Plaquettes::new(&xy_config("[true, true, false]")).len(),
+        L * L
+    );
+    assert_eq!(
+        Plaquettes::new(&xy_config("[false, false, false]")).len(),
+        (L - 1) * (L - 1)
+    );
+}
+
+#[test]
+fn ordered_state_has_no_vortices() {
+    let plaquettes = Plaquettes::new(&xy_config("[true, true, false]"));
+    assert_eq!(plaquettes.vortices(&spins(|_, _| 0.3)), 0);
+}
+
+#[test]
+fn single_vortex_and_antivortex_are_counted_once() {
+    let plaquettes = Plaquettes::new(&xy_config("[false, false, false]"));
+    let centre = 2.5;
+    let vortex = spins(|x, y| (y - centre).atan2(x - centre));
+    let antivortex = spins(|x, y| -(y - centre).atan2(x - centre));
+    assert_eq!(plaquettes.vortices(&vortex), 1);
+    assert_eq!(plaquettes.vortices(&antivortex), 1);
+}
+
+#[test]
+fn helicity_derivatives_of_a_twisted_state() {
+    let helicity = Helicity::new(&xy_config("[true, true, false]"), vec![0, 1]);
+    let delta = 2.0 * PI / L as f64;
+    let derivatives = helicity.derivatives(&spins(|x, _| delta * x));
+    let n = (L * L) as f64;
+    // Along x every bond is twisted by δ; along y the state is uniform.
+    assert!((derivatives[0][0] - n * delta.cos()).abs() < n * tolerance(1e-9));
+    assert!((derivatives[0][1] - n * delta.sin()).abs() < n * tolerance(1e-9));
+    assert!((derivatives[1][0] - n).abs() < n * tolerance(1e-9));
+    assert!(derivatives[1][1].abs() < n * tolerance(1e-9));
+}
+
+#[test]
+fn wrap_maps_into_half_open_interval() {
+    assert!((wrap(1.5 * PI) + 0.5 * PI).abs() < 1e-12);
+    assert!((wrap(-1.5 * PI) - 0.5 * PI).abs() < 1e-12);
+    assert!((wrap(0.25) - 0.25).abs() < 1e-12);
+}
@@ -0,0 +1,87 @@
+use crate::lattice::NeighborTable;
+use crate::spin::{SpinState, widen};
+use rand::{RngExt, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+use rayon::prelude::*;
+use tracing::debug;
+
+use super::MonteCarlo;
+use super::metropolis::accepts_metropolis_move;
+
+/// Sites handed to one RNG stream. Fixed, so a seeded run does not depend on
+/// the number of threads.
+const CHUNK_SIZE: usize = 1024;
+
+/// Metropolis sweeping one color of the bond graph at a time, updating the
+/// sites of a color in parallel. Every chunk of sites draws from its own
+/// stream, seeded from `rng`.
+pub struct CheckerboardMetropolis<R: rand::Rng> {
+    pub rng: R,
+    pub beta: f64,
+    colors: Vec<Vec<usize>>, // see NeighborTable::colors
+}
+
+impl<R: rand::Rng> CheckerboardMetropolis<R> {
+    pub fn new(rng: R, beta: f64, neighbors: &NeighborTable) -> Self {
+        let colors = neighbors.colors();
+        debug!("exchange graph colored with {} colors", colors.len());
+        Self { rng, beta, colors }
+    }
+}
+
+impl<S: SpinState, R: rand::Rng> MonteCarlo<S, R> for CheckerboardMetropolis<R> {
+    fn step(&mut self, grid: &mut crate::lattice::Grid<S, R>) -> usize {
+        let beta = self.beta;
+        for sites in &self.colors {
+            let seeds: Vec<u64> = (0..sites.len().div_ceil(CHUNK_SIZE))
+                .map(|_| self.rng.random())
+                .collect();
+            let (spins, calc_inputs, neighbors, hamiltonian) = (
+                &grid.spins,
+                &grid.calc_inputs,
+                &grid.neighbors,
+                &grid.hamiltonian,
+            );
+
+            // Sites of one color share no bond, so every proposal sees the
+            // same neighbors whether the moves are applied now or afterwards.
+            let accepted: Vec<(usize, S, f64)> = sites
+                .par_chunks(CHUNK_SIZE)
+                .zip(seeds.par_iter())
+                .flat_map_iter(|(chunk, seed)| {
+                    let mut rng = Pcg64Mcg::seed_from_u64(*seed);
+                    chunk
+                        .iter()
+                        .filter_map(|&i| {
+                            let proposed_spin =
+                                spins[i].perturb(&mut rng, widen(calc_inputs[i].magnitude));
+                            let delta_e = proposed_spin.energy_diff(
+                                &calc_inputs[i],
+                                neighbors.bonds(i),
+                                hamiltonian,
+                                spins,
+                                &spins[i],
+                            );
+                            accepts_metropolis_move(delta_e, beta, &mut rng).then_some((
+                                i,
+                                proposed_spin,
+                                delta_e,
+                            ))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+
+            for (i, spin, delta_e) in accepted {
+                grid.set_spin(i, spin);
+                grid.add_energy(delta_e);
+            }
+        }
+        grid.resync_if_due();
+        grid.size
+    }
+}
+
+#[cfg(test)]
+#[path = "checkerboard_tests.rs"]
+mod tests;
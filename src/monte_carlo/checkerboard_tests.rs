@@ -0,0 +1,106 @@
+use super::*;
+use crate::config::Config;
+use crate::lattice::Grid;
+use crate::spin::{HeisenbergSpin, IsingSpin, tolerance};
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+fn config(model: &str, dimensions: &str) -> Config {
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "{model}"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "checkerboard"
+
+[grid]
+dimensions = {dimensions}
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]
+strength = 1.0
+
+[anisotropy]
+axis = [[0.0, 0.0, 1.0]]
+strength = [0.2]
+
+[output]
+energy = true
+group = [[0]]
+"#
+    );
+    Config::new(&toml).unwrap()
+}
+
+#[test]
+fn checkerboard_zero_beta_accepts_all() {
+    let mut grid: Grid<IsingSpin, SmallRng> =
+        Grid::new(&config("ising", "[4, 4, 1]"), SmallRng::seed_from_u64(0)).unwrap();
+    let mut mc = CheckerboardMetropolis::new(SmallRng::seed_from_u64(1), 0.0, &grid.neighbors);
+    assert_eq!(mc.step(&mut grid), 16);
+    assert!(grid.spins.iter().all(|s| s.to_array()[2] < 0.0));
+}
+
+#[test]
+fn checkerboard_zero_temperature_preserves_ground_state() {
+    let mut grid: Grid<IsingSpin, SmallRng> =
+        Grid::new(&config("ising", "[4, 4, 1]"), SmallRng::seed_from_u64(0)).unwrap();
+    let mut mc =
+        CheckerboardMetropolis::new(SmallRng::seed_from_u64(1), f64::INFINITY, &grid.neighbors);
+    mc.step(&mut grid);
+    assert!(grid.spins.iter().all(|s| s.to_array()[2] > 0.0));
+}
+
+#[test]
+fn checkerboard_running_totals_match_full_recompute() {
+    let mut grid: Grid<HeisenbergSpin, SmallRng> = Grid::new(
+        &config("heisenberg", "[6, 5, 1]"),
+        SmallRng::seed_from_u64(2),
+    )
+    .unwrap();
+    let mut mc = CheckerboardMetropolis::new(SmallRng::seed_from_u64(3), 0.5, &grid.neighbors);
+    for _ in 0..20 {
+        mc.step(&mut grid);
+    }
+    // Energy deltas accumulate rounding over every accepted move.
+    let drift = (grid.energy() - grid.total_energy()).abs();
+    assert!(drift < grid.size as f64 * tolerance(1e-9), "{drift}");
+    let m = grid.spin_sum();
+    let expected = grid.total_spin_vector();
+    for k in 0..3 {
+        assert!((m[k] - expected[k]).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn checkerboard_is_independent_of_thread_count() {
+    let run = |threads: usize| {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            let mut grid: Grid<HeisenbergSpin, SmallRng> = Grid::new(
+                &config("heisenberg", "[64, 64, 1]"),
+                SmallRng::seed_from_u64(4),
+            )
+            .unwrap();
+            let mut mc =
+                CheckerboardMetropolis::new(SmallRng::seed_from_u64(5), 1.0, &grid.neighbors);
+            for _ in 0..3 {
+                mc.step(&mut grid);
+            }
+            grid.spins.iter().map(|s| s.to_array()).collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(run(1), run(4));
+}
@@ -0,0 +1,251 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use itertools::iproduct;
+
+use crate::config::Config;
+use crate::lattice::{Atoms, safe_coord_to_index};
+use crate::spin::SpinState;
+
+/// One distance shell of G(r): every pair of sites (i on sublattice `from`,
+/// j on sublattice `to`) whose separation is the `order`-th smallest between
+/// the two sublattices.
+#[derive(Debug)]
+pub struct Shell {
+    pub from: usize,
+    pub to: usize,
+    pub order: usize,
+    pub distance: f64,
+    pairs: Vec<(usize, usize)>,
+}
+
+/// Real-space spin–spin correlation function G_ab(r) = <S_i · S_j>, averaged
+/// over the pairs of each shell, for every sublattice pair a <= b. Shells are
+/// found from the `[structure]` section like `neighbor_order` exchanges.
+#[derive(Debug)]
+pub struct PairCorrelation {
+    pub shells: Vec<Shell>,
+}
+
+impl PairCorrelation {
+    pub fn new(config: &Config, num_shells: usize) -> anyhow::Result<Self> {
+        let Some(structure) = config.structure.as_ref().map(|s| s.parse()).transpose()? else {
+            anyhow::bail!("correlation requires a [structure] section to find distance shells");
+        };
+        let pbc = config.grid.periodic_boundary;
+        let dim = config.grid.dimensions;
+        let sublattices = config.grid.sublattices;
+        let atoms = Atoms {
+            cell: structure.cell,
+            positions: structure.positions.clone(),
+            pbc,
+            tolerance: structure.tolerance.unwrap_or(0.0001),
+        };
+
+        let mut shells = Vec::new();
+        for (from, to) in iproduct!(0..sublattices, 0..sublattices) {
+            if from > to {
+                continue;
+            }
+            for order in 1..=num_shells {
+                let neighbors = atoms.find_neighbors_from_to(from, to, order);
+                let Some(first) = neighbors.first() else {
+                    break;
+                };
+                let separation: Vec<f64> = (0..3)
+                    .map(|k| {
+                        structure.positions[to][k] - structure.positions[from][k]
+                            + (0..3)
+                                .map(|axis| first.offset[axis] as f64 * structure.cell[axis][k])
+                                .sum::<f64>()
+                    })
+                    .collect();
+                let distance = separation.iter().map(|x| x * x).sum::<f64>().sqrt();
+
+                let mut pairs = Vec::new();
+                for (x, y, z) in iproduct!(0..dim[0], 0..dim[1], 0..dim[2]) {
+                    let coord = [x as isize, y as isize, z as isize];
+                    let Some(i) = safe_coord_to_index(coord, from, dim, sublattices, pbc) else {
+                        continue;
+                    };
+                    for neighbor in &neighbors {
+                        let offset_coord = [
+                            coord[0] + neighbor.offset[0],
+                            coord[1] + neighbor.offset[1],
+                            coord[2] + neighbor.offset[2],
+                        ];
+                        if let Some(j) =
+                            safe_coord_to_index(offset_coord, to, dim, sublattices, pbc)
+                        {
+                            pairs.push((i, j));
+                        }
+                    }
+                }
+                shells.push(Shell {
+                    from,
+                    to,
+                    order,
+                    distance,
+                    pairs,
+                });
+            }
+        }
+        Ok(Self { shells })
+    }
+
+    /// Comment lines describing the shells, then the column header.
+    pub fn header(&self) -> String {
+        let mut header = String::new();
+        for shell in &self.shells {
+            header.push_str(&format!(
+                "# G_{{{},{}}}^{{({})}}: r = {:.8}, {} pairs\n",
+                shell.from,
+                shell.to,
+                shell.order,
+                shell.distance,
+                shell.pairs.len()
+            ));
+        }
+        header.push_str(&format!("{:<12}", "#T(K)"));
+        for shell in &self.shells {
+            let label = format!("G_{{{},{}}}^{{({})}}", shell.from, shell.to, shell.order);
+            header.push_str(&format!("\t{label:<12}"));
+        }
+        header
+    }
+}
+
+/// Running sums of G(r) over the measurements of one temperature.
+#[derive(Debug)]
+pub struct CorrelationAccumulator {
+    pub correlation: Arc<PairCorrelation>,
+    pub sums: Vec<f64>,
+}
+
+impl CorrelationAccumulator {
+    pub fn new(correlation: Arc<PairCorrelation>) -> Self {
+        let sums = vec![0.0; correlation.shells.len()];
+        Self { correlation, sums }
+    }
+
+    pub fn record<S: SpinState>(&mut self, spins: &[S]) {
+        for (sum, shell) in self.sums.iter_mut().zip(&self.correlation.shells) {
+            if shell.pairs.is_empty() {
+                continue;
+            }
+            *sum += shell
+                .pairs
+                .iter()
+                .map(|&(i, j)| spins[i].dot(&spins[j]))
+                .sum::<f64>()
+                / shell.pairs.len() as f64;
+        }
+    }
+
+    /// G per shell over `steps` measurements; NaN for shells without pairs.
+    pub fn averages(&self, steps: usize) -> Vec<f64> {
+        self.sums
+            .iter()
+            .zip(&self.correlation.shells)
+            .map(|(sum, shell)| {
+                if shell.pairs.is_empty() {
+                    f64::NAN
+                } else {
+                    sum / steps as f64
+                }
+            })
+            .collect()
+    }
+}
+
+/// Fourier amplitude of the spins at the smallest nonzero wavevector,
+/// q = 2π/L along each periodic axis of the largest periodic length L. Sites
+/// are placed at their cell coordinates, so ξ comes out in cell units.
+#[derive(Clone, Debug)]
+pub struct StructureFactor {
+    pub length: usize,
+    dim: [usize; 3],
+    axes: Vec<usize>,
+    phases: Vec<(f64, f64)>, // (cos, sin) of 2πk / L for k < L
+}
+
+impl StructureFactor {
+    pub fn new(dim: [usize; 3], pbc: [bool; 3]) -> Option<Self> {
+        let length = (0..3)
+            .filter(|&axis| pbc[axis])
+            .map(|axis| dim[axis])
+            .max()
+            .filter(|&length| length > 1)?;
+        let axes = (0..3)
+            .filter(|&axis| pbc[axis] && dim[axis] == length)
+            .collect();
+        let phases = (0..length)
+            .map(|k| {
+                let angle = 2.0 * PI * k as f64 / length as f64;
+                (angle.cos(), angle.sin())
+            })
+            .collect();
+        Some(Self {
+            length,
+            dim,
+            axes,
+            phases,
+        })
+    }
+
+    /// |M(q_min)|² = |Σ_i S_i e^{i q·r_i}|², averaged over the axes.
+    pub fn min_q<S: SpinState>(&self, spins: &[S]) -> f64 {
+        self.axes
+            .iter()
+            .map(|&axis| {
+                let (mut re, mut im) = ([0.0; 3], [0.0; 3]);
+                for (index, spin) in spins.iter().enumerate() {
+                    let (cos, sin) = self.phase(index, axis);
+                    for (k, s) in spin.to_array().into_iter().enumerate() {
+                        re[k] += s * cos;
+                        im[k] += s * sin;
+                    }
+                }
+                (0..3).map(|k| re[k] * re[k] + im[k] * im[k]).sum::<f64>()
+            })
+            .sum::<f64>()
+            / self.axes.len() as f64
+    }
+
+    /// |Σ_{i∈C} (S_i·r) e^{i q·r_i}|² over the sites of one cluster, for the
+    /// projections `(site, S_i·r)` onto its reflection axis r.
+    pub fn min_q_projected(&self, projections: impl Iterator<Item = (usize, f64)> + Clone) -> f64 {
+        self.axes
+            .iter()
+            .map(|&axis| {
+                let (mut re, mut im) = (0.0, 0.0);
+                for (index, p) in projections.clone() {
+                    let (cos, sin) = self.phase(index, axis);
+                    re += p * cos;
+                    im += p * sin;
+                }
+                re * re + im * im
+            })
+            .sum::<f64>()
+            / self.axes.len() as f64
+    }
+
+    fn phase(&self, index: usize, axis: usize) -> (f64, f64) {
+        let cell = index % (self.dim[0] * self.dim[1] * self.dim[2]);
+        let coord = match axis {
+            0 => cell / (self.dim[1] * self.dim[2]),
+            1 => cell / self.dim[2] % self.dim[1],
+            _ => cell % self.dim[2],
+        };
+        self.phases[coord]
+    }
+
+    /// Second-moment correlation length from <|M(0)|²> and <|M(q_min)|²>.
+    pub fn correlation_length(&self, s0: f64, s_q: f64) -> f64 {
+        (s0 / s_q - 1.0).sqrt() / (2.0 * (PI / self.length as f64).sin())
+    }
+}
+
+#[cfg(test)]
+#[path = "correlation_tests.rs"]
+mod tests;
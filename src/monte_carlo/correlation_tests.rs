@@ -0,0 +1,127 @@
+use super::*;
+use crate::spin::IsingSpin;
+
+fn square_lattice_config(correlation_length: bool) -> Config {
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[structure]
+cell = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 10.0]]
+positions = [[0.0, 0.0, 0.0]]
+
+[[exchange]]
+neighbor_order = 1
+strength = 1.0
+
+[output]
+energy = true
+correlation_length = {correlation_length}
+
+[correlation]
+shells = 2
+"#
+    );
+    Config::new(&toml).unwrap()
+}
+
+/// Néel pattern on the 4x4 square lattice, index = 4x + y.
+fn neel_spins() -> Vec<IsingSpin> {
+    let up = IsingSpin::along_z(1.0).unwrap();
+    (0..16)
+        .map(|i| if (i / 4 + i % 4) % 2 == 0 { up } else { -up })
+        .collect()
+}
+
+#[test]
+fn shells_follow_square_lattice_distances() {
+    let correlation = PairCorrelation::new(&square_lattice_config(false), 2).unwrap();
+    assert_eq!(correlation.shells.len(), 2);
+    assert!((correlation.shells[0].distance - 1.0).abs() < 1e-12);
+    assert!((correlation.shells[1].distance - 2f64.sqrt()).abs() < 1e-12);
+    // Four neighbours per site in both shells.
+    assert_eq!(correlation.shells[0].pairs.len(), 64);
+    assert_eq!(correlation.shells[1].pairs.len(), 64);
+}
+
+#[test]
+fn accumulator_averages_dot_products_per_shell() {
+    let correlation = Arc::new(PairCorrelation::new(&square_lattice_config(false), 2).unwrap());
+    let mut accumulator = CorrelationAccumulator::new(correlation);
+    let up = IsingSpin::along_z(1.0).unwrap();
+    accumulator.record(&[up; 16]);
+    accumulator.record(&neel_spins());
+    let averages = accumulator.averages(2);
+    assert!((averages[0] - 0.0).abs() < 1e-12); // (1 + -1) / 2
+    assert!((averages[1] - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn header_lists_shells_and_columns() {
+    let correlation = PairCorrelation::new(&square_lattice_config(false), 2).unwrap();
+    let header = correlation.header();
+    let lines: Vec<&str> = header.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("# G_{0,0}^{(1)}: r = 1.00000000, 64 pairs"));
+    assert!(lines[2].starts_with("#T(K)"));
+    assert_eq!(lines[2].split('\t').count(), 3);
+}
+
+#[test]
+fn structure_factor_uses_longest_periodic_axes() {
+    let sf = StructureFactor::new([8, 8, 2], [true, true, true]).unwrap();
+    assert_eq!(sf.length, 8);
+    assert_eq!(sf.axes, vec![0, 1]);
+
+    let sf = StructureFactor::new([4, 6, 8], [true, true, false]).unwrap();
+    assert_eq!(sf.length, 6);
+    assert_eq!(sf.axes, vec![1]);
+
+    assert!(StructureFactor::new([4, 4, 1], [false, false, true]).is_none());
+}
+
+#[test]
+fn structure_factor_of_uniform_and_single_spin() {
+    let sf = StructureFactor::new([4, 4, 1], [true, true, false]).unwrap();
+    let up = IsingSpin::along_z(1.0).unwrap();
+    assert!(sf.min_q(&[up; 16]) < 1e-20);
+
+    let mut single = [IsingSpin::zero(); 16];
+    single[5] = up;
+    assert!((sf.min_q(&single) - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn projected_structure_factor_matches_min_q_on_ising_spins() {
+    let sf = StructureFactor::new([4, 4, 1], [true, true, false]).unwrap();
+    let spins: Vec<IsingSpin> = (0..16)
+        .map(|i| IsingSpin::along_z(if i % 3 == 0 { -1.0 } else { 1.0 }).unwrap())
+        .collect();
+    let projections = spins.iter().map(|s| s.to_array()[2]).enumerate();
+    assert!((sf.min_q_projected(projections) - sf.min_q(&spins)).abs() < 1e-12);
+    assert!((sf.min_q_projected([(5, 2.0)].into_iter()) - 4.0).abs() < 1e-12);
+}
+
+#[test]
+fn correlation_length_inverts_the_ornstein_zernike_ratio() {
+    let sf = StructureFactor::new([16, 16, 1], [true, true, false]).unwrap();
+    let xi = 3.0;
+    let s_q = 1.0;
+    let s0 = s_q * (1.0 + (2.0 * (PI / 16.0).sin() * xi).powi(2));
+    assert!((sf.correlation_length(s0, s_q) - xi).abs() < 1e-12);
+    // No correlations at all: S(0) = S(q).
+    assert_eq!(sf.correlation_length(2.0, 2.0), 0.0);
+}
@@ -0,0 +1,95 @@
+use crate::lattice::Grid;
+use crate::spin::{SpinState, norm_sqr};
+
+/// Batch length of the MSER-5 rule.
+const MSER_BATCH: usize = 5;
+/// Sweeps before the first check, and the minimum spacing between checks.
+const MIN_CHECK_SWEEPS: usize = 100;
+
+/// Watches E and |M| during equilibration and reports when both look
+/// stationary by the MSER-5 rule: the truncation point minimising the
+/// marginal standard error of the batch means lies in the first half of the
+/// series. Checks are spaced geometrically, so the overhead is O(n) overall
+/// and at most a quarter of the detected length is spent past the point
+/// where the series became stationary.
+#[derive(Debug)]
+pub struct EquilibrationDetector {
+    energy: Vec<f64>,
+    m_abs: Vec<f64>,
+    next_check: usize,
+}
+
+impl Default for EquilibrationDetector {
+    fn default() -> Self {
+        Self {
+            energy: Vec::new(),
+            m_abs: Vec::new(),
+            next_check: MIN_CHECK_SWEEPS,
+        }
+    }
+}
+
+impl EquilibrationDetector {
+    pub fn record<S: SpinState, R: rand::Rng>(&mut self, grid: &Grid<S, R>) {
+        self.push(grid.energy(), norm_sqr(grid.spin_sum()).sqrt());
+    }
+
+    pub fn push(&mut self, energy: f64, m_abs: f64) {
+        self.energy.push(energy);
+        self.m_abs.push(m_abs);
+    }
+
+    pub fn len(&self) -> usize {
+        self.energy.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.energy.is_empty()
+    }
+
+    /// Whether both series are stationary. Only evaluated once the next
+    /// scheduled check is reached; returns false in between.
+    pub fn is_stationary(&mut self) -> bool {
+        let n = self.len();
+        if n < self.next_check {
+            return false;
+        }
+        self.next_check = (n + MIN_CHECK_SWEEPS).max(n + n / 4);
+        mser_is_stationary(&self.energy) && mser_is_stationary(&self.m_abs)
+    }
+}
+
+/// MSER-5: with batch means x_1..x_m, pick d minimising
+/// Σ_{i>d} (x_i - x̄_d)² / (m - d)²; the series is stationary if d < m / 2.
+fn mser_is_stationary(series: &[f64]) -> bool {
+    let batches: Vec<f64> = series
+        .chunks_exact(MSER_BATCH)
+        .map(|chunk| chunk.iter().sum::<f64>() / MSER_BATCH as f64)
+        .collect();
+    let m = batches.len();
+    if m < 2 {
+        return false;
+    }
+
+    // Suffix sums give mean and variance of batches[d..] for every d.
+    let (mut sum, mut sum_sq) = (0.0, 0.0);
+    let mut best = (f64::INFINITY, m);
+    for d in (0..m).rev() {
+        sum += batches[d];
+        sum_sq += batches[d] * batches[d];
+        if d > m / 2 {
+            continue;
+        }
+        let len = (m - d) as f64;
+        let sse = (sum_sq - sum * sum / len).max(0.0);
+        let mser = sse / (len * len);
+        if mser <= best.0 {
+            best = (mser, d);
+        }
+    }
+    best.1 < m / 2
+}
+
+#[cfg(test)]
+#[path = "equilibration_tests.rs"]
+mod tests;
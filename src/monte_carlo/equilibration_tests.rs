@@ -0,0 +1,73 @@
+use super::*;
+use rand::RngExt;
+use rand::SeedableRng;
+use rand_pcg::Pcg64Mcg;
+
+fn noise(rng: &mut Pcg64Mcg) -> f64 {
+    rng.random::<f64>() - 0.5
+}
+
+#[test]
+fn stationary_noise_is_detected_at_first_check() {
+    let mut rng = Pcg64Mcg::seed_from_u64(1);
+    let mut detector = EquilibrationDetector::default();
+    for _ in 0..MIN_CHECK_SWEEPS - 1 {
+        detector.push(noise(&mut rng), 1.0 + noise(&mut rng));
+        assert!(!detector.is_stationary());
+    }
+    detector.push(noise(&mut rng), 1.0 + noise(&mut rng));
+    assert!(detector.is_stationary());
+}
+
+#[test]
+fn relaxing_series_is_stationary_only_after_relaxation() {
+    let mut rng = Pcg64Mcg::seed_from_u64(2);
+    let mut detector = EquilibrationDetector::default();
+    let mut detected = None;
+    for i in 0..20_000 {
+        // Exponential relaxation with a time constant of 1000 sweeps.
+        let drift = 100.0 * (-(i as f64) / 1000.0).exp();
+        detector.push(drift + noise(&mut rng), 0.5 + noise(&mut rng));
+        if detector.is_stationary() {
+            detected = Some(detector.len());
+            break;
+        }
+    }
+    let detected = detected.expect("relaxed series should become stationary");
+    assert!(detected > 3000, "detected too early: {detected}");
+    assert!(detected < 15_000, "detected too late: {detected}");
+}
+
+#[test]
+fn linear_trend_is_never_stationary() {
+    let mut detector = EquilibrationDetector::default();
+    for i in 0..5000 {
+        detector.push(-(i as f64), 0.0);
+        assert!(!detector.is_stationary(), "at {i}");
+    }
+}
+
+#[test]
+fn frozen_series_is_stationary() {
+    let mut detector = EquilibrationDetector::default();
+    for _ in 0..MIN_CHECK_SWEEPS {
+        detector.push(-2.0, 4.0);
+    }
+    assert!(detector.is_stationary());
+}
+
+#[test]
+fn checks_are_spaced_geometrically() {
+    let mut detector = EquilibrationDetector::default();
+    let mut checks = Vec::new();
+    for i in 1..=2000 {
+        detector.push(-(i as f64), 0.0);
+        let before = detector.next_check;
+        detector.is_stationary();
+        if detector.next_check != before {
+            checks.push(i);
+        }
+    }
+    assert_eq!(&checks[..4], &[100, 200, 300, 400]);
+    assert!(checks.windows(2).all(|w| w[1] >= w[0] + w[0] / 4));
+}
@@ -44,16 +44,26 @@ fn any_mc_beta_set_beta() {
     });
     assert!((mc.beta() - 2.0).abs() < 1e-10);
 
-    let mut mc = AnyMC::Wolff(Wolff {
-        rng: SmallRng::seed_from_u64(0),
-        beta: 3.0,
-        ham_config: HamiltonianConfig {
+    let mut grid = single_spin_grid();
+    let mut mc = AnyMC::Checkerboard(CheckerboardMetropolis::new(
+        SmallRng::seed_from_u64(0),
+        1.5,
+        &grid.neighbors,
+    ));
+    mc.set_beta(2.5);
+    assert_eq!(mc.step(&mut grid), 1);
+    assert!((mc.beta() - 2.5).abs() < 1e-10);
+
+    let mut mc = AnyMC::Wolff(Wolff::new(
+        SmallRng::seed_from_u64(0),
+        3.0,
+        HamiltonianConfig {
             exchange_enable: true,
             anisotropy_enable: false,
             zeeman_enable: false,
             dm_enable: false,
         },
-    });
+    ));
     assert!((mc.beta() - 3.0).abs() < 1e-10);
     mc.set_beta(5.0);
     assert!((mc.beta() - 5.0).abs() < 1e-10);
@@ -74,16 +84,16 @@ fn any_mc_step_dispatches_metropolis() {
 #[test]
 fn any_mc_step_dispatches_wolff() {
     let mut grid = single_spin_grid();
-    let mut mc = AnyMC::Wolff(Wolff {
-        rng: SmallRng::seed_from_u64(0),
-        beta: 0.0,
-        ham_config: HamiltonianConfig {
+    let mut mc = AnyMC::Wolff(Wolff::new(
+        SmallRng::seed_from_u64(0),
+        0.0,
+        HamiltonianConfig {
             exchange_enable: true,
             anisotropy_enable: false,
             zeeman_enable: false,
             dm_enable: false,
         },
-    });
+    ));
 
     assert_eq!(mc.step(&mut grid), 1);
     assert_eq!(grid.spins[0].to_array(), [0.0, 0.0, -1.0]);
@@ -1,8 +1,8 @@
 use super::*;
 use crate::config::Config;
-use crate::lattice::Grid;
+use crate::lattice::{Grid, NeighborTable};
 use crate::monte_carlo::Metropolis;
-use crate::spin::IsingSpin;
+use crate::spin::{IsingSpin, tolerance};
 use rand::SeedableRng;
 use rand::rngs::SmallRng;
 
@@ -125,17 +125,27 @@ group = [[0]]
     assert_eq!(grid.spins[0].to_array(), [0.0, 0.0, -1.0]);
 }
 
+/// Keeps every bond but sets its coupling to zero.
+fn zero_exchange(grid: &mut Grid<IsingSpin, SmallRng>) {
+    let mut neighbors = NeighborTable::default();
+    for site in 0..grid.size {
+        let bonds = grid.bonds(site);
+        neighbors
+            .push_site(
+                bonds
+                    .iter()
+                    .zip(bonds.entries())
+                    .map(|((n, _), e)| (n, 0.0, e)),
+            )
+            .unwrap();
+    }
+    grid.neighbors = neighbors;
+}
+
 #[test]
 fn metropolis_zero_exchange_accepts_all_moves() {
     let mut grid = make_ferro_grid();
-    for calc_input in &mut grid.calc_inputs {
-        calc_input.exchanges.fill(0.0);
-        if let Some(neighbors) = &mut calc_input.exchange_neighbors {
-            for (_, j) in neighbors {
-                *j = 0.0;
-            }
-        }
-    }
+    zero_exchange(&mut grid);
     let spins_before: Vec<_> = grid.spins.iter().map(|s| s.to_array()[2]).collect();
     let mut mc = Metropolis {
         rng: SmallRng::seed_from_u64(1),
@@ -151,14 +161,7 @@ fn metropolis_zero_exchange_accepts_all_moves() {
 #[test]
 fn metropolis_zero_temperature_rejects_equal_energy_moves() {
     let mut grid = make_ferro_grid();
-    for calc_input in &mut grid.calc_inputs {
-        calc_input.exchanges.fill(0.0);
-        if let Some(neighbors) = &mut calc_input.exchange_neighbors {
-            for (_, j) in neighbors {
-                *j = 0.0;
-            }
-        }
-    }
+    zero_exchange(&mut grid);
     let spins_before: Vec<_> = grid.spins.iter().map(|s| s.to_array()[2]).collect();
     let mut mc = Metropolis {
         rng: SmallRng::seed_from_u64(1),
@@ -168,3 +171,68 @@ fn metropolis_zero_temperature_rejects_equal_energy_moves() {
     let spins_after: Vec<_> = grid.spins.iter().map(|s| s.to_array()[2]).collect();
     assert_eq!(spins_after, spins_before);
 }
+
+#[test]
+fn metropolis_running_totals_match_full_recompute() {
+    let toml = r#"
+[simulation]
+initial_state = "random"
+model = "heisenberg"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 2
+spin_magnitudes = [1.0, 1.5]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 1
+offsets = [[0, 0, 0], [-1, 0, 0]]
+strength = -1.0
+
+[[exchange]]
+from_sublattice = 1
+to_sublattice = 0
+offsets = [[0, 0, 0], [1, 0, 0]]
+strength = -1.0
+
+[anisotropy]
+axis = [[0.0, 0.0, 1.0], [0.0, 0.0, 1.0]]
+strength = [0.5, -0.5]
+
+[output]
+energy = true
+group = [[0], [0, 1]]
+"#;
+    let config = Config::new(toml).unwrap();
+    let mut grid: Grid<crate::spin::HeisenbergSpin, SmallRng> =
+        Grid::new(&config, SmallRng::seed_from_u64(3)).unwrap();
+    let mut mc = Metropolis {
+        rng: SmallRng::seed_from_u64(4),
+        beta: 0.7,
+    };
+    for _ in 0..20 {
+        mc.step(&mut grid);
+    }
+    // Energy deltas accumulate rounding over every accepted move.
+    let drift = (grid.energy() - grid.total_energy()).abs();
+    assert!(drift < grid.size as f64 * tolerance(1e-9), "{drift}");
+    let m = grid.spin_sum();
+    let expected = grid.total_spin_vector();
+    for k in 0..3 {
+        assert!((m[k] - expected[k]).abs() < 1e-9);
+    }
+    for group in 0..2 {
+        let m = grid.group_spin_sum(group);
+        let expected = grid.partial_spin_vector(group);
+        for k in 0..3 {
+            assert!((m[k] - expected[k]).abs() < 1e-9);
+        }
+    }
+}
@@ -1,12 +1,30 @@
+mod bkt;
+mod checkerboard;
+mod correlation;
+mod equilibration;
 mod metropolis;
+mod reweighting;
 mod stats;
+mod structure_factor;
+mod tempering;
+mod time_series;
+mod topology;
 mod wolff;
 use crate::lattice::Grid;
 use crate::spin::SpinState;
 
+pub use bkt::{Helicity, Plaquettes};
+pub use checkerboard::CheckerboardMetropolis;
+pub use correlation::{CorrelationAccumulator, PairCorrelation, StructureFactor};
+pub use equilibration::EquilibrationDetector;
 pub use metropolis::Metropolis;
+pub use reweighting::{ReweightedPoint, reweight};
 pub use stats::{StatResult, Stats, StatsConfig};
-pub use wolff::Wolff;
+pub use structure_factor::{QPoints, StructureFactorAccumulator};
+pub use tempering::SwapStatistics;
+pub use time_series::TimeSeriesWriter;
+pub use topology::{TopologyAccumulator, TopologyResult, Triangulation};
+pub use wolff::{Cluster, Wolff};
 
 pub trait MonteCarlo<S: SpinState, R: rand::Rng> {
     fn step(&mut self, grid: &mut Grid<S, R>) -> usize;
@@ -14,6 +32,7 @@ pub trait MonteCarlo<S: SpinState, R: rand::Rng> {
 
 pub enum AnyMC<R: rand::Rng> {
     Metropolis(Metropolis<R>),
+    Checkerboard(CheckerboardMetropolis<R>),
     Wolff(Wolff<R>),
 }
 
@@ -21,6 +40,7 @@ impl<S: SpinState, R: rand::Rng> MonteCarlo<S, R> for AnyMC<R> {
     fn step(&mut self, grid: &mut crate::lattice::Grid<S, R>) -> usize {
         match self {
             AnyMC::Metropolis(mc) => mc.step(grid),
+            AnyMC::Checkerboard(mc) => mc.step(grid),
             AnyMC::Wolff(mc) => mc.step(grid),
         }
     }
@@ -30,15 +50,24 @@ impl<R: rand::Rng> AnyMC<R> {
     pub fn beta(&self) -> f64 {
         match self {
             AnyMC::Metropolis(m) => m.beta,
+            AnyMC::Checkerboard(c) => c.beta,
             AnyMC::Wolff(w) => w.beta,
         }
     }
     pub fn set_beta(&mut self, beta: f64) {
         match self {
             AnyMC::Metropolis(m) => m.beta = beta,
+            AnyMC::Checkerboard(c) => c.beta = beta,
             AnyMC::Wolff(w) => w.beta = beta,
         }
     }
+    /// The last cluster built, for the cluster algorithms.
+    pub fn cluster(&self) -> Option<Cluster<'_>> {
+        match self {
+            AnyMC::Wolff(w) => Some(w.cluster()),
+            AnyMC::Metropolis(_) | AnyMC::Checkerboard(_) => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,222 @@
+use std::fmt;
+
+use super::StatResult;
+use crate::config::{Reweighting, ReweightingMethod};
+
+const WHAM_TOLERANCE: f64 = 1e-10;
+const WHAM_MAX_ITERATIONS: usize = 10_000;
+
+/// The (E, |M|) series measured at one inverse temperature.
+struct Run<'a> {
+    t: f64,
+    beta: f64,
+    samples: &'a [[f64; 2]],
+}
+
+#[derive(Debug)]
+pub struct ReweightedPoint {
+    pub t: f64,
+    pub energy: f64,             // <E> / N
+    pub specific_heat: f64,      // (<E^2> - <E>^2) / (N k_B T^2)
+    pub magnetization_abs: f64,  // <|M|> / N
+    pub susceptibility_abs: f64, // (<M^2> - <|M|>^2) / (N k_B T)
+}
+
+/// Reweight the recorded (E, |M|) series of `results` to every target
+/// temperature of `reweighting`. Quantities are per spin, `size` being the
+/// number of spins. Runs at T = 0 carry no information at finite T and are
+/// ignored; targets get NaN if no run is left.
+pub fn reweight(
+    results: &[StatResult],
+    reweighting: &Reweighting,
+    kb: f64,
+    size: f64,
+) -> Vec<ReweightedPoint> {
+    let runs: Vec<Run> = results
+        .iter()
+        .filter(|r| r.t > 0.0 && !r.samples.is_empty())
+        .map(|r| Run {
+            t: r.t,
+            beta: 1.0 / (kb * r.t),
+            samples: &r.samples,
+        })
+        .collect();
+
+    // Multi-histogram: every sample carries its WHAM denominator, which does
+    // not depend on the target temperature.
+    let pooled: Vec<([f64; 2], f64)> = match reweighting.method {
+        ReweightingMethod::MultiHistogram if !runs.is_empty() => {
+            let free_energies = wham_free_energies(&runs, reweighting.energy_bins);
+            runs.iter()
+                .flat_map(|run| run.samples.iter())
+                .map(|&s| (s, log_denominator(&runs, &free_energies, s[0])))
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    reweighting
+        .temperatures
+        .iter()
+        .map(|&t| {
+            let beta = 1.0 / (kb * t);
+            let averages = match reweighting.method {
+                _ if runs.is_empty() => [f64::NAN; 4],
+                ReweightingMethod::SingleHistogram => {
+                    let nearest = runs
+                        .iter()
+                        .min_by(|a, b| (a.t - t).abs().total_cmp(&(b.t - t).abs()))
+                        .expect("runs is not empty");
+                    weighted_averages(
+                        nearest
+                            .samples
+                            .iter()
+                            .map(|&s| (s, -(beta - nearest.beta) * s[0])),
+                    )
+                }
+                ReweightingMethod::MultiHistogram => weighted_averages(
+                    pooled
+                        .iter()
+                        .map(|&(s, log_den)| (s, -beta * s[0] - log_den)),
+                ),
+            };
+            let [e_avg, e2_avg, m_abs_avg, m2_avg] = averages;
+            ReweightedPoint {
+                t,
+                energy: e_avg / size,
+                specific_heat: (e2_avg - e_avg * e_avg) / (kb * t * t) / size,
+                magnetization_abs: m_abs_avg / size,
+                susceptibility_abs: (m2_avg - m_abs_avg * m_abs_avg) / (kb * t) / size,
+            }
+        })
+        .collect()
+}
+
+/// ln ∑_k N_k exp(f_k - β_k E), the WHAM denominator for energy `e`.
+fn log_denominator(runs: &[Run], free_energies: &[f64], e: f64) -> f64 {
+    log_sum_exp(
+        runs.iter()
+            .zip(free_energies)
+            .map(|(run, f)| (run.samples.len() as f64).ln() + f - run.beta * e),
+    )
+}
+
+/// Solve the multiple-histogram equations self-consistently on an energy
+/// histogram. Returns the dimensionless free energies f_k = -ln Z_k (up to a
+/// common constant), one per run.
+fn wham_free_energies(runs: &[Run], energy_bins: usize) -> Vec<f64> {
+    let energies = || runs.iter().flat_map(|run| run.samples.iter().map(|s| s[0]));
+    let e_min = energies().fold(f64::INFINITY, f64::min);
+    let e_max = energies().fold(f64::NEG_INFINITY, f64::max);
+    let width = (e_max - e_min) / energy_bins as f64;
+
+    // Counts and mean energy per bin; empty bins are dropped.
+    let mut counts = vec![0usize; energy_bins];
+    let mut e_sums = vec![0.0; energy_bins];
+    for e in energies() {
+        let bin = if width > 0.0 {
+            (((e - e_min) / width) as usize).min(energy_bins - 1)
+        } else {
+            0
+        };
+        counts[bin] += 1;
+        e_sums[bin] += e;
+    }
+    let bins: Vec<(f64, f64)> = counts
+        .iter()
+        .zip(&e_sums)
+        .filter(|(count, _)| **count > 0)
+        .map(|(count, e_sum)| ((*count as f64).ln(), e_sum / *count as f64))
+        .collect();
+
+    let mut free_energies = vec![0.0; runs.len()];
+    for _ in 0..WHAM_MAX_ITERATIONS {
+        // ln g(E), the density of states up to a constant.
+        let log_dos: Vec<f64> = bins
+            .iter()
+            .map(|&(log_count, e)| log_count - log_denominator(runs, &free_energies, e))
+            .collect();
+        let mut next: Vec<f64> = runs
+            .iter()
+            .map(|run| {
+                -log_sum_exp(
+                    bins.iter()
+                        .zip(&log_dos)
+                        .map(|(&(_, e), log_g)| log_g - run.beta * e),
+                )
+            })
+            .collect();
+        let shift = next[0];
+        next.iter_mut().for_each(|f| *f -= shift);
+
+        let change = next
+            .iter()
+            .zip(&free_energies)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        free_energies = next;
+        if change < WHAM_TOLERANCE {
+            break;
+        }
+    }
+    free_energies
+}
+
+/// <E>, <E^2>, <|M|> and <M^2> over (sample, log weight) pairs.
+fn weighted_averages(samples: impl Iterator<Item = ([f64; 2], f64)> + Clone) -> [f64; 4] {
+    let max = samples
+        .clone()
+        .map(|(_, log_w)| log_w)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let mut norm = 0.0;
+    let mut sums = [0.0; 4];
+    for ([e, m_abs], log_w) in samples {
+        let w = (log_w - max).exp();
+        norm += w;
+        sums[0] += w * e;
+        sums[1] += w * e * e;
+        sums[2] += w * m_abs;
+        sums[3] += w * m_abs * m_abs;
+    }
+    sums.map(|sum| sum / norm)
+}
+
+fn log_sum_exp(values: impl Iterator<Item = f64> + Clone) -> f64 {
+    let max = values.clone().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + values.map(|v| (v - max).exp()).sum::<f64>().ln()
+}
+
+impl ReweightedPoint {
+    /// Column header matching the `Display` layout, in the style of the
+    /// main result file.
+    pub fn header() -> String {
+        let mut header = format!("{:<12}", "#T(K)");
+        for (label, width) in [
+            ("Energy(eV)", 12),
+            ("$C$(eV/K)", 12),
+            ("|M|($\\mu_B$)", 12),
+            ("$|\\chi|$($\\mu_B^2/eV$)", 24),
+        ] {
+            header.push_str(&format!("\t{label:<width$}"));
+        }
+        header
+    }
+}
+
+impl fmt::Display for ReweightedPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use crate::utils::fmt_fixed_width;
+        write!(f, "{}", fmt_fixed_width(self.t, 12))?;
+        write!(f, "\t{}", fmt_fixed_width(self.energy, 12))?;
+        write!(f, "\t{}", fmt_fixed_width(self.specific_heat, 12))?;
+        write!(f, "\t{}", fmt_fixed_width(self.magnetization_abs, 12))?;
+        write!(f, "\t{}", fmt_fixed_width(self.susceptibility_abs, 24))
+    }
+}
+
+#[cfg(test)]
+#[path = "reweighting_tests.rs"]
+mod tests;
@@ -0,0 +1,157 @@
+use super::*;
+
+// Two-level system: E = 0 (one state) or E = 1 (three states), |M| = 1 - E.
+fn excited_fraction(t: f64) -> f64 {
+    let w = 3.0 * (-1.0 / t).exp();
+    w / (1.0 + w)
+}
+
+/// A run whose samples follow the exact Boltzmann distribution at `t`.
+fn exact_run(t: f64, n: usize) -> StatResult {
+    let excited = (excited_fraction(t) * n as f64).round() as usize;
+    let samples = (0..n)
+        .map(|i| if i < excited { [1.0, 0.0] } else { [0.0, 1.0] })
+        .collect();
+    StatResult {
+        t,
+        samples,
+        ..Default::default()
+    }
+}
+
+fn reweighting(method: ReweightingMethod, temperatures: Vec<f64>) -> Reweighting {
+    Reweighting {
+        temperatures,
+        temperature_range: vec![],
+        method,
+        energy_bins: 10,
+        savefile: "reweighted.txt".into(),
+        samples_directory: None,
+    }
+}
+
+fn assert_exact(point: &ReweightedPoint) {
+    let p = excited_fraction(point.t);
+    let t = point.t;
+    assert!((point.energy - p).abs() < 1e-4, "{point:?}");
+    assert!(
+        (point.specific_heat - p * (1.0 - p) / (t * t)).abs() < 1e-3,
+        "{point:?}"
+    );
+    assert!(
+        (point.magnetization_abs - (1.0 - p)).abs() < 1e-4,
+        "{point:?}"
+    );
+    assert!(
+        (point.susceptibility_abs - p * (1.0 - p) / t).abs() < 1e-3,
+        "{point:?}"
+    );
+}
+
+#[test]
+fn single_histogram_at_simulated_temperature_gives_plain_averages() {
+    let results = [exact_run(1.0, 1000)];
+    let points = reweight(
+        &results,
+        &reweighting(ReweightingMethod::SingleHistogram, vec![1.0]),
+        1.0,
+        1.0,
+    );
+    let n = results[0].samples.len() as f64;
+    let e_avg = results[0].samples.iter().map(|s| s[0]).sum::<f64>() / n;
+    assert!((points[0].energy - e_avg).abs() < 1e-12);
+}
+
+#[test]
+fn single_histogram_reweights_to_nearby_temperatures() {
+    let results = [exact_run(1.0, 100_000), exact_run(4.0, 100_000)];
+    let points = reweight(
+        &results,
+        &reweighting(ReweightingMethod::SingleHistogram, vec![0.8, 1.2, 3.5, 5.0]),
+        1.0,
+        1.0,
+    );
+    assert_eq!(points.len(), 4);
+    points.iter().for_each(assert_exact);
+}
+
+#[test]
+fn multi_histogram_combines_all_runs() {
+    let results = [
+        exact_run(0.5, 100_000),
+        exact_run(1.0, 100_000),
+        exact_run(2.0, 100_000),
+    ];
+    let targets: Vec<f64> = (0..=30).map(|i| 0.5 + 0.05 * i as f64).collect();
+    let points = reweight(
+        &results,
+        &reweighting(ReweightingMethod::MultiHistogram, targets),
+        1.0,
+        1.0,
+    );
+    points.iter().for_each(assert_exact);
+}
+
+#[test]
+fn quantities_are_normalised_per_spin() {
+    let results = [exact_run(1.0, 100_000)];
+    let config = reweighting(ReweightingMethod::MultiHistogram, vec![1.0]);
+    let one = &reweight(&results, &config, 1.0, 1.0)[0];
+    let four = &reweight(&results, &config, 1.0, 4.0)[0];
+    assert!((one.energy - 4.0 * four.energy).abs() < 1e-12);
+    assert!((one.specific_heat - 4.0 * four.specific_heat).abs() < 1e-12);
+}
+
+#[test]
+fn zero_temperature_and_empty_runs_are_ignored() {
+    let zero = StatResult {
+        t: 0.0,
+        samples: vec![[-5.0, 1.0]],
+        ..Default::default()
+    };
+    let empty = StatResult {
+        t: 2.0,
+        ..Default::default()
+    };
+    let results = [zero, empty, exact_run(1.0, 100_000)];
+    for method in [
+        ReweightingMethod::SingleHistogram,
+        ReweightingMethod::MultiHistogram,
+    ] {
+        let points = reweight(&results, &reweighting(method, vec![1.5]), 1.0, 1.0);
+        assert_exact(&points[0]);
+    }
+}
+
+#[test]
+fn no_usable_runs_give_nan() {
+    let results = [StatResult {
+        t: 1.0,
+        ..Default::default()
+    }];
+    let points = reweight(
+        &results,
+        &reweighting(ReweightingMethod::MultiHistogram, vec![1.0]),
+        1.0,
+        1.0,
+    );
+    assert!(points[0].energy.is_nan());
+    assert!(points[0].specific_heat.is_nan());
+}
+
+#[test]
+fn header_and_rows_have_same_columns() {
+    let point = ReweightedPoint {
+        t: 1.0,
+        energy: -1.0,
+        specific_heat: 0.5,
+        magnetization_abs: 0.9,
+        susceptibility_abs: 0.1,
+    };
+    let header = ReweightedPoint::header();
+    assert!(header.starts_with("#T(K)"));
+    assert_eq!(
+        header.split('\t').count(),
+        format!("{point}").split('\t').count()
+    );
+}
@@ -1,7 +1,15 @@
-use crate::config::Config;
+use super::bkt::{Helicity, Plaquettes};
+use super::correlation::{CorrelationAccumulator, StructureFactor};
+use super::structure_factor::StructureFactorAccumulator;
+use super::time_series::TimeSeriesWriter;
+use super::topology::{TopologyAccumulator, TopologyResult};
+use super::wolff::Cluster;
+use crate::config::{Config, TargetObservable};
 use crate::lattice::Grid;
-use crate::spin::SpinState;
+use crate::spin::{SpinState, norm_sqr};
 use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
 
 #[inline]
 fn maybe<T>(flag: bool, f: impl FnOnce() -> T) -> Option<T> {
@@ -12,6 +20,8 @@ fn maybe<T>(flag: bool, f: impl FnOnce() -> T) -> Option<T> {
 pub struct StatsConfig {
     pub energy: bool,
     pub heat_capacity: bool,
+    pub energy_terms: bool,
+    pub exchange_entries: usize, // [[exchange]] entries with their own energy column; 0 when disabled
     pub magnetization: bool,
     pub susceptibility: bool,
     pub magnetization_abs: bool,
@@ -20,70 +30,355 @@ pub struct StatsConfig {
     pub group_susceptibility: bool,
     pub group_magnetization_abs: bool,
     pub group_susceptibility_abs: bool,
+    pub binder_cumulant: bool,
+    pub group_binder_cumulant: bool,
+    pub correlation_length: bool,
+    pub vortex_density: bool,
+    pub helicity_axes: Vec<usize>, // periodic axes Υ is measured along; empty when disabled
+    pub cluster_size: bool,        // mean Wolff cluster size, fed through `record_cluster`
+    pub improved_estimators: bool, // cluster estimators of χ (and ξ with correlation_length)
+    pub anisotropy_scale: bool, // a K/K_0 column after T, for anisotropy-scale parallel tempering
+    pub error_bars: bool,
+    pub autocorrelation: bool,
+    pub record_samples: bool, // keep the (E, |M|) series for histogram reweighting
     pub group_num: usize,
+    pub order_parameter_num: usize, // weighted order parameters, each with M_w, χ_w and U4_w
 }
 
-impl fmt::Display for StatsConfig {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:<12}", "#T(K)")?;
+impl StatsConfig {
+    /// Column labels and widths, in the order `StatResult` writes its values.
+    fn columns(&self) -> Vec<(String, usize)> {
+        let mut columns = Vec::new();
         if self.energy {
-            write!(f, "\t{:<12}", "Energy(eV)")?;
+            columns.push(("Energy(eV)".to_string(), 12));
         }
         if self.heat_capacity {
-            write!(f, "\t{:<12}", "$C$(eV/K)")?;
+            columns.push(("$C$(eV/K)".to_string(), 12));
+        }
+        if self.energy_terms {
+            columns.push(("$E_{ex}$(eV)".to_string(), 12));
+            columns.push(("$E_{ani}$(eV)".to_string(), 12));
         }
         if self.magnetization {
-            write!(f, "\t{:<12}", "M($\\mu_B$)")?;
+            columns.push(("M($\\mu_B$)".to_string(), 12));
         }
         if self.susceptibility {
-            write!(f, "\t{:<24}", "$\\chi$($\\mu_B^2/eV$)")?;
+            columns.push(("$\\chi$($\\mu_B^2/eV$)".to_string(), 24));
         }
         if self.magnetization_abs {
-            write!(f, "\t{:<12}", "|M|($\\mu_B$)")?;
+            columns.push(("|M|($\\mu_B$)".to_string(), 12));
         }
         if self.susceptibility_abs {
-            write!(f, "\t{:<24}", "$|\\chi|$($\\mu_B^2/eV$)")?;
+            columns.push(("$|\\chi|$($\\mu_B^2/eV$)".to_string(), 24));
+        }
+        if self.binder_cumulant {
+            columns.push(("$U_4$".to_string(), 12));
+        }
+        if self.correlation_length {
+            columns.push(("$\\xi$".to_string(), 12));
+            columns.push(("$\\xi/L$".to_string(), 12));
+        }
+        if self.vortex_density {
+            columns.push(("$\\rho_v$".to_string(), 12));
+        }
+        if self.cluster_size {
+            columns.push(("$\\langle|C|\\rangle$".to_string(), 12));
+        }
+        if self.improved_estimators {
+            columns.push(("$\\chi_C$($\\mu_B^2/eV$)".to_string(), 24));
+            if self.correlation_length {
+                columns.push(("$\\xi_C$".to_string(), 12));
+                columns.push(("$\\xi_C/L$".to_string(), 12));
+            }
+        }
+        for i in 0..self.exchange_entries {
+            columns.push((format!("$E_{{J_{i}}}$(eV)"), 12));
+        }
+        for axis in &self.helicity_axes {
+            columns.push((format!("$\\Upsilon_{}$", ["x", "y", "z"][*axis]), 12));
         }
         if self.group_magnetization {
             for i in 0..self.group_num {
-                write!(f, "\t{:<12}", format!("M$_{i}$($\\mu_B$)"))?;
+                columns.push((format!("M$_{i}$($\\mu_B$)"), 12));
             }
         }
         if self.group_susceptibility {
             for i in 0..self.group_num {
-                write!(f, "\t{:<24}", format!("$\\chi_{i}(\\mu_B^2/eV$)"))?;
+                columns.push((format!("$\\chi_{i}(\\mu_B^2/eV$)"), 24));
             }
         }
         if self.group_magnetization_abs {
             for i in 0..self.group_num {
-                write!(f, "\t{:<12}", format!("|M|_{i}($\\mu_B$)"))?;
+                columns.push((format!("|M|_{i}($\\mu_B$)"), 12));
             }
         }
         if self.group_susceptibility_abs {
             for i in 0..self.group_num {
-                write!(f, "\t{:<24}", format!("$|\\chi|_{i}(\\mu_B^2/eV$)"))?;
+                columns.push((format!("$|\\chi|_{i}(\\mu_B^2/eV$)"), 24));
+            }
+        }
+        if self.group_binder_cumulant {
+            for i in 0..self.group_num {
+                columns.push((format!("$U_{{4,{i}}}$"), 12));
+            }
+        }
+        for i in 0..self.order_parameter_num {
+            columns.push((format!("|M|$_{{w,{i}}}$($\\mu_B$)"), 12));
+        }
+        for i in 0..self.order_parameter_num {
+            columns.push((format!("$|\\chi|_{{w,{i}}}(\\mu_B^2/eV$)"), 24));
+        }
+        for i in 0..self.order_parameter_num {
+            columns.push((format!("$U_{{4,w,{i}}}$"), 12));
+        }
+        columns
+    }
+}
+
+impl fmt::Display for StatsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<12}", "#T(K)")?;
+        if self.anisotropy_scale {
+            write!(f, "\t{:<12}", "K/K_0")?;
+        }
+        for (label, width) in self.columns() {
+            write!(f, "\t{label:<width$}")?;
+            if self.error_bars {
+                write!(f, "\t{:<width$}", format!("δ{label}"))?;
             }
         }
+        if self.autocorrelation {
+            write!(f, "\t{:<12}\t{:<12}", "$\\tau_E$", "$\\tau_{|M|}$")?;
+        }
         Ok(())
     }
 }
 
+/// Logarithmic binning of a time series: level `k` holds the means of
+/// consecutive bins of `2^k` measurements. The growth of the variance of the
+/// bin means with `k` gives the integrated autocorrelation time online, in
+/// O(log N) memory.
+#[derive(Clone, Debug, Default)]
+pub struct LogBinning {
+    pub levels: Vec<BinLevel>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BinLevel {
+    pub sum: f64,
+    pub sum_sq: f64,
+    pub count: usize,
+    pub pending: Option<f64>,
+}
+
+impl BinLevel {
+    fn variance(&self) -> f64 {
+        let n = self.count as f64;
+        let mean = self.sum / n;
+        (self.sum_sq / n - mean * mean) * n / (n - 1.0)
+    }
+}
+
+/// Levels with fewer bins than this give too noisy a variance estimate
+/// (relative error ~ sqrt(2 / bins)).
+const MIN_BINS_FOR_TAU: usize = 128;
+
+impl LogBinning {
+    pub fn push(&mut self, mut value: f64) {
+        for level in 0.. {
+            if level == self.levels.len() {
+                self.levels.push(BinLevel::default());
+            }
+            let bin = &mut self.levels[level];
+            bin.sum += value;
+            bin.sum_sq += value * value;
+            bin.count += 1;
+            match bin.pending.take() {
+                Some(previous) => value = 0.5 * (previous + value),
+                None => {
+                    bin.pending = Some(value);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// τ_int = ½ · 2^k · σ²_k / σ²_0 at the coarsest level that still has
+    /// `MIN_BINS_FOR_TAU` bins, in units of recorded measurements. NaN when
+    /// there is too little data or the series is constant.
+    pub fn tau_int(&self) -> f64 {
+        let Some(level) = self
+            .levels
+            .iter()
+            .rposition(|bin| bin.count >= MIN_BINS_FOR_TAU)
+        else {
+            return f64::NAN;
+        };
+        let variance0 = self.levels[0].variance();
+        if variance0 <= 0.0 {
+            return f64::NAN;
+        }
+        0.5 * (1usize << level) as f64 * self.levels[level].variance() / variance0
+    }
+}
+
+/// Running sums accumulated by `Stats`. Snapshots taken at block boundaries
+/// give the per-block sums used by the binning/jackknife error analysis.
+#[derive(Clone, Debug)]
+pub struct Moments {
+    pub energy_sum: f64,
+    pub energy2_sum: f64,
+    pub exchange_energy_sum: f64,
+    pub anisotropy_energy_sum: f64,
+    pub entry_energy_sums: Vec<f64>,
+    pub m_sum: [f64; 3],
+    pub m_2_sum: f64,
+    pub m_4_sum: f64,
+    pub m_abs_sum: f64,
+    pub m_q_2_sum: f64,
+    pub vortex_sum: f64,
+    pub cluster_size_sum: f64,
+    pub m_c_2_sum: f64,
+    pub m_c_q_2_sum: f64,
+    pub helicity_sums: Vec<[f64; 3]>,
+    pub steps: usize,
+    pub partial_m_sum: Vec<[f64; 3]>,
+    pub partial_m_2_sum: Vec<f64>,
+    pub partial_m_4_sum: Vec<f64>,
+    pub partial_m_abs_sum: Vec<f64>,
+    pub order_m_abs_sum: Vec<f64>,
+    pub order_m_2_sum: Vec<f64>,
+    pub order_m_4_sum: Vec<f64>,
+}
+
+impl Moments {
+    fn zero(cfg: &StatsConfig) -> Self {
+        let group_num = cfg.group_num;
+        let order_num = cfg.order_parameter_num;
+        Self {
+            energy_sum: 0.,
+            energy2_sum: 0.,
+            exchange_energy_sum: 0.,
+            anisotropy_energy_sum: 0.,
+            entry_energy_sums: vec![0.0; cfg.exchange_entries],
+            m_sum: [0.0; 3],
+            m_2_sum: 0.,
+            m_4_sum: 0.,
+            m_abs_sum: 0.,
+            m_q_2_sum: 0.,
+            vortex_sum: 0.,
+            cluster_size_sum: 0.,
+            m_c_2_sum: 0.,
+            m_c_q_2_sum: 0.,
+            helicity_sums: vec![[0.0; 3]; cfg.helicity_axes.len()],
+            steps: 0,
+            partial_m_sum: vec![[0.0; 3]; group_num],
+            partial_m_2_sum: vec![0.0; group_num],
+            partial_m_4_sum: vec![0.0; group_num],
+            partial_m_abs_sum: vec![0.0; group_num],
+            order_m_abs_sum: vec![0.0; order_num],
+            order_m_2_sum: vec![0.0; order_num],
+            order_m_4_sum: vec![0.0; order_num],
+        }
+    }
+
+    fn minus(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a - b, |a, b| a - b)
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a + b, |a, b| a + b)
+    }
+
+    /// Applies `op` to every pair of sums and `count` to the step counts.
+    fn zip_with(
+        &self,
+        other: &Self,
+        op: fn(f64, f64) -> f64,
+        count: fn(usize, usize) -> usize,
+    ) -> Self {
+        let each = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| op(*x, *y)).collect();
+        let each3 = |a: &[[f64; 3]], b: &[[f64; 3]]| {
+            a.iter()
+                .zip(b)
+                .map(|(x, y)| std::array::from_fn(|k| op(x[k], y[k])))
+                .collect()
+        };
+        Self {
+            energy_sum: op(self.energy_sum, other.energy_sum),
+            energy2_sum: op(self.energy2_sum, other.energy2_sum),
+            exchange_energy_sum: op(self.exchange_energy_sum, other.exchange_energy_sum),
+            anisotropy_energy_sum: op(self.anisotropy_energy_sum, other.anisotropy_energy_sum),
+            entry_energy_sums: each(&self.entry_energy_sums, &other.entry_energy_sums),
+            m_sum: std::array::from_fn(|k| op(self.m_sum[k], other.m_sum[k])),
+            m_2_sum: op(self.m_2_sum, other.m_2_sum),
+            m_4_sum: op(self.m_4_sum, other.m_4_sum),
+            m_abs_sum: op(self.m_abs_sum, other.m_abs_sum),
+            m_q_2_sum: op(self.m_q_2_sum, other.m_q_2_sum),
+            vortex_sum: op(self.vortex_sum, other.vortex_sum),
+            cluster_size_sum: op(self.cluster_size_sum, other.cluster_size_sum),
+            m_c_2_sum: op(self.m_c_2_sum, other.m_c_2_sum),
+            m_c_q_2_sum: op(self.m_c_q_2_sum, other.m_c_q_2_sum),
+            helicity_sums: each3(&self.helicity_sums, &other.helicity_sums),
+            steps: count(self.steps, other.steps),
+            partial_m_sum: each3(&self.partial_m_sum, &other.partial_m_sum),
+            partial_m_2_sum: each(&self.partial_m_2_sum, &other.partial_m_2_sum),
+            partial_m_4_sum: each(&self.partial_m_4_sum, &other.partial_m_4_sum),
+            partial_m_abs_sum: each(&self.partial_m_abs_sum, &other.partial_m_abs_sum),
+            order_m_abs_sum: each(&self.order_m_abs_sum, &other.order_m_abs_sum),
+            order_m_2_sum: each(&self.order_m_2_sum, &other.order_m_2_sum),
+            order_m_4_sum: each(&self.order_m_4_sum, &other.order_m_4_sum),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Stats<S: SpinState> {
     pub energy_sum: f64,
     pub energy2_sum: f64,
-    pub m_sum: S,       // ∑ M
-    pub m_2_sum: f64,   // ∑ M^2
-    pub m_abs_sum: f64, // ∑ |M|
+    pub exchange_energy_sum: f64,     // ∑ E_ex
+    pub anisotropy_energy_sum: f64,   // ∑ E_ani
+    pub entry_energy_sums: Vec<f64>,  // ∑ E_J per [[exchange]] entry
+    pub m_sum: [f64; 3],              // ∑ M
+    pub m_2_sum: f64,                 // ∑ M^2
+    pub m_4_sum: f64,                 // ∑ M^4
+    pub m_abs_sum: f64,               // ∑ |M|
+    pub m_q_2_sum: f64,               // ∑ |M(q_min)|^2
+    pub vortex_sum: f64,              // ∑ (vortices + antivortices)
+    pub cluster_size_sum: f64,        // ∑ mean |C| of the clusters built between measurements
+    pub m_c_2_sum: f64,               // ∑ n (Σ_C S·r)² / |C|, improved estimator of M^2 / N
+    pub m_c_q_2_sum: f64,             // ∑ n |Σ_C (S·r) e^{iq·r}|² / |C|, same for |M(q_min)|^2
+    pub helicity_sums: Vec<[f64; 3]>, // per axis: ∑ ∂²E/∂φ², ∑ ∂E/∂φ, ∑ (∂E/∂φ)²
     pub steps: usize,
     pub size: f64,
     pub kb: f64,
     pub t: f64,
+    pub anisotropy_scale: Option<f64>, // factor on the anisotropy strengths, when tempered in it
     pub stats_config: StatsConfig,
-    pub partial_m_sum: Vec<S>,
+    pub partial_m_sum: Vec<[f64; 3]>,
     pub partial_m_2_sum: Vec<f64>,
+    pub partial_m_4_sum: Vec<f64>,
     pub partial_m_abs_sum: Vec<f64>,
     pub partial_size: Vec<f64>,
+    pub order_m_abs_sum: Vec<f64>, // per order parameter: ∑ |M_w|
+    pub order_m_2_sum: Vec<f64>,   // ∑ M_w^2
+    pub order_m_4_sum: Vec<f64>,   // ∑ M_w^4
+    pub order_size: Vec<f64>,      // Σ_i |w_i| from Grid::order_sizes, the normalisation of M_w
+    pub block_size: usize,         // measurements per error block
+    pub max_blocks: usize,         // blocks are merged pairwise on reaching this; 0 disables
+    pub checkpoints: Vec<Moments>, // running sums at the end of each block
+    pub energy_binning: LogBinning,
+    pub m_abs_binning: LogBinning,
+    pub samples: Vec<[f64; 2]>, // (E, |M|) per measurement, when record_samples is set
+    pub built_cluster_sizes: [f64; 2], // (∑ |C|, clusters) since the last measurement
+    pub time_series: Option<TimeSeriesWriter>,
+    pub structure_factor: Option<StructureFactor>, // set when correlation_length is enabled
+    pub correlation: Option<CorrelationAccumulator>,
+    pub static_structure_factor: Option<StructureFactorAccumulator>,
+    pub topology: Option<TopologyAccumulator>,
+    pub plaquettes: Option<Arc<Plaquettes>>, // set when vortex_density is enabled
+    pub helicity: Option<Arc<Helicity>>,     // set when helicity_axes is non-empty
+    pub spin: PhantomData<S>,                // sums are f64 whatever precision S stores
 }
 
 impl<S: SpinState> Stats<S> {
@@ -96,31 +391,135 @@ impl<S: SpinState> Stats<S> {
             .iter()
             .map(|i| size / config.grid.sublattices as f64 * i.len() as f64)
             .collect();
+        let order_num = stats_config.order_parameter_num;
         Self {
             energy_sum: 0.,
             energy2_sum: 0.,
-            m_sum: S::zero(),
+            exchange_energy_sum: 0.,
+            anisotropy_energy_sum: 0.,
+            entry_energy_sums: vec![0.0; stats_config.exchange_entries],
+            m_sum: [0.0; 3],
             m_2_sum: 0.,
+            m_4_sum: 0.,
             m_abs_sum: 0.,
+            m_q_2_sum: 0.,
+            vortex_sum: 0.,
+            cluster_size_sum: 0.,
+            m_c_2_sum: 0.,
+            m_c_q_2_sum: 0.,
+            helicity_sums: vec![[0.0; 3]; stats_config.helicity_axes.len()],
             steps: 0,
             kb: config.simulation.boltzmann_constant,
             t,
+            anisotropy_scale: None,
             size,
-            partial_m_sum: vec![S::zero(); stats_config.group_num],
+            partial_m_sum: vec![[0.0; 3]; stats_config.group_num],
             partial_m_2_sum: vec![0.0; stats_config.group_num],
+            partial_m_4_sum: vec![0.0; stats_config.group_num],
             partial_m_abs_sum: vec![0.0; stats_config.group_num],
             partial_size,
+            order_m_abs_sum: vec![0.0; order_num],
+            order_m_2_sum: vec![0.0; order_num],
+            order_m_4_sum: vec![0.0; order_num],
+            order_size: Vec::new(),
+            block_size: 1,
+            max_blocks: if stats_config.error_bars {
+                2 * config.output.error_blocks
+            } else {
+                0
+            },
+            checkpoints: Vec::new(),
+            energy_binning: LogBinning::default(),
+            m_abs_binning: LogBinning::default(),
+            samples: Vec::new(),
+            built_cluster_sizes: [0.0; 2],
+            time_series: None,
+            structure_factor: maybe(stats_config.correlation_length, || {
+                StructureFactor::new(dim, config.grid.periodic_boundary)
+            })
+            .flatten(),
+            correlation: None,
+            static_structure_factor: None,
+            topology: None,
+            plaquettes: None,
+            helicity: None,
             stats_config,
+            spin: PhantomData,
+        }
+    }
+
+    /// Counts the cluster built by one measurement-phase step, whether or not
+    /// the anisotropy test rejected its flip: ⟨|C|⟩ describes the clusters
+    /// the algorithm grows at this temperature.
+    pub fn count_cluster(&mut self, size: usize) {
+        if self.stats_config.cluster_size {
+            self.built_cluster_sizes[0] += size as f64;
+            self.built_cluster_sizes[1] += 1.0;
+        }
+    }
+
+    /// Adds the cluster flipped before this measurement, and the mean size of
+    /// the clusters counted since the last one; call it ahead of `record` so
+    /// both land in the same error block.
+    ///
+    /// The cluster holding a random seed is picked with probability |C|/N,
+    /// and it is reflected along a uniformly drawn axis r, so n (Σ_C S·r)² / |C|
+    /// averages to <M^2> / N with a much smaller variance than M^2 near Tc.
+    /// Exact without anisotropy, which the config rejects alongside them.
+    pub fn record_cluster(&mut self, spins: &[S], cluster: Cluster<'_>) {
+        let size = cluster.sites.len();
+        if self.stats_config.cluster_size {
+            let [sum, clusters] = std::mem::take(&mut self.built_cluster_sizes);
+            self.cluster_size_sum += sum / clusters.max(1.0);
+        }
+        if self.stats_config.improved_estimators && size > 0 {
+            let weight = S::COMPONENTS as f64 / size as f64;
+            let projections = cluster.sites.iter().map(|&i| {
+                let s = spins[i].to_array();
+                (i, (0..3).map(|k| s[k] * cluster.axis[k]).sum::<f64>())
+            });
+            let m_c = projections.clone().map(|(_, p)| p).sum::<f64>();
+            self.m_c_2_sum += weight * m_c * m_c;
+            if let Some(structure_factor) = &self.structure_factor {
+                self.m_c_q_2_sum += weight * structure_factor.min_q_projected(projections);
+            }
         }
     }
 
     pub fn record<R: rand::Rng>(&mut self, grid: &Grid<S, R>) {
-        if self.stats_config.energy {
-            let energy = grid.total_energy();
-            self.energy_sum += energy;
+        if self.stats_config.record_samples {
+            let energy = grid.energy();
+            let m_abs = norm_sqr(grid.spin_sum()).sqrt();
+            self.samples.push([energy, m_abs]);
+        }
+
+        if self.stats_config.energy || self.stats_config.autocorrelation {
+            let energy = grid.energy();
+            if self.stats_config.energy {
+                self.energy_sum += energy;
 
-            if self.stats_config.heat_capacity {
-                self.energy2_sum += energy * energy;
+                if self.stats_config.heat_capacity {
+                    self.energy2_sum += energy * energy;
+                }
+            }
+            if self.stats_config.autocorrelation {
+                self.energy_binning.push(energy);
+            }
+        }
+
+        if self.stats_config.energy_terms {
+            let terms = grid.energy_terms();
+            self.exchange_energy_sum += terms.exchange;
+            self.anisotropy_energy_sum += terms.anisotropy;
+        }
+
+        if self.stats_config.exchange_entries > 0 {
+            for (sum, energy) in self
+                .entry_energy_sums
+                .iter_mut()
+                .zip(grid.exchange_entry_energies(self.stats_config.exchange_entries))
+            {
+                *sum += energy;
             }
         }
 
@@ -128,18 +527,65 @@ impl<S: SpinState> Stats<S> {
             || self.stats_config.susceptibility
             || self.stats_config.magnetization_abs
             || self.stats_config.susceptibility_abs
+            || self.stats_config.binder_cumulant
+            || self.stats_config.correlation_length
+            || self.stats_config.autocorrelation
         {
-            let spin_vec = grid.total_spin_vector();
+            let spin_vec = grid.spin_sum();
+            let m2 = norm_sqr(spin_vec);
+
+            if self.stats_config.autocorrelation {
+                self.m_abs_binning.push(m2.sqrt());
+            }
 
             if self.stats_config.magnetization || self.stats_config.susceptibility {
-                self.m_sum += &spin_vec;
+                add_assign(&mut self.m_sum, spin_vec);
             }
 
             if self.stats_config.magnetization_abs || self.stats_config.susceptibility_abs {
-                self.m_abs_sum += &spin_vec.norm();
+                self.m_abs_sum += m2.sqrt();
             }
-            if self.stats_config.susceptibility || self.stats_config.susceptibility_abs {
-                self.m_2_sum += spin_vec.norm_sqr();
+            if self.stats_config.susceptibility
+                || self.stats_config.susceptibility_abs
+                || self.stats_config.binder_cumulant
+                || self.stats_config.correlation_length
+            {
+                self.m_2_sum += m2;
+            }
+            if self.stats_config.binder_cumulant {
+                self.m_4_sum += m2 * m2;
+            }
+        }
+
+        if let Some(structure_factor) = &self.structure_factor {
+            self.m_q_2_sum += structure_factor.min_q(&grid.spins);
+        }
+
+        if let Some(correlation) = &mut self.correlation {
+            correlation.record(&grid.spins);
+        }
+
+        if let Some(structure_factor) = &mut self.static_structure_factor {
+            structure_factor.record(&grid.spins);
+        }
+
+        if let Some(topology) = &mut self.topology {
+            topology.record(&grid.spins);
+        }
+
+        if let Some(plaquettes) = &self.plaquettes {
+            self.vortex_sum += plaquettes.vortices(&grid.spins) as f64;
+        }
+
+        if let Some(helicity) = &self.helicity {
+            for (sums, [curvature, current]) in self
+                .helicity_sums
+                .iter_mut()
+                .zip(helicity.derivatives(&grid.spins))
+            {
+                sums[0] += curvature;
+                sums[1] += current;
+                sums[2] += current * current;
             }
         }
 
@@ -147,77 +593,331 @@ impl<S: SpinState> Stats<S> {
             || self.stats_config.group_susceptibility
             || self.stats_config.group_magnetization_abs
             || self.stats_config.group_susceptibility_abs
+            || self.stats_config.group_binder_cumulant
         {
             for i in 0..self.stats_config.group_num {
-                let partial_spin_vec = &grid.partial_spin_vector(i);
+                let partial_spin_vec = grid.group_spin_sum(i);
+                let m2 = norm_sqr(partial_spin_vec);
                 if self.stats_config.group_magnetization || self.stats_config.group_susceptibility {
-                    self.partial_m_sum[i] += partial_spin_vec;
+                    add_assign(&mut self.partial_m_sum[i], partial_spin_vec);
                 }
                 if self.stats_config.group_susceptibility
                     || self.stats_config.group_susceptibility_abs
+                    || self.stats_config.group_binder_cumulant
                 {
-                    self.partial_m_2_sum[i] += partial_spin_vec.norm_sqr();
+                    self.partial_m_2_sum[i] += m2;
+                }
+                if self.stats_config.group_binder_cumulant {
+                    self.partial_m_4_sum[i] += m2 * m2;
                 }
                 if self.stats_config.group_magnetization_abs
                     || self.stats_config.group_susceptibility_abs
                 {
-                    self.partial_m_abs_sum[i] += partial_spin_vec.norm();
+                    self.partial_m_abs_sum[i] += m2.sqrt();
                 }
             }
         }
 
+        for i in 0..self.stats_config.order_parameter_num {
+            let m2 = norm_sqr(grid.order_parameter_vector(i));
+            self.order_m_abs_sum[i] += m2.sqrt();
+            self.order_m_2_sum[i] += m2;
+            self.order_m_4_sum[i] += m2 * m2;
+        }
+
         self.steps += 1;
+
+        if self.max_blocks > 0 && self.steps.is_multiple_of(self.block_size) {
+            self.checkpoints.push(self.moments());
+            if self.checkpoints.len() == self.max_blocks {
+                // Merge neighbouring blocks by keeping every second boundary,
+                // so the block count stays bounded for any run length.
+                self.checkpoints = self
+                    .checkpoints
+                    .iter()
+                    .skip(1)
+                    .step_by(2)
+                    .cloned()
+                    .collect();
+                self.block_size *= 2;
+            }
+        }
+    }
+
+    /// Append the current state to the time series, if one is attached.
+    pub fn record_time_series<R: rand::Rng>(&mut self, grid: &Grid<S, R>, sweep: usize) {
+        let Some(time_series) = &mut self.time_series else {
+            return;
+        };
+        let mut row = vec![grid.energy() / self.size];
+        row.extend(grid.spin_sum().map(|m| m / self.size));
+        for (i, partial_size) in self.partial_size.iter().enumerate() {
+            row.extend(grid.group_spin_sum(i).map(|m| m / partial_size));
+        }
+        time_series.write_row(sweep, &row);
+    }
+
+    pub fn moments(&self) -> Moments {
+        Moments {
+            energy_sum: self.energy_sum,
+            energy2_sum: self.energy2_sum,
+            exchange_energy_sum: self.exchange_energy_sum,
+            anisotropy_energy_sum: self.anisotropy_energy_sum,
+            entry_energy_sums: self.entry_energy_sums.clone(),
+            m_sum: self.m_sum,
+            m_2_sum: self.m_2_sum,
+            m_4_sum: self.m_4_sum,
+            m_abs_sum: self.m_abs_sum,
+            m_q_2_sum: self.m_q_2_sum,
+            vortex_sum: self.vortex_sum,
+            cluster_size_sum: self.cluster_size_sum,
+            m_c_2_sum: self.m_c_2_sum,
+            m_c_q_2_sum: self.m_c_q_2_sum,
+            helicity_sums: self.helicity_sums.clone(),
+            steps: self.steps,
+            partial_m_sum: self.partial_m_sum.clone(),
+            partial_m_2_sum: self.partial_m_2_sum.clone(),
+            partial_m_4_sum: self.partial_m_4_sum.clone(),
+            partial_m_abs_sum: self.partial_m_abs_sum.clone(),
+            order_m_abs_sum: self.order_m_abs_sum.clone(),
+            order_m_2_sum: self.order_m_2_sum.clone(),
+            order_m_4_sum: self.order_m_4_sum.clone(),
+        }
     }
 
     pub fn result(&self) -> StatResult {
+        let mut result = self.estimate(&self.moments());
+        if self.stats_config.error_bars {
+            result.errors = Some(Box::new(self.jackknife_errors(&result)));
+        }
+        if self.stats_config.autocorrelation {
+            result.tau_energy = Some(self.energy_binning.tau_int());
+            result.tau_magnetization_abs = Some(self.m_abs_binning.tau_int());
+        }
+        if let Some(correlation) = &self.correlation {
+            result.correlation = correlation.averages(self.steps);
+        }
+        if let Some(structure_factor) = &self.static_structure_factor {
+            result.structure_factor = structure_factor.averages();
+        }
+        if let Some(topology) = &self.topology {
+            result.topological_charge = Some(topology.result());
+        }
+        result
+    }
+
+    /// Result of independent replicas of one point, `results` being their
+    /// own results. The observables come from the pooled running sums, so
+    /// that C, χ, U4 and ξ are ratios of pooled moments rather than means of
+    /// per-replica ratios; the rest, errors included, is `StatResult::average`.
+    pub fn pooled_result(replicas: &[Stats<S>], results: &[StatResult]) -> StatResult {
+        let moments = replicas
+            .iter()
+            .map(Stats::moments)
+            .reduce(|sum, moments| sum.plus(&moments))
+            .expect("at least one replica");
+        let pooled: Vec<f64> = replicas[0]
+            .estimate(&moments)
+            .columns()
+            .into_iter()
+            .map(|(value, _)| value)
+            .collect();
+        let mut average = StatResult::average(results);
+        StatResult {
+            errors: average.errors.take(),
+            tau_energy: average.tau_energy,
+            tau_magnetization_abs: average.tau_magnetization_abs,
+            samples: std::mem::take(&mut average.samples),
+            correlation: std::mem::take(&mut average.correlation),
+            structure_factor: std::mem::take(&mut average.structure_factor),
+            topological_charge: average.topological_charge.take(),
+            ..average.with_columns(&pooled)
+        }
+    }
+
+    /// Moves the recorded (E, |M|) series out, for the final result; `result`
+    /// leaves it out so that intermediate results do not copy it.
+    pub fn take_samples(&mut self) -> Vec<[f64; 2]> {
+        std::mem::take(&mut self.samples)
+    }
+
+    /// Largest jackknife standard error among `observables`. NaN until half
+    /// of the error blocks are filled, so that early, noisy error estimates
+    /// cannot end a run-until-precision measurement.
+    pub fn max_error(&self, observables: &[TargetObservable]) -> f64 {
+        if self.max_blocks == 0 || self.checkpoints.len() < self.max_blocks / 2 {
+            return f64::NAN;
+        }
+        let result = self.estimate(&self.moments());
+        let errors = self.jackknife_errors(&result);
+        observables
+            .iter()
+            .map(|observable| {
+                match observable {
+                    TargetObservable::Energy => errors.energy,
+                    TargetObservable::HeatCapacity => errors.specific_heat,
+                    TargetObservable::Magnetization => errors.magnetization,
+                    TargetObservable::Susceptibility => errors.susceptibility,
+                    TargetObservable::MagnetizationAbs => errors.magnetization_abs,
+                    TargetObservable::SusceptibilityAbs => errors.susceptibility_abs,
+                    TargetObservable::BinderCumulant => errors.binder,
+                }
+                .unwrap_or(f64::NAN)
+            })
+            .fold(0.0, |max, error| {
+                if error > max || error.is_nan() {
+                    error
+                } else {
+                    max
+                }
+            })
+    }
+
+    /// Delete-one-block jackknife over the completed blocks. For plain
+    /// averages (E, M, |M|) this equals the standard error of the block means;
+    /// for C and χ it also accounts for the nonlinear estimator. Measurements
+    /// after the last block boundary are folded into the last block, so the
+    /// errors cover the same samples as the values.
+    fn jackknife_errors(&self, result: &StatResult) -> StatResult {
+        let n_blocks = self.checkpoints.len();
+        if n_blocks < 2 {
+            return result.with_columns(&[]);
+        }
+        let full = self.moments();
+        let zero = Moments::zero(&self.stats_config);
+        let ends = self.checkpoints[..n_blocks - 1]
+            .iter()
+            .chain(std::iter::once(&full));
+        let estimates: Vec<Vec<f64>> = std::iter::once(&zero)
+            .chain(&self.checkpoints)
+            .zip(ends)
+            .map(|(start, end)| {
+                let block = end.minus(start);
+                self.estimate(&full.minus(&block))
+                    .columns()
+                    .into_iter()
+                    .map(|(value, _)| value)
+                    .collect()
+            })
+            .collect();
+
+        let nb = n_blocks as f64;
+        let errors: Vec<f64> = (0..estimates[0].len())
+            .map(|column| {
+                let mean = estimates.iter().map(|e| e[column]).sum::<f64>() / nb;
+                let spread = estimates
+                    .iter()
+                    .map(|e| (e[column] - mean).powi(2))
+                    .sum::<f64>();
+                ((nb - 1.0) / nb * spread).sqrt()
+            })
+            .collect();
+        result.with_columns(&errors)
+    }
+
+    fn estimate(&self, m: &Moments) -> StatResult {
         let size = self.size;
-        let n = self.steps as f64;
+        let n = m.steps as f64;
         let kbt = self.kb * self.t;
         let cfg = &self.stats_config;
 
-        let energy = maybe(cfg.energy, || self.energy_sum / n / size);
+        let energy = maybe(cfg.energy, || m.energy_sum / n / size);
 
         let specific_heat = maybe(cfg.heat_capacity, || {
-            let e_avg = self.energy_sum / n;
-            let e2_avg = self.energy2_sum / n;
+            let e_avg = m.energy_sum / n;
+            let e2_avg = m.energy2_sum / n;
             (e2_avg - e_avg * e_avg) / (kbt * self.t) / size
         });
 
-        let magnetization = maybe(cfg.magnetization, || (self.m_sum / n).norm() / size);
+        let exchange_energy = maybe(cfg.energy_terms, || m.exchange_energy_sum / n / size);
+        let anisotropy_energy = maybe(cfg.energy_terms, || m.anisotropy_energy_sum / n / size);
+        let entry_energy = maybe(cfg.exchange_entries > 0, || {
+            m.entry_energy_sums.iter().map(|e| e / n / size).collect()
+        });
+
+        let magnetization = maybe(cfg.magnetization, || norm_sqr(m.m_sum).sqrt() / n / size);
 
         let susceptibility = maybe(cfg.susceptibility, || {
-            let m_avg = self.m_sum / n;
-            let m2_avg = self.m_2_sum / n;
-            (m2_avg - m_avg.norm_sqr()) / kbt / size
+            let m2_avg = m.m_2_sum / n;
+            (m2_avg - norm_sqr(m.m_sum) / (n * n)) / kbt / size
         });
 
-        let magnetization_abs = maybe(cfg.magnetization_abs, || self.m_abs_sum / n / size);
+        let magnetization_abs = maybe(cfg.magnetization_abs, || m.m_abs_sum / n / size);
 
         let susceptibility_abs = maybe(cfg.susceptibility_abs, || {
-            let m_abs_avg = self.m_abs_sum / n;
-            let m2_avg = self.m_2_sum / n;
+            let m_abs_avg = m.m_abs_sum / n;
+            let m2_avg = m.m_2_sum / n;
             (m2_avg - m_abs_avg * m_abs_avg) / kbt / size
         });
 
+        let binder = maybe(cfg.binder_cumulant, || {
+            binder_cumulant::<S>(m.m_2_sum / n, m.m_4_sum / n)
+        });
+
+        let correlation_length = maybe(cfg.correlation_length, || {
+            self.structure_factor.as_ref().map_or(f64::NAN, |sf| {
+                sf.correlation_length(m.m_2_sum / n, m.m_q_2_sum / n)
+            })
+        });
+        let correlation_length_ratio = correlation_length.map(|xi| {
+            self.structure_factor
+                .as_ref()
+                .map_or(f64::NAN, |sf| xi / sf.length as f64)
+        });
+
+        let vortex_density = maybe(cfg.vortex_density, || {
+            self.plaquettes
+                .as_ref()
+                .map_or(f64::NAN, |p| m.vortex_sum / n / p.len() as f64)
+        });
+
+        let cluster_size = maybe(cfg.cluster_size, || m.cluster_size_sum / n);
+
+        // The cluster estimators carry no <M>^2 term: it vanishes by symmetry
+        // in a finite system, which is what they sample.
+        let susceptibility_improved = maybe(cfg.improved_estimators, || m.m_c_2_sum / n / kbt);
+        let correlation_length_improved =
+            maybe(cfg.improved_estimators && cfg.correlation_length, || {
+                self.structure_factor.as_ref().map_or(f64::NAN, |sf| {
+                    sf.correlation_length(m.m_c_2_sum / n, m.m_c_q_2_sum / n)
+                })
+            });
+        let correlation_length_improved_ratio = correlation_length_improved.map(|xi| {
+            self.structure_factor
+                .as_ref()
+                .map_or(f64::NAN, |sf| xi / sf.length as f64)
+        });
+
+        // Υ = (<∂²E/∂φ²> - β(<(∂E/∂φ)²> - <∂E/∂φ>²)) / N
+        let helicity = maybe(!cfg.helicity_axes.is_empty(), || {
+            m.helicity_sums
+                .iter()
+                .map(|[curvature, current, current2]| {
+                    let current_avg = current / n;
+                    (curvature / n - (current2 / n - current_avg * current_avg) / kbt) / size
+                })
+                .collect()
+        });
+
         let group_mag = maybe(cfg.group_magnetization, || {
-            self.partial_m_sum
+            m.partial_m_sum
                 .iter()
                 .zip(self.partial_size.iter())
-                .map(|(m_sum, s)| (*m_sum / n).norm() / s)
+                .map(|(m_sum, s)| norm_sqr(*m_sum).sqrt() / n / s)
                 .collect()
         });
 
         let group_sus = maybe(cfg.group_susceptibility, || {
-            self.partial_m_sum
+            m.partial_m_sum
                 .iter()
-                .zip(self.partial_m_2_sum.iter())
+                .zip(m.partial_m_2_sum.iter())
                 .zip(self.partial_size.iter())
-                .map(|((m_sum, m2), s)| (m2 / n - (*m_sum / n).norm_sqr()) / kbt / s)
+                .map(|((m_sum, m2), s)| (m2 / n - norm_sqr(*m_sum) / (n * n)) / kbt / s)
                 .collect()
         });
 
         let group_mag_abs = maybe(cfg.group_magnetization_abs, || {
-            self.partial_m_abs_sum
+            m.partial_m_abs_sum
                 .iter()
                 .zip(self.partial_size.iter())
                 .map(|(m_sum, s)| m_sum / n / s)
@@ -225,9 +925,9 @@ impl<S: SpinState> Stats<S> {
         });
 
         let group_sus_abs = maybe(cfg.group_susceptibility_abs, || {
-            self.partial_m_abs_sum
+            m.partial_m_abs_sum
                 .iter()
-                .zip(self.partial_m_2_sum.iter())
+                .zip(m.partial_m_2_sum.iter())
                 .zip(self.partial_size.iter())
                 .map(|((m_abs_sum, m2), s)| {
                     let m_abs_avg = m_abs_sum / n;
@@ -236,80 +936,349 @@ impl<S: SpinState> Stats<S> {
                 .collect()
         });
 
+        let group_binder = maybe(cfg.group_binder_cumulant, || {
+            m.partial_m_2_sum
+                .iter()
+                .zip(m.partial_m_4_sum.iter())
+                .map(|(m2, m4)| binder_cumulant::<S>(m2 / n, m4 / n))
+                .collect()
+        });
+
+        let has_order = cfg.order_parameter_num > 0;
+        let order_mag = maybe(has_order, || {
+            m.order_m_abs_sum
+                .iter()
+                .zip(&self.order_size)
+                .map(|(m_abs_sum, s)| m_abs_sum / n / s)
+                .collect()
+        });
+
+        let order_sus = maybe(has_order, || {
+            m.order_m_abs_sum
+                .iter()
+                .zip(&m.order_m_2_sum)
+                .zip(&self.order_size)
+                .map(|((m_abs_sum, m2), s)| {
+                    let m_abs_avg = m_abs_sum / n;
+                    (m2 / n - m_abs_avg * m_abs_avg) / kbt / s
+                })
+                .collect()
+        });
+
+        let order_binder = maybe(has_order, || {
+            m.order_m_2_sum
+                .iter()
+                .zip(&m.order_m_4_sum)
+                .map(|(m2, m4)| binder_cumulant::<S>(m2 / n, m4 / n))
+                .collect()
+        });
+
         StatResult {
             t: self.t,
+            anisotropy_scale: self.anisotropy_scale,
+            sweeps: 0,
+            equilibration_sweeps: 0,
+            measurement_sweeps: 0,
             energy,
             specific_heat,
+            exchange_energy,
+            anisotropy_energy,
+            entry_energy,
             magnetization,
             susceptibility,
             magnetization_abs,
             susceptibility_abs,
+            binder,
+            correlation_length,
+            correlation_length_ratio,
+            vortex_density,
+            cluster_size,
+            susceptibility_improved,
+            correlation_length_improved,
+            correlation_length_improved_ratio,
+            helicity,
             group_mag,
             group_sus,
             group_mag_abs,
             group_sus_abs,
+            group_binder,
+            order_mag,
+            order_sus,
+            order_binder,
+            errors: None,
+            tau_energy: None,
+            tau_magnetization_abs: None,
+            samples: Vec::new(),
+            correlation: Vec::new(),
+            structure_factor: Vec::new(),
+            topological_charge: None,
         }
     }
 }
 
+fn add_assign(sum: &mut [f64; 3], delta: [f64; 3]) {
+    for (total, d) in sum.iter_mut().zip(delta) {
+        *total += d;
+    }
+}
+
+/// Binder cumulant normalised by the number of spin components n, so that it
+/// tends to 0 in the disordered (Gaussian) phase for every model. For Ising
+/// this is the usual U4 = 1 - <M^4> / (3 <M^2>^2), tending to 2/3 when ordered;
+/// the ordered limit is 1/2 for XY and 2/5 for Heisenberg.
+fn binder_cumulant<S: SpinState>(m2_avg: f64, m4_avg: f64) -> f64 {
+    let n = S::COMPONENTS as f64;
+    1.0 - n * m4_avg / ((n + 2.0) * m2_avg * m2_avg)
+}
+
 #[derive(Debug, Default)]
 pub struct StatResult {
     pub t: f64,
+    pub anisotropy_scale: Option<f64>,
+    pub sweeps: usize,               // MC sweeps completed, filled in by the runner
+    pub equilibration_sweeps: usize, // of which (or planned) equilibration, filled in by the runner
+    pub measurement_sweeps: usize,   // of which (or planned) measurement, filled in by the runner
     pub energy: Option<f64>,
     pub specific_heat: Option<f64>,
-    pub magnetization: Option<f64>,      // |<M>| / N
-    pub susceptibility: Option<f64>,     // ( < M^2 > - <M>^2)/(N * k_B * T)
-    pub magnetization_abs: Option<f64>,  // < |M| >/ N
-    pub susceptibility_abs: Option<f64>, // ( < |M|^2 > - <M>^2)/(N * k_B * T)
+    pub exchange_energy: Option<f64>,                   // <E_ex> / N
+    pub anisotropy_energy: Option<f64>,                 // <E_ani> / N
+    pub entry_energy: Option<Vec<f64>>,                 // <E_J> / N per [[exchange]] entry
+    pub magnetization: Option<f64>,                     // |<M>| / N
+    pub susceptibility: Option<f64>,                    // ( < M^2 > - <M>^2)/(N * k_B * T)
+    pub magnetization_abs: Option<f64>,                 // < |M| >/ N
+    pub susceptibility_abs: Option<f64>,                // ( < |M|^2 > - <M>^2)/(N * k_B * T)
+    pub binder: Option<f64>,                            // 1 - n <M^4> / ((n + 2) <M^2>^2)
+    pub correlation_length: Option<f64>,                // second-moment ξ, in cells
+    pub correlation_length_ratio: Option<f64>,          // ξ / L
+    pub vortex_density: Option<f64>,                    // <vortices + antivortices> per plaquette
+    pub cluster_size: Option<f64>,                      // <|C|>, mean Wolff cluster size in sites
+    pub susceptibility_improved: Option<f64>,           // n <(Σ_C S·r)² / |C|> / (k_B T)
+    pub correlation_length_improved: Option<f64>,       // ξ from the cluster estimate of S(q)
+    pub correlation_length_improved_ratio: Option<f64>, // ξ_C / L
+    pub helicity: Option<Vec<f64>>,                     // Υ along each periodic axis
     pub group_mag: Option<Vec<f64>>,
     pub group_sus: Option<Vec<f64>>,
     pub group_mag_abs: Option<Vec<f64>>,
     pub group_sus_abs: Option<Vec<f64>>,
+    pub group_binder: Option<Vec<f64>>,
+    pub order_mag: Option<Vec<f64>>, // <|M_w|> / Σ|w_i| per order parameter
+    pub order_sus: Option<Vec<f64>>, // (<M_w^2> - <|M_w|>^2) / (Σ|w_i| k_B T)
+    pub order_binder: Option<Vec<f64>>, // 1 - n <M_w^4> / ((n + 2) <M_w^2>^2)
+    pub errors: Option<Box<StatResult>>, // jackknife or replica errors, same layout as the values
+    pub tau_energy: Option<f64>,     // integrated autocorrelation times, in measurements
+    pub tau_magnetization_abs: Option<f64>,
+    pub samples: Vec<[f64; 2]>, // raw (E, |M|) series, written to the reweighting samples files
+    pub correlation: Vec<f64>,  // G(r) per shell, written to the correlation file
+    pub structure_factor: Vec<f64>, // S(q) per q point, written to a per-temperature file
+    pub topological_charge: Option<TopologyResult>, // written to the topological charge file
 }
 
-impl fmt::Display for StatResult {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", crate::utils::fmt_fixed_width(self.t, 12))?;
-        if let Some(e) = self.energy {
-            write!(f, "\t{}", crate::utils::fmt_fixed_width(e, 12))?;
+impl StatResult {
+    /// Values and widths in output column order, excluding the temperature.
+    pub fn columns(&self) -> Vec<(f64, usize)> {
+        let mut columns = Vec::new();
+        let scalars = [
+            (self.energy, 12),
+            (self.specific_heat, 12),
+            (self.exchange_energy, 12),
+            (self.anisotropy_energy, 12),
+            (self.magnetization, 12),
+            (self.susceptibility, 24),
+            (self.magnetization_abs, 12),
+            (self.susceptibility_abs, 24),
+            (self.binder, 12),
+            (self.correlation_length, 12),
+            (self.correlation_length_ratio, 12),
+            (self.vortex_density, 12),
+            (self.cluster_size, 12),
+            (self.susceptibility_improved, 24),
+            (self.correlation_length_improved, 12),
+            (self.correlation_length_improved_ratio, 12),
+        ];
+        for (value, width) in scalars {
+            if let Some(value) = value {
+                columns.push((value, width));
+            }
         }
-        if let Some(c) = self.specific_heat {
-            write!(f, "\t{}", crate::utils::fmt_fixed_width(c, 12))?;
+        let groups = [
+            (&self.entry_energy, 12),
+            (&self.helicity, 12),
+            (&self.group_mag, 12),
+            (&self.group_sus, 24),
+            (&self.group_mag_abs, 12),
+            (&self.group_sus_abs, 24),
+            (&self.group_binder, 12),
+            (&self.order_mag, 12),
+            (&self.order_sus, 24),
+            (&self.order_binder, 12),
+        ];
+        for (values, width) in groups {
+            if let Some(values) = values {
+                columns.extend(values.iter().map(|value| (*value, width)));
+            }
         }
+        columns
+    }
 
-        if let Some(m) = self.magnetization {
-            write!(f, "\t{}", crate::utils::fmt_fixed_width(m, 12))?;
-        }
-        if let Some(chi) = self.susceptibility {
-            write!(f, "\t{}", crate::utils::fmt_fixed_width(chi, 24))?;
+    /// A result with the same enabled observables, filled column by column
+    /// from `values` (NaN once they run out).
+    fn with_columns(&self, values: &[f64]) -> StatResult {
+        fn fill(v: &Option<Vec<f64>>, next: &mut impl FnMut() -> f64) -> Option<Vec<f64>> {
+            v.as_ref().map(|v| v.iter().map(|_| next()).collect())
         }
-        if let Some(m_abs) = self.magnetization_abs {
-            write!(f, "\t{}", crate::utils::fmt_fixed_width(m_abs, 12))?;
-        }
-        if let Some(chi_absi) = self.susceptibility_abs {
-            write!(f, "\t{}", crate::utils::fmt_fixed_width(chi_absi, 24))?;
+        let mut values = values.iter().copied();
+        let mut next = || values.next().unwrap_or(f64::NAN);
+        StatResult {
+            t: self.t,
+            anisotropy_scale: self.anisotropy_scale,
+            sweeps: self.sweeps,
+            equilibration_sweeps: self.equilibration_sweeps,
+            measurement_sweeps: self.measurement_sweeps,
+            energy: self.energy.map(|_| next()),
+            specific_heat: self.specific_heat.map(|_| next()),
+            exchange_energy: self.exchange_energy.map(|_| next()),
+            anisotropy_energy: self.anisotropy_energy.map(|_| next()),
+            magnetization: self.magnetization.map(|_| next()),
+            susceptibility: self.susceptibility.map(|_| next()),
+            magnetization_abs: self.magnetization_abs.map(|_| next()),
+            susceptibility_abs: self.susceptibility_abs.map(|_| next()),
+            binder: self.binder.map(|_| next()),
+            correlation_length: self.correlation_length.map(|_| next()),
+            correlation_length_ratio: self.correlation_length_ratio.map(|_| next()),
+            vortex_density: self.vortex_density.map(|_| next()),
+            cluster_size: self.cluster_size.map(|_| next()),
+            susceptibility_improved: self.susceptibility_improved.map(|_| next()),
+            correlation_length_improved: self.correlation_length_improved.map(|_| next()),
+            correlation_length_improved_ratio: self
+                .correlation_length_improved_ratio
+                .map(|_| next()),
+            entry_energy: fill(&self.entry_energy, &mut next),
+            helicity: fill(&self.helicity, &mut next),
+            group_mag: fill(&self.group_mag, &mut next),
+            group_sus: fill(&self.group_sus, &mut next),
+            group_mag_abs: fill(&self.group_mag_abs, &mut next),
+            group_sus_abs: fill(&self.group_sus_abs, &mut next),
+            group_binder: fill(&self.group_binder, &mut next),
+            order_mag: fill(&self.order_mag, &mut next),
+            order_sus: fill(&self.order_sus, &mut next),
+            order_binder: fill(&self.order_binder, &mut next),
+            errors: None,
+            tau_energy: None,
+            tau_magnetization_abs: None,
+            samples: Vec::new(),
+            correlation: Vec::new(),
+            structure_factor: Vec::new(),
+            topological_charge: None,
         }
+    }
+}
 
-        if let Some(group_m) = &self.group_mag {
-            for m in group_m {
-                write!(f, "\t{}", crate::utils::fmt_fixed_width(*m, 12))?;
-            }
+impl StatResult {
+    /// Mean of independent replicas at one temperature, column by column.
+    /// With two or more replicas the errors are the standard error of the
+    /// replica means, whether or not error bars were requested. Sweeps add up, so one interrupted replica leaves the
+    /// mean partial, and the (E, |M|) samples are pooled for reweighting.
+    pub fn average(replicas: &[StatResult]) -> StatResult {
+        let n = replicas.len() as f64;
+        let values: Vec<Vec<f64>> = replicas
+            .iter()
+            .map(|replica| {
+                replica
+                    .columns()
+                    .into_iter()
+                    .map(|(value, _)| value)
+                    .collect()
+            })
+            .collect();
+        let means = mean_of(&values);
+        let first = &replicas[0];
+        let mut result = first.with_columns(&means);
+        if replicas.len() > 1 {
+            let errors: Vec<f64> = (0..means.len())
+                .map(|column| {
+                    let spread = values
+                        .iter()
+                        .map(|v| (v[column] - means[column]).powi(2))
+                        .sum::<f64>();
+                    (spread / (n * (n - 1.0))).sqrt()
+                })
+                .collect();
+            result.errors = Some(Box::new(first.with_columns(&errors)));
         }
-
-        if let Some(group_chi) = &self.group_sus {
-            for chi in group_chi {
-                write!(f, "\t{}", crate::utils::fmt_fixed_width(*chi, 24))?;
+        result.sweeps = replicas.iter().map(|r| r.sweeps).sum();
+        result.equilibration_sweeps = replicas.iter().map(|r| r.equilibration_sweeps).sum();
+        result.measurement_sweeps = replicas.iter().map(|r| r.measurement_sweeps).sum();
+        let mean = |value: fn(&StatResult) -> Option<f64>| {
+            value(first).map(|_| replicas.iter().filter_map(value).sum::<f64>() / n)
+        };
+        result.tau_energy = mean(|r| r.tau_energy);
+        result.tau_magnetization_abs = mean(|r| r.tau_magnetization_abs);
+        result.samples = replicas
+            .iter()
+            .flat_map(|r| r.samples.iter().copied())
+            .collect();
+        result.correlation = mean_of(&replicas.iter().map(|r| &r.correlation).collect::<Vec<_>>());
+        result.structure_factor = mean_of(
+            &replicas
+                .iter()
+                .map(|r| &r.structure_factor)
+                .collect::<Vec<_>>(),
+        );
+        result.topological_charge = first.topological_charge.as_ref().map(|_| {
+            let topologies: Vec<&TopologyResult> = replicas
+                .iter()
+                .filter_map(|r| r.topological_charge.as_ref())
+                .collect();
+            let scalars = mean_of(
+                &topologies
+                    .iter()
+                    .map(|t| [t.charge, t.charge_abs, t.susceptibility])
+                    .collect::<Vec<_>>(),
+            );
+            TopologyResult {
+                charge: scalars[0],
+                charge_abs: scalars[1],
+                susceptibility: scalars[2],
+                layer_charges: mean_of(
+                    &topologies
+                        .iter()
+                        .map(|t| &t.layer_charges)
+                        .collect::<Vec<_>>(),
+                ),
+                density: mean_of(&topologies.iter().map(|t| &t.density).collect::<Vec<_>>()),
             }
+        });
+        result
+    }
+}
+
+/// Element-wise mean of equally long rows.
+fn mean_of<V: AsRef<[f64]>>(rows: &[V]) -> Vec<f64> {
+    let n = rows.len() as f64;
+    (0..rows[0].as_ref().len())
+        .map(|i| rows.iter().map(|row| row.as_ref()[i]).sum::<f64>() / n)
+        .collect()
+}
+
+impl fmt::Display for StatResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", crate::utils::fmt_fixed_width(self.t, 12))?;
+        if let Some(scale) = self.anisotropy_scale {
+            write!(f, "\t{}", crate::utils::fmt_fixed_width(scale, 12))?;
         }
-        if let Some(group_m_abs) = &self.group_mag_abs {
-            for m in group_m_abs {
-                write!(f, "\t{}", crate::utils::fmt_fixed_width(*m, 12))?;
+        let errors = self.errors.as_ref().map(|errors| errors.columns());
+        for (i, (value, width)) in self.columns().into_iter().enumerate() {
+            write!(f, "\t{}", crate::utils::fmt_fixed_width(value, width))?;
+            if let Some(errors) = &errors {
+                let error = errors.get(i).map_or(f64::NAN, |(error, _)| *error);
+                write!(f, "\t{}", crate::utils::fmt_fixed_width(error, width))?;
             }
         }
-        if let Some(group_chi_abs) = &self.group_sus_abs {
-            for chi in group_chi_abs {
-                write!(f, "\t{}", crate::utils::fmt_fixed_width(*chi, 24))?;
-            }
+        for tau in [self.tau_energy, self.tau_magnetization_abs]
+            .into_iter()
+            .flatten()
+        {
+            write!(f, "\t{}", crate::utils::fmt_fixed_width(tau, 12))?;
         }
         Ok(())
     }
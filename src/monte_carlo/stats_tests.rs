@@ -1,7 +1,8 @@
 use super::*;
 use crate::config::Config;
 use crate::lattice::Grid;
-use crate::spin::IsingSpin;
+use crate::monte_carlo::{Cluster, MonteCarlo, Wolff};
+use crate::spin::{HeisenbergSpin, IsingSpin, XYSpin};
 use rand::SeedableRng;
 use rand::rngs::SmallRng;
 
@@ -39,6 +40,8 @@ fn energy_stats_config(group_num: usize) -> StatsConfig {
     StatsConfig {
         energy: true,
         heat_capacity: false,
+        energy_terms: false,
+        exchange_entries: 0,
         magnetization: false,
         susceptibility: false,
         magnetization_abs: false,
@@ -47,7 +50,19 @@ fn energy_stats_config(group_num: usize) -> StatsConfig {
         group_susceptibility: false,
         group_magnetization_abs: false,
         group_susceptibility_abs: false,
+        binder_cumulant: false,
+        group_binder_cumulant: false,
+        correlation_length: false,
+        vortex_density: false,
+        cluster_size: false,
+        improved_estimators: false,
+        anisotropy_scale: false,
+        helicity_axes: vec![],
+        error_bars: false,
+        autocorrelation: false,
+        record_samples: false,
         group_num,
+        order_parameter_num: 0,
     }
 }
 
@@ -55,6 +70,8 @@ fn all_stats_config(group_num: usize) -> StatsConfig {
     StatsConfig {
         energy: true,
         heat_capacity: true,
+        energy_terms: false,
+        exchange_entries: 0,
         magnetization: true,
         susceptibility: true,
         magnetization_abs: true,
@@ -63,7 +80,19 @@ fn all_stats_config(group_num: usize) -> StatsConfig {
         group_susceptibility: true,
         group_magnetization_abs: true,
         group_susceptibility_abs: true,
+        binder_cumulant: false,
+        group_binder_cumulant: false,
+        correlation_length: false,
+        vortex_density: false,
+        cluster_size: false,
+        improved_estimators: false,
+        anisotropy_scale: false,
+        helicity_axes: vec![],
+        error_bars: false,
+        autocorrelation: false,
+        record_samples: false,
         group_num,
+        order_parameter_num: 0,
     }
 }
 
@@ -86,6 +115,8 @@ fn stats_config_display_no_flags() {
     let cfg = StatsConfig {
         energy: false,
         heat_capacity: false,
+        energy_terms: false,
+        exchange_entries: 0,
         magnetization: false,
         susceptibility: false,
         magnetization_abs: false,
@@ -94,7 +125,19 @@ fn stats_config_display_no_flags() {
         group_susceptibility: false,
         group_magnetization_abs: false,
         group_susceptibility_abs: false,
+        binder_cumulant: false,
+        group_binder_cumulant: false,
+        correlation_length: false,
+        vortex_density: false,
+        cluster_size: false,
+        improved_estimators: false,
+        anisotropy_scale: false,
+        helicity_axes: vec![],
+        error_bars: false,
+        autocorrelation: false,
+        record_samples: false,
         group_num: 0,
+        order_parameter_num: 0,
     };
     let s = format!("{cfg}");
     assert!(s.contains("#T(K)"));
@@ -105,6 +148,8 @@ fn stats_config_display_with_energy() {
     let cfg = StatsConfig {
         energy: true,
         heat_capacity: true,
+        energy_terms: false,
+        exchange_entries: 0,
         magnetization: false,
         susceptibility: false,
         magnetization_abs: false,
@@ -113,7 +158,19 @@ fn stats_config_display_with_energy() {
         group_susceptibility: false,
         group_magnetization_abs: false,
         group_susceptibility_abs: false,
+        binder_cumulant: false,
+        group_binder_cumulant: false,
+        correlation_length: false,
+        vortex_density: false,
+        cluster_size: false,
+        improved_estimators: false,
+        anisotropy_scale: false,
+        helicity_axes: vec![],
+        error_bars: false,
+        autocorrelation: false,
+        record_samples: false,
         group_num: 0,
+        order_parameter_num: 0,
     };
     let s = format!("{cfg}");
     assert!(s.contains("Energy"));
@@ -150,16 +207,29 @@ fn make_stats(overrides: impl FnOnce(&mut Stats<IsingSpin>)) -> Stats<IsingSpin>
     let mut s = Stats {
         energy_sum: 0.0,
         energy2_sum: 0.0,
-        m_sum: IsingSpin::zero(),
+        exchange_energy_sum: 0.0,
+        anisotropy_energy_sum: 0.0,
+        entry_energy_sums: vec![],
+        m_sum: [0.0; 3],
         m_2_sum: 0.0,
+        m_4_sum: 0.0,
         m_abs_sum: 0.0,
+        m_q_2_sum: 0.0,
+        vortex_sum: 0.0,
+        cluster_size_sum: 0.0,
+        m_c_2_sum: 0.0,
+        m_c_q_2_sum: 0.0,
+        helicity_sums: vec![],
         steps: 1,
         size: 4.0,
         kb: 1.0,
         t: 2.0,
+        anisotropy_scale: None,
         stats_config: StatsConfig {
             energy: true,
             heat_capacity: true,
+            energy_terms: false,
+            exchange_entries: 0,
             magnetization: true,
             susceptibility: true,
             magnetization_abs: true,
@@ -168,12 +238,44 @@ fn make_stats(overrides: impl FnOnce(&mut Stats<IsingSpin>)) -> Stats<IsingSpin>
             group_susceptibility: false,
             group_magnetization_abs: false,
             group_susceptibility_abs: false,
+            binder_cumulant: false,
+            group_binder_cumulant: false,
+            correlation_length: false,
+            vortex_density: false,
+            cluster_size: false,
+            improved_estimators: false,
+            anisotropy_scale: false,
+            helicity_axes: vec![],
+            error_bars: false,
+            autocorrelation: false,
+            record_samples: false,
             group_num: 0,
+            order_parameter_num: 0,
         },
         partial_m_sum: vec![],
         partial_m_2_sum: vec![],
+        partial_m_4_sum: vec![],
         partial_m_abs_sum: vec![],
+        order_m_abs_sum: vec![],
+        order_m_2_sum: vec![],
+        order_m_4_sum: vec![],
         partial_size: vec![],
+        order_size: vec![],
+        block_size: 1,
+        max_blocks: 0,
+        checkpoints: vec![],
+        energy_binning: LogBinning::default(),
+        m_abs_binning: LogBinning::default(),
+        samples: vec![],
+        built_cluster_sizes: [0.0; 2],
+        time_series: None,
+        structure_factor: None,
+        correlation: None,
+        static_structure_factor: None,
+        topology: None,
+        plaquettes: None,
+        helicity: None,
+        spin: PhantomData,
     };
     overrides(&mut s);
     s
@@ -208,7 +310,7 @@ fn result_specific_heat() {
 #[test]
 fn result_magnetization() {
     let stats = make_stats(|s| {
-        s.m_sum = IsingSpin::along_z(8.0).unwrap();
+        s.m_sum = [0.0, 0.0, 8.0];
         s.steps = 2;
     });
     let r = stats.result();
@@ -219,7 +321,7 @@ fn result_magnetization() {
 #[test]
 fn result_susceptibility() {
     let stats = make_stats(|s| {
-        s.m_sum = IsingSpin::along_z(8.0).unwrap();
+        s.m_sum = [0.0, 0.0, 8.0];
         s.m_2_sum = 64.0;
         s.steps = 2;
         s.kb = 1.0;
@@ -279,7 +381,7 @@ fn result_single_step_variance_zero() {
     let stats = make_stats(|s| {
         s.energy_sum = 5.0;
         s.energy2_sum = 25.0;
-        s.m_sum = IsingSpin::along_z(4.0).unwrap();
+        s.m_sum = [0.0, 0.0, 4.0];
         s.m_2_sum = 16.0;
         s.steps = 1;
     });
@@ -297,10 +399,7 @@ fn result_group_observables() {
         s.kb = 1.0;
         s.t = 1.0;
         s.stats_config = all_stats_config(2);
-        s.partial_m_sum = vec![
-            IsingSpin::along_z(8.0).unwrap(),
-            IsingSpin::along_z(4.0).unwrap(),
-        ];
+        s.partial_m_sum = vec![[0.0, 0.0, 8.0], [0.0, 0.0, 4.0]];
         s.partial_m_2_sum = vec![64.0, 20.0];
         s.partial_m_abs_sum = vec![6.0, 2.0];
         s.partial_size = vec![4.0, 2.0];
@@ -336,21 +435,49 @@ fn stat_result_display_contains_temperature() {
 fn stat_result_display_contains_all_observables() {
     let r = StatResult {
         t: 300.0,
+        anisotropy_scale: None,
+        sweeps: 20,
+        equilibration_sweeps: 5,
+        measurement_sweeps: 15,
         energy: Some(-1.0),
         specific_heat: Some(2.0),
+        exchange_energy: Some(-0.75),
+        anisotropy_energy: Some(-0.25),
+        entry_energy: Some(vec![-0.5]),
         magnetization: Some(3.0),
         susceptibility: Some(4.0),
         magnetization_abs: Some(5.0),
         susceptibility_abs: Some(6.0),
+        binder: Some(0.25),
+        correlation_length: Some(14.0),
+        correlation_length_ratio: Some(15.0),
+        vortex_density: Some(16.0),
+        cluster_size: Some(20.0),
+        susceptibility_improved: Some(21.0),
+        correlation_length_improved: Some(22.0),
+        correlation_length_improved_ratio: Some(23.0),
+        helicity: Some(vec![17.0]),
         group_mag: Some(vec![7.0, 8.0]),
         group_sus: Some(vec![9.0]),
         group_mag_abs: Some(vec![10.0]),
         group_sus_abs: Some(vec![11.0]),
+        group_binder: Some(vec![0.125]),
+        order_mag: Some(vec![18.0]),
+        order_sus: Some(vec![19.0]),
+        order_binder: Some(vec![0.0625]),
+        errors: None,
+        tau_energy: Some(12.0),
+        tau_magnetization_abs: Some(13.0),
+        samples: Vec::new(),
+        correlation: Vec::new(),
+        structure_factor: Vec::new(),
+        topological_charge: None,
     };
     let s = format!("{r}");
 
     for expected in [
-        "300", "-1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11",
+        "300", "-1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+        "20", "21", "22", "23", "0.25", "0.125",
     ] {
         assert!(s.contains(expected), "missing {expected} in {s}");
     }
@@ -416,10 +543,798 @@ fn stats_record_all_observables() {
     assert_eq!(stats.steps, 1);
     assert!((stats.energy_sum - energy).abs() < 1e-10);
     assert!((stats.energy2_sum - energy * energy).abs() < 1e-10);
-    assert_eq!(stats.m_sum.to_array(), [0.0, 0.0, 4.0]);
+    assert_eq!(stats.m_sum, [0.0, 0.0, 4.0]);
     assert_eq!(stats.m_abs_sum, 4.0);
     assert_eq!(stats.m_2_sum, 16.0);
-    assert_eq!(stats.partial_m_sum[0].to_array(), [0.0, 0.0, 4.0]);
+    assert_eq!(stats.partial_m_sum[0], [0.0, 0.0, 4.0]);
     assert_eq!(stats.partial_m_abs_sum[0], 4.0);
     assert_eq!(stats.partial_m_2_sum[0], 16.0);
 }
+
+#[test]
+fn stats_record_keeps_magnetization_moments_in_f64() {
+    // 2^24 + 1 has no f32 representation; M^2 and M^4 must not round it.
+    let config = ising_grid_config();
+    let mut stats: Stats<IsingSpin> = Stats::new(&config, 1.0, all_stats_config(1));
+    let rng = SmallRng::seed_from_u64(42);
+    let mut grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, rng).unwrap();
+    let m = 16_777_217.0;
+    grid.totals.spin_sum = [0.0, 0.0, m];
+    grid.totals.group_sums[0] = [0.0, 0.0, m];
+
+    stats.record(&grid);
+
+    assert_eq!(stats.m_sum, [0.0, 0.0, m]);
+    assert_eq!(stats.m_abs_sum, m);
+    assert_eq!(stats.m_2_sum, m * m);
+    assert_eq!(stats.partial_m_abs_sum[0], m);
+    assert_eq!(stats.partial_m_2_sum[0], m * m);
+}
+
+#[test]
+fn stats_record_correlation_length_of_ordered_grid_diverges() {
+    let config = ising_grid_config();
+    let stats_config = StatsConfig {
+        correlation_length: true,
+        ..energy_stats_config(1)
+    };
+    let mut stats: Stats<IsingSpin> = Stats::new(&config, 1.0, stats_config);
+    assert_eq!(stats.structure_factor.as_ref().unwrap().length, 2);
+    let rng = SmallRng::seed_from_u64(42);
+    let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, rng).unwrap();
+
+    stats.record(&grid);
+
+    assert_eq!(stats.m_2_sum, 16.0);
+    assert!(stats.m_q_2_sum.abs() < 1e-20);
+    let result = stats.result();
+    assert!(result.correlation_length.unwrap() > 1e6);
+    assert!(result.correlation_length_ratio.unwrap() > 1e6);
+}
+
+// --- Binning / jackknife errors ---
+
+fn energy_moments(energy_sum: f64, steps: usize) -> Moments {
+    Moments {
+        energy_sum,
+        energy2_sum: 0.0,
+        exchange_energy_sum: 0.0,
+        anisotropy_energy_sum: 0.0,
+        entry_energy_sums: vec![],
+        m_sum: [0.0; 3],
+        m_2_sum: 0.0,
+        m_4_sum: 0.0,
+        m_abs_sum: 0.0,
+        m_q_2_sum: 0.0,
+        vortex_sum: 0.0,
+        cluster_size_sum: 0.0,
+        m_c_2_sum: 0.0,
+        m_c_q_2_sum: 0.0,
+        helicity_sums: vec![],
+        steps,
+        partial_m_sum: vec![],
+        partial_m_2_sum: vec![],
+        partial_m_4_sum: vec![],
+        partial_m_abs_sum: vec![],
+        order_m_abs_sum: vec![],
+        order_m_2_sum: vec![],
+        order_m_4_sum: vec![],
+    }
+}
+
+#[test]
+fn result_energy_error_matches_standard_error_of_block_means() {
+    let stats = make_stats(|s| {
+        s.size = 1.0;
+        s.energy_sum = 10.0;
+        s.steps = 4;
+        s.stats_config.error_bars = true;
+        // Four one-measurement blocks with energies 1, 2, 3, 4.
+        s.checkpoints = vec![
+            energy_moments(1.0, 1),
+            energy_moments(3.0, 2),
+            energy_moments(6.0, 3),
+            energy_moments(10.0, 4),
+        ];
+    });
+    let r = stats.result();
+    let errors = r.errors.as_ref().unwrap();
+    // Sample variance 5/3, standard error sqrt(5/3/4).
+    assert!((errors.energy.unwrap() - (5.0_f64 / 12.0).sqrt()).abs() < 1e-12);
+    assert_eq!(errors.columns().len(), r.columns().len());
+}
+
+#[test]
+fn result_energy_error_folds_the_remainder_into_the_last_block() {
+    let stats = make_stats(|s| {
+        s.size = 1.0;
+        s.energy_sum = 15.0;
+        s.steps = 5;
+        s.stats_config.error_bars = true;
+        // Blocks end after energies 1, 2, 3 and 4; the fifth measurement (5)
+        // came after the last boundary.
+        s.checkpoints = vec![
+            energy_moments(1.0, 1),
+            energy_moments(3.0, 2),
+            energy_moments(6.0, 3),
+            energy_moments(10.0, 4),
+        ];
+    });
+    let r = stats.result();
+    assert_eq!(r.energy, Some(3.0));
+    // Leave-one-block-out means over blocks {1}, {2}, {3}, {4, 5}.
+    let estimates = [14.0 / 4.0, 13.0 / 4.0, 12.0 / 4.0, 6.0 / 3.0];
+    let mean = estimates.iter().sum::<f64>() / 4.0;
+    let spread = estimates.iter().map(|e| (e - mean).powi(2)).sum::<f64>();
+    let expected = (0.75 * spread).sqrt();
+    assert!((r.errors.unwrap().energy.unwrap() - expected).abs() < 1e-12);
+}
+
+#[test]
+fn result_errors_are_nan_with_fewer_than_two_blocks() {
+    let stats = make_stats(|s| {
+        s.energy_sum = 1.0;
+        s.stats_config.error_bars = true;
+        s.checkpoints = vec![energy_moments(1.0, 1)];
+    });
+    let errors = stats.result().errors.unwrap();
+    assert!(errors.energy.unwrap().is_nan());
+    assert!(errors.susceptibility.unwrap().is_nan());
+}
+
+#[test]
+fn max_error_is_nan_until_half_the_blocks_are_filled() {
+    let blocks = vec![
+        energy_moments(1.0, 1),
+        energy_moments(3.0, 2),
+        energy_moments(6.0, 3),
+        energy_moments(10.0, 4),
+    ];
+    let stats = make_stats(|s| {
+        s.size = 1.0;
+        s.energy_sum = 10.0;
+        s.steps = 4;
+        s.stats_config.error_bars = true;
+        s.max_blocks = 16;
+        s.checkpoints = blocks.clone();
+    });
+    assert!(stats.max_error(&[TargetObservable::Energy]).is_nan());
+
+    let stats = make_stats(|s| {
+        s.size = 1.0;
+        s.energy_sum = 10.0;
+        s.steps = 4;
+        s.stats_config.error_bars = true;
+        s.max_blocks = 8;
+        s.checkpoints = blocks;
+    });
+    let error = stats.max_error(&[TargetObservable::Energy]);
+    assert!((error - (5.0_f64 / 12.0).sqrt()).abs() < 1e-12);
+    // An observable that is not measured has no error estimate.
+    let error = stats.max_error(&[TargetObservable::Energy, TargetObservable::BinderCumulant]);
+    assert!(error.is_nan());
+}
+
+#[test]
+fn result_without_error_bars_has_no_errors() {
+    let stats = make_stats(|_| {});
+    assert!(stats.result().errors.is_none());
+}
+
+#[test]
+fn stats_record_merges_blocks_when_full() {
+    let config = ising_grid_config();
+    let mut stats_config = energy_stats_config(1);
+    stats_config.error_bars = true;
+    let mut stats: Stats<IsingSpin> = Stats::new(&config, 1.0, stats_config);
+    stats.max_blocks = 4;
+    let rng = SmallRng::seed_from_u64(42);
+    let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, rng).unwrap();
+
+    for _ in 0..3 {
+        stats.record(&grid);
+    }
+    assert_eq!(stats.block_size, 1);
+    assert_eq!(stats.checkpoints.len(), 3);
+
+    stats.record(&grid);
+    assert_eq!(stats.block_size, 2);
+    assert_eq!(
+        stats
+            .checkpoints
+            .iter()
+            .map(|c| c.steps)
+            .collect::<Vec<_>>(),
+        vec![2, 4]
+    );
+
+    for _ in 0..3 {
+        stats.record(&grid);
+    }
+    assert_eq!(stats.block_size, 2);
+    assert_eq!(
+        stats
+            .checkpoints
+            .iter()
+            .map(|c| c.steps)
+            .collect::<Vec<_>>(),
+        vec![2, 4, 6]
+    );
+
+    for _ in 0..2 {
+        stats.record(&grid);
+    }
+    assert_eq!(stats.block_size, 4);
+    assert_eq!(
+        stats
+            .checkpoints
+            .iter()
+            .map(|c| c.steps)
+            .collect::<Vec<_>>(),
+        vec![4, 8]
+    );
+    // A constant configuration has zero error.
+    let errors = stats.result().errors.unwrap();
+    assert!(errors.energy.unwrap().abs() < 1e-12);
+}
+
+#[test]
+fn stats_config_display_interleaves_error_columns() {
+    let mut cfg = energy_stats_config(0);
+    cfg.heat_capacity = true;
+    cfg.error_bars = true;
+    let s = format!("{cfg}");
+    let labels: Vec<&str> = s.split('\t').map(str::trim).collect();
+    assert_eq!(
+        labels,
+        vec![
+            "#T(K)",
+            "Energy(eV)",
+            "δEnergy(eV)",
+            "$C$(eV/K)",
+            "δ$C$(eV/K)"
+        ]
+    );
+}
+
+#[test]
+fn stat_result_display_interleaves_errors() {
+    let r = StatResult {
+        t: 1.0,
+        energy: Some(-2.0),
+        specific_heat: Some(3.0),
+        errors: Some(Box::new(StatResult {
+            t: 1.0,
+            energy: Some(0.25),
+            specific_heat: Some(0.5),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+    let values: Vec<f64> = format!("{r}")
+        .split('\t')
+        .map(|v| v.trim().parse().unwrap())
+        .collect();
+    assert_eq!(values, vec![1.0, -2.0, 0.25, 3.0, 0.5]);
+}
+
+#[test]
+fn stat_result_average_takes_errors_from_replica_scatter() {
+    let replica = |energy: f64, sweeps: usize| StatResult {
+        t: 1.0,
+        sweeps,
+        equilibration_sweeps: 5,
+        measurement_sweeps: 10,
+        energy: Some(energy),
+        group_mag: Some(vec![energy, 2.0 * energy]),
+        errors: Some(Box::new(StatResult {
+            energy: Some(100.0),
+            group_mag: Some(vec![100.0, 100.0]),
+            ..Default::default()
+        })),
+        tau_energy: Some(energy),
+        samples: vec![[energy, 0.0]],
+        correlation: vec![energy],
+        ..Default::default()
+    };
+    let result = StatResult::average(&[replica(1.0, 15), replica(2.0, 15), replica(6.0, 7)]);
+
+    assert_eq!(result.t, 1.0);
+    assert_eq!(result.energy, Some(3.0));
+    assert_eq!(result.group_mag, Some(vec![3.0, 6.0]));
+    // Scatter 14 over 3 replicas: sqrt(14 / (3 * 2)).
+    let errors = result.errors.unwrap();
+    let error = (14.0_f64 / 6.0).sqrt();
+    assert!((errors.energy.unwrap() - error).abs() < 1e-12);
+    assert!((errors.group_mag.unwrap()[1] - 2.0 * error).abs() < 1e-12);
+    assert_eq!(result.tau_energy, Some(3.0));
+    assert_eq!(result.samples.len(), 3);
+    assert_eq!(result.correlation, [3.0]);
+    // The interrupted third replica keeps the mean partial.
+    assert_eq!(result.sweeps, 37);
+    assert!(result.sweeps < result.equilibration_sweeps + result.measurement_sweeps);
+}
+
+#[test]
+fn pooled_result_takes_ratios_of_pooled_moments() {
+    // Two replicas ordered in opposite directions: each alone has χ = 0,
+    // together they sample the symmetric distribution.
+    let replica = |m: f64| {
+        make_stats(|s| {
+            s.m_sum = [m, 0.0, 0.0];
+            s.m_2_sum = m * m;
+            s.m_abs_sum = m.abs();
+        })
+    };
+    let replicas = [replica(4.0), replica(-4.0)];
+    let results: Vec<StatResult> = replicas
+        .iter()
+        .map(|r| StatResult {
+            sweeps: 10,
+            ..r.result()
+        })
+        .collect();
+    assert_eq!(results[0].susceptibility, Some(0.0));
+
+    let pooled = Stats::pooled_result(&replicas, &results);
+    // <M^2> / (k_B T N) = 16 / (2 * 4) with <M> = 0.
+    assert_eq!(pooled.susceptibility, Some(2.0));
+    assert_eq!(pooled.magnetization, Some(0.0));
+    assert_eq!(pooled.magnetization_abs, Some(1.0));
+    assert_eq!(pooled.sweeps, 20);
+}
+
+#[test]
+fn stat_result_average_without_error_bars_still_has_replica_errors() {
+    let replica = |energy: f64| StatResult {
+        energy: Some(energy),
+        ..Default::default()
+    };
+    let result = StatResult::average(&[replica(-1.0), replica(-2.0)]);
+    assert_eq!(result.energy, Some(-1.5));
+    assert_eq!(result.errors.unwrap().energy, Some(0.5));
+    assert!(result.topological_charge.is_none());
+}
+
+// --- Autocorrelation times ---
+
+#[test]
+fn log_binning_push_builds_levels() {
+    let mut binning = LogBinning::default();
+    for value in [1.0, 3.0, 5.0, 7.0] {
+        binning.push(value);
+    }
+    let counts: Vec<usize> = binning.levels.iter().map(|l| l.count).collect();
+    assert_eq!(counts, vec![4, 2, 1]);
+    // Level 1 holds the pair means 2 and 6, level 2 their mean 4.
+    assert_eq!(binning.levels[1].sum, 8.0);
+    assert_eq!(binning.levels[2].sum, 4.0);
+}
+
+#[test]
+fn log_binning_tau_nan_without_enough_data() {
+    let mut binning = LogBinning::default();
+    assert!(binning.tau_int().is_nan());
+    for _ in 0..100 {
+        binning.push(1.0);
+    }
+    assert!(binning.tau_int().is_nan());
+}
+
+#[test]
+fn log_binning_tau_uncorrelated_series() {
+    let mut rng = SmallRng::seed_from_u64(7);
+    let mut binning = LogBinning::default();
+    for _ in 0..1 << 16 {
+        binning.push(rand::RngExt::random::<f64>(&mut rng));
+    }
+    let tau = binning.tau_int();
+    assert!((tau - 0.5).abs() < 0.2, "tau = {tau}");
+}
+
+#[test]
+fn log_binning_tau_ar1_series() {
+    // x_{t+1} = ρ x_t + noise has τ_int = ½ (1 + ρ) / (1 - ρ) = 4.5 for ρ = 0.8.
+    let mut rng = SmallRng::seed_from_u64(11);
+    let mut binning = LogBinning::default();
+    let mut x = 0.0;
+    for _ in 0..1 << 17 {
+        x = 0.8 * x + rand::RngExt::random::<f64>(&mut rng) - 0.5;
+        binning.push(x);
+    }
+    let tau = binning.tau_int();
+    assert!((tau - 4.5).abs() < 1.5, "tau = {tau}");
+}
+
+#[test]
+fn stats_record_autocorrelation_without_energy_output() {
+    let config = ising_grid_config();
+    let mut stats_config = energy_stats_config(1);
+    stats_config.energy = false;
+    stats_config.autocorrelation = true;
+    let mut stats: Stats<IsingSpin> = Stats::new(&config, 1.0, stats_config);
+    let rng = SmallRng::seed_from_u64(42);
+    let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, rng).unwrap();
+    stats.record(&grid);
+
+    assert_eq!(stats.energy_sum, 0.0);
+    assert_eq!(stats.energy_binning.levels[0].count, 1);
+    assert_eq!(stats.m_abs_binning.levels[0].sum, 4.0);
+    let r = stats.result();
+    assert!(r.energy.is_none());
+    assert!(r.tau_energy.is_some());
+    assert!(r.tau_magnetization_abs.is_some());
+}
+
+#[test]
+fn stats_config_display_autocorrelation_columns() {
+    let mut cfg = energy_stats_config(0);
+    cfg.autocorrelation = true;
+    let s = format!("{cfg}");
+    assert!(s.contains("$\\tau_E$"));
+    assert!(s.contains("$\\tau_{|M|}$"));
+}
+
+// --- Binder cumulant ---
+
+#[test]
+fn binder_cumulant_limits_per_model() {
+    use crate::spin::{HeisenbergSpin, XYSpin};
+    // Perfect order: <M^4> = <M^2>^2.
+    assert!((binder_cumulant::<IsingSpin>(4.0, 16.0) - 2.0 / 3.0).abs() < 1e-12);
+    assert!((binder_cumulant::<XYSpin>(4.0, 16.0) - 0.5).abs() < 1e-12);
+    assert!((binder_cumulant::<HeisenbergSpin>(4.0, 16.0) - 0.4).abs() < 1e-12);
+    // Gaussian disorder: <M^4> = (n + 2) / n <M^2>^2.
+    assert!(binder_cumulant::<IsingSpin>(1.0, 3.0).abs() < 1e-12);
+    assert!(binder_cumulant::<XYSpin>(1.0, 2.0).abs() < 1e-12);
+    assert!(binder_cumulant::<HeisenbergSpin>(1.0, 5.0 / 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn result_binder_cumulant() {
+    let stats = make_stats(|s| {
+        s.stats_config.binder_cumulant = true;
+        s.stats_config.group_binder_cumulant = true;
+        s.stats_config.group_num = 1;
+        s.steps = 2;
+        // |M| = 2 and 4: <M^2> = 10, <M^4> = 136.
+        s.m_2_sum = 20.0;
+        s.m_4_sum = 272.0;
+        s.partial_m_sum = vec![[0.0; 3]];
+        s.partial_m_2_sum = vec![20.0];
+        s.partial_m_4_sum = vec![272.0];
+        s.partial_m_abs_sum = vec![0.0];
+        s.partial_size = vec![4.0];
+    });
+    let r = stats.result();
+    let expected = 1.0 - 136.0 / (3.0 * 100.0);
+    assert!((r.binder.unwrap() - expected).abs() < 1e-12);
+    assert!((r.group_binder.unwrap()[0] - expected).abs() < 1e-12);
+}
+
+#[test]
+fn stats_record_binder_cumulant() {
+    let config = ising_grid_config();
+    let mut stats_config = energy_stats_config(1);
+    stats_config.binder_cumulant = true;
+    stats_config.group_binder_cumulant = true;
+    let mut stats: Stats<IsingSpin> = Stats::new(&config, 1.0, stats_config);
+    let rng = SmallRng::seed_from_u64(42);
+    let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, rng).unwrap();
+    stats.record(&grid);
+
+    assert_eq!(stats.m_2_sum, 16.0);
+    assert_eq!(stats.m_4_sum, 256.0);
+    assert_eq!(stats.partial_m_2_sum[0], 16.0);
+    assert_eq!(stats.partial_m_4_sum[0], 256.0);
+    assert!((stats.result().binder.unwrap() - 2.0 / 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn stats_record_cluster_size() {
+    let config = ising_grid_config();
+    let mut stats_config = energy_stats_config(0);
+    stats_config.cluster_size = true;
+    let mut stats: Stats<IsingSpin> = Stats::new(&config, 1.0, stats_config);
+    let rng = SmallRng::seed_from_u64(42);
+    let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, rng).unwrap();
+    // Every cluster between two measurements counts, not only the last one.
+    for built in [&[1][..], &[3, 5]] {
+        for &size in built {
+            stats.count_cluster(size);
+        }
+        let cluster = Cluster {
+            sites: &[0],
+            axis: [0.0, 0.0, 1.0],
+        };
+        stats.record_cluster(&grid.spins, cluster);
+        stats.record(&grid);
+    }
+
+    let result = stats.result();
+    assert_eq!(result.cluster_size, Some(2.5));
+    assert_eq!(result.columns()[1], (2.5, 12));
+    assert!(format!("{}", stats.stats_config).contains("$\\langle|C|\\rangle$"));
+}
+
+#[test]
+fn stats_record_cluster_ignored_when_disabled() {
+    let config = ising_grid_config();
+    let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, SmallRng::seed_from_u64(42)).unwrap();
+    let mut stats: Stats<IsingSpin> = Stats::new(&config, 1.0, energy_stats_config(0));
+    let cluster = Cluster {
+        sites: &[0, 1],
+        axis: [0.0, 0.0, 1.0],
+    };
+    stats.count_cluster(2);
+    stats.record_cluster(&grid.spins, cluster);
+    assert_eq!(stats.cluster_size_sum, 0.0);
+    assert_eq!(stats.m_c_2_sum, 0.0);
+}
+
+#[test]
+fn stats_record_improved_estimators_of_ordered_grid() {
+    let config = ising_grid_config();
+    let stats_config = StatsConfig {
+        susceptibility: true,
+        correlation_length: true,
+        improved_estimators: true,
+        ..energy_stats_config(0)
+    };
+    let mut stats: Stats<IsingSpin> = Stats::new(&config, 1.0, stats_config);
+    let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, SmallRng::seed_from_u64(42)).unwrap();
+    // The whole ordered lattice as one cluster: (Σ_C S·r)² / |C| = N.
+    let cluster = Cluster {
+        sites: &[0, 1, 2, 3],
+        axis: [0.0, 0.0, -1.0],
+    };
+    stats.record_cluster(&grid.spins, cluster);
+    stats.record(&grid);
+
+    let result = stats.result();
+    assert_eq!(result.susceptibility_improved, Some(4.0 / stats.kb));
+    assert!(stats.m_c_q_2_sum < 1e-12);
+    assert!(result.correlation_length_improved.unwrap() > 1e6);
+    assert!(format!("{}", stats.stats_config).contains("$\\xi_C/L$"));
+}
+
+#[test]
+fn stats_record_improved_estimators_of_single_site_cluster() {
+    let config = ising_grid_config();
+    let stats_config = StatsConfig {
+        correlation_length: true,
+        improved_estimators: true,
+        ..energy_stats_config(0)
+    };
+    let mut stats: Stats<IsingSpin> = Stats::new(&config, 1.0, stats_config);
+    let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, SmallRng::seed_from_u64(42)).unwrap();
+    let cluster = Cluster {
+        sites: &[2],
+        axis: [0.0, 0.0, 1.0],
+    };
+    stats.record_cluster(&grid.spins, cluster);
+    stats.record(&grid);
+
+    // A lone spin is uncorrelated: S_C(0) = S_C(q_min) = 1, so ξ_C = 0.
+    let result = stats.result();
+    assert_eq!(result.susceptibility_improved, Some(1.0 / stats.kb));
+    assert_eq!(result.correlation_length_improved, Some(0.0));
+    assert_eq!(result.correlation_length_improved_ratio, Some(0.0));
+}
+
+#[test]
+fn improved_susceptibility_matches_direct_estimator() {
+    let toml = r#"
+[simulation]
+initial_state = "random"
+model = "heisenberg"
+boltzmann_constant = 1
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "wolff"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [0, 1, 0]]
+strength = 1.0
+
+[output]
+susceptibility = true
+improved_estimators = true
+"#;
+    let config = Config::new(toml).unwrap();
+    let stats_config = StatsConfig {
+        energy: false,
+        susceptibility: true,
+        improved_estimators: true,
+        ..energy_stats_config(0)
+    };
+    let t = 1.0;
+    let mut stats: Stats<HeisenbergSpin> = Stats::new(&config, t, stats_config);
+    let mut grid: Grid<HeisenbergSpin, SmallRng> =
+        Grid::new(&config, SmallRng::seed_from_u64(3)).unwrap();
+    let mut wolff = Wolff::new(SmallRng::seed_from_u64(4), 1.0 / t, grid.hamiltonian.config);
+    for _ in 0..1000 {
+        wolff.step(&mut grid);
+    }
+    for _ in 0..40000 {
+        wolff.step(&mut grid);
+        stats.record_cluster(&grid.spins, wolff.cluster());
+        stats.record(&grid);
+    }
+
+    // Both estimate <M^2> / (N k_B T); <M> vanishes up to noise on this lattice.
+    let n = stats.steps as f64;
+    let direct = stats.m_2_sum / n / stats.size / t;
+    let improved = stats.result().susceptibility_improved.unwrap();
+    assert!(
+        (improved - direct).abs() < 0.05 * direct,
+        "improved {improved}, direct {direct}"
+    );
+}
+
+#[test]
+fn stats_record_helicity_and_vortices_of_ordered_xy_grid() {
+    let toml = r#"
+[simulation]
+initial_state = "x"
+model = "xy"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]
+strength = 1.0
+
+[output]
+vortex_density = true
+helicity_modulus = true
+"#;
+    let config = Config::new(toml).unwrap();
+    let stats_config = StatsConfig {
+        vortex_density: true,
+        helicity_axes: config.grid.periodic_axes(),
+        ..energy_stats_config(0)
+    };
+    let helicity = Helicity::new(&config, stats_config.helicity_axes.clone());
+    let mut stats: Stats<XYSpin> = Stats::new(&config, 1.0, stats_config);
+    stats.plaquettes = Some(Arc::new(Plaquettes::new(&config)));
+    stats.helicity = Some(Arc::new(helicity));
+    let grid: Grid<XYSpin, SmallRng> = Grid::new(&config, SmallRng::seed_from_u64(42)).unwrap();
+
+    stats.record(&grid);
+    stats.record(&grid);
+
+    let result = stats.result();
+    assert_eq!(result.vortex_density, Some(0.0));
+    // Ground state of the ferromagnet: Υ = J along both periodic axes.
+    let helicity = result.helicity.unwrap();
+    assert_eq!(helicity.len(), 2);
+    assert!(
+        helicity.iter().all(|u| (u - 1.0).abs() < 1e-12),
+        "{helicity:?}"
+    );
+    assert!(format!("{}", stats.stats_config).contains("$\\Upsilon_y$"));
+}
+
+#[test]
+fn stats_record_staggered_order_parameter_of_neel_state() {
+    let toml = r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]
+strength = -1.0
+
+[output]
+energy = true
+
+[[order_parameter]]
+weights = [1.0]
+wavevector = [0.5, 0.5, 0.0]
+"#;
+    let config = Config::new(toml).unwrap();
+    let stats_config = StatsConfig {
+        order_parameter_num: 1,
+        ..energy_stats_config(0)
+    };
+    let mut grid: Grid<IsingSpin, SmallRng> =
+        Grid::new(&config, SmallRng::seed_from_u64(42)).unwrap();
+    assert_eq!(grid.order_sizes(), vec![16.0]);
+    let mut stats: Stats<IsingSpin> = Stats::new(&config, 1.0, stats_config);
+    stats.order_size = grid.order_sizes();
+    for (i, spin) in grid.spins.iter_mut().enumerate() {
+        if (i / 4 + i % 4) % 2 == 1 {
+            *spin = -*spin;
+        }
+    }
+    grid.resync();
+
+    stats.record(&grid);
+    stats.record(&grid);
+
+    let result = stats.result();
+    assert!((result.order_mag.unwrap()[0] - 1.0).abs() < 1e-12);
+    assert!(result.order_sus.unwrap()[0].abs() < 1e-12);
+    assert!((result.order_binder.unwrap()[0] - 2.0 / 3.0).abs() < 1e-12);
+    assert!(format!("{}", stats.stats_config).contains("U_{4,w,0}"));
+}
+
+#[test]
+fn stats_record_energy_terms_and_exchange_entries() {
+    let config = ising_grid_config();
+    let stats_config = StatsConfig {
+        energy_terms: true,
+        exchange_entries: 1,
+        ..energy_stats_config(0)
+    };
+    let mut stats: Stats<IsingSpin> = Stats::new(&config, 1.0, stats_config);
+    let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, SmallRng::seed_from_u64(42)).unwrap();
+
+    stats.record(&grid);
+
+    let result = stats.result();
+    let energy = result.energy.unwrap();
+    assert!((result.exchange_energy.unwrap() - energy).abs() < 1e-12);
+    assert_eq!(result.anisotropy_energy, Some(0.0));
+    assert!((result.entry_energy.unwrap()[0] - energy).abs() < 1e-12);
+    let header = format!("{}", stats.stats_config);
+    assert!(header.contains("$E_{ex}$") && header.contains("$E_{J_0}$"));
+}
+
+#[test]
+fn stats_samples_are_moved_out_not_copied_into_results() {
+    let config = ising_grid_config();
+    let stats_config = StatsConfig {
+        record_samples: true,
+        ..energy_stats_config(0)
+    };
+    let mut stats: Stats<IsingSpin> = Stats::new(&config, 1.0, stats_config);
+    let grid: Grid<IsingSpin, SmallRng> = Grid::new(&config, SmallRng::seed_from_u64(42)).unwrap();
+    stats.record(&grid);
+    stats.record(&grid);
+
+    assert!(stats.result().samples.is_empty());
+    let samples = stats.take_samples();
+    assert_eq!(samples.len(), 2);
+    assert_eq!(
+        samples[0],
+        [grid.energy(), norm_sqr(grid.spin_sum()).sqrt()]
+    );
+    assert!(stats.samples.is_empty());
+}
@@ -0,0 +1,335 @@
+use std::f64::consts::PI;
+use std::fmt;
+use std::sync::Arc;
+
+use itertools::iproduct;
+
+use crate::config::{self, Config};
+use crate::spin::SpinState;
+
+type Phase = (f64, f64); // (cos, sin)
+
+fn mul(a: Phase, b: Phase) -> Phase {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+fn phase(angle: f64) -> Phase {
+    (angle.cos(), angle.sin())
+}
+
+/// A wavevector in reciprocal lattice units, with e^{iq·r_i} factorised into
+/// one table per cell axis and one factor per sublattice. The axis tables are
+/// left empty on the full grid, where the cell sum is an FFT instead.
+#[derive(Debug)]
+pub struct QPoint {
+    pub hkl: [f64; 3],
+    pub q_norm: f64, // |q| in 1/Å, or in 1/cell without [structure]
+    axis_phases: [Vec<Phase>; 3],
+    sublattice_phases: Vec<Phase>,
+}
+
+/// The wavevectors at which S(q) is measured: a path through the given
+/// vertices or every wavevector allowed by the grid. Sites sit at their
+/// real-space positions from `[structure]`, or at their unit-cell indices
+/// (all sublattices at the origin of the cell) when it is absent.
+#[derive(Debug)]
+pub struct QPoints {
+    pub points: Vec<QPoint>,
+    dim: [usize; 3],
+    sublattices: usize,
+    full_grid: bool,
+}
+
+impl QPoints {
+    pub fn new(
+        config: &Config,
+        structure_factor: &config::StructureFactor,
+    ) -> anyhow::Result<Self> {
+        let dim = config.grid.dimensions;
+        let sublattices = config.grid.sublattices;
+        let (cell, positions) = match &config.structure {
+            Some(structure) => {
+                let structure = structure.parse()?;
+                (structure.cell, structure.positions)
+            }
+            None => (
+                [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+                vec![[0.0; 3]; sublattices],
+            ),
+        };
+        let inverse = invert(cell)?;
+        // p = f · cell, so the fractional coordinates are f = p · cell⁻¹.
+        let fractional: Vec<[f64; 3]> = positions
+            .iter()
+            .map(|p| std::array::from_fn(|i| (0..3).map(|k| p[k] * inverse[k][i]).sum()))
+            .collect();
+
+        let hkls: Vec<[f64; 3]> = if structure_factor.full_grid {
+            iproduct!(0..dim[0], 0..dim[1], 0..dim[2])
+                .map(|(h, k, l)| {
+                    [
+                        h as f64 / dim[0] as f64,
+                        k as f64 / dim[1] as f64,
+                        l as f64 / dim[2] as f64,
+                    ]
+                })
+                .collect()
+        } else {
+            let path = &structure_factor.q_path;
+            let steps = structure_factor.path_points;
+            let mut hkls: Vec<[f64; 3]> = path
+                .windows(2)
+                .flat_map(|segment| {
+                    (0..steps).map(move |j| {
+                        let t = j as f64 / steps as f64;
+                        std::array::from_fn(|i| segment[0][i] + t * (segment[1][i] - segment[0][i]))
+                    })
+                })
+                .collect();
+            hkls.extend(path.last());
+            hkls
+        };
+
+        let full_grid = structure_factor.full_grid;
+        let points = hkls
+            .into_iter()
+            .map(|hkl| {
+                // q = 2π Σ_i h_i b_i, the b_i being the columns of cell⁻¹.
+                let q: Vec<f64> = (0..3)
+                    .map(|k| 2.0 * PI * (0..3).map(|i| hkl[i] * inverse[k][i]).sum::<f64>())
+                    .collect();
+                QPoint {
+                    hkl,
+                    q_norm: q.iter().map(|x| x * x).sum::<f64>().sqrt(),
+                    axis_phases: std::array::from_fn(|axis| {
+                        if full_grid {
+                            return Vec::new();
+                        }
+                        (0..dim[axis])
+                            .map(|x| phase(2.0 * PI * hkl[axis] * x as f64))
+                            .collect()
+                    }),
+                    sublattice_phases: fractional
+                        .iter()
+                        .map(|f| phase(2.0 * PI * (0..3).map(|i| hkl[i] * f[i]).sum::<f64>()))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            points,
+            dim,
+            sublattices,
+            full_grid,
+        })
+    }
+
+    /// |Σ_i s_i e^{iq·r_i}|² / N at every q point. A path costs O(N) per
+    /// point; the full grid costs O(N log N) in total through one FFT over the
+    /// cell indices per sublattice and spin component.
+    pub fn evaluate<S: SpinState>(&self, spins: &[S]) -> impl Iterator<Item = f64> + '_ {
+        let values: Vec<[f64; 3]> = spins.iter().map(|spin| spin.to_array()).collect();
+        let evaluated = if self.full_grid {
+            self.evaluate_grid(&values)
+        } else {
+            self.evaluate_path(&values)
+        };
+        evaluated.into_iter()
+    }
+
+    fn evaluate_grid(&self, values: &[[f64; 3]]) -> Vec<f64> {
+        let cells = self.points.len();
+        let mut sums = vec![[(0.0, 0.0); 3]; cells];
+        for (sub, block) in values.chunks_exact(cells).enumerate() {
+            for k in 0..3 {
+                let mut data: Vec<Phase> = block.iter().map(|value| (value[k], 0.0)).collect();
+                for axis in 0..3 {
+                    transform_axis(&mut data, self.dim, axis);
+                }
+                for ((sum, point), f) in sums.iter_mut().zip(&self.points).zip(data) {
+                    let term = mul(point.sublattice_phases[sub], f);
+                    sum[k].0 += term.0;
+                    sum[k].1 += term.1;
+                }
+            }
+        }
+        sums.iter()
+            .map(|sum| {
+                sum.iter().map(|(re, im)| re * re + im * im).sum::<f64>() / values.len() as f64
+            })
+            .collect()
+    }
+
+    fn evaluate_path(&self, values: &[[f64; 3]]) -> Vec<f64> {
+        let [nx, ny, nz] = self.dim;
+        self.points
+            .iter()
+            .map(|point| {
+                let (mut re, mut im) = ([0.0; 3], [0.0; 3]);
+                let mut index = 0;
+                for sub in 0..self.sublattices {
+                    for x in 0..nx {
+                        let px = mul(point.sublattice_phases[sub], point.axis_phases[0][x]);
+                        for y in 0..ny {
+                            let pxy = mul(px, point.axis_phases[1][y]);
+                            for z in 0..nz {
+                                let (cos, sin) = mul(pxy, point.axis_phases[2][z]);
+                                for k in 0..3 {
+                                    re[k] += values[index][k] * cos;
+                                    im[k] += values[index][k] * sin;
+                                }
+                                index += 1;
+                            }
+                        }
+                    }
+                }
+                (0..3).map(|k| re[k] * re[k] + im[k] * im[k]).sum::<f64>() / values.len() as f64
+            })
+            .collect()
+    }
+
+    pub fn header() -> String {
+        let mut header = format!("{:<12}", "#h");
+        for label in ["k", "l", "|q|(1/Å)", "S(q)"] {
+            header.push_str(&format!("\t{label:<12}"));
+        }
+        header
+    }
+
+    /// One row per q point, in the layout of `header`.
+    pub fn rows<'a>(&'a self, values: &'a [f64]) -> impl Iterator<Item = QRow<'a>> {
+        self.points.iter().zip(values).map(|(point, value)| QRow {
+            point,
+            value: *value,
+        })
+    }
+}
+
+pub struct QRow<'a> {
+    point: &'a QPoint,
+    value: f64,
+}
+
+impl fmt::Display for QRow<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use crate::utils::fmt_fixed_width;
+        let [h, k, l] = self.point.hkl;
+        write!(f, "{}", fmt_fixed_width(h, 12))?;
+        for value in [k, l, self.point.q_norm, self.value] {
+            write!(f, "\t{}", fmt_fixed_width(value, 12))?;
+        }
+        Ok(())
+    }
+}
+
+/// Running sums of S(q) over the measurements of one temperature, evaluated
+/// every `interval` recorded measurements.
+#[derive(Debug)]
+pub struct StructureFactorAccumulator {
+    pub q_points: Arc<QPoints>,
+    pub interval: usize,
+    pub sums: Vec<f64>,
+    pub samples: usize,
+    recorded: usize,
+}
+
+impl StructureFactorAccumulator {
+    pub fn new(q_points: Arc<QPoints>, interval: usize) -> Self {
+        let sums = vec![0.0; q_points.points.len()];
+        Self {
+            q_points,
+            interval,
+            sums,
+            samples: 0,
+            recorded: 0,
+        }
+    }
+
+    pub fn record<S: SpinState>(&mut self, spins: &[S]) {
+        self.recorded += 1;
+        if !(self.recorded - 1).is_multiple_of(self.interval) {
+            return;
+        }
+        for (sum, value) in self.sums.iter_mut().zip(self.q_points.evaluate(spins)) {
+            *sum += value;
+        }
+        self.samples += 1;
+    }
+
+    pub fn averages(&self) -> Vec<f64> {
+        self.sums
+            .iter()
+            .map(|sum| sum / self.samples as f64)
+            .collect()
+    }
+}
+
+/// Transforms every line of the (x, y, z)-ordered `data` along `axis` in
+/// place, with the e^{+2πi kx/n} sign of `QPoint::axis_phases`.
+fn transform_axis(data: &mut [Phase], dim: [usize; 3], axis: usize) {
+    let n = dim[axis];
+    let stride: usize = dim[axis + 1..].iter().product();
+    let mut line = vec![(0.0, 0.0); n];
+    for start in (0..data.len()).filter(|start| (start / stride).is_multiple_of(n)) {
+        for (x, value) in line.iter_mut().enumerate() {
+            *value = data[start + x * stride];
+        }
+        for (x, value) in fft(&line).into_iter().enumerate() {
+            data[start + x * stride] = value;
+        }
+    }
+}
+
+/// Mixed-radix Cooley-Tukey transform, splitting off the smallest prime factor
+/// of the length at each level and summing prime lengths directly.
+fn fft(input: &[Phase]) -> Vec<Phase> {
+    let n = input.len();
+    let Some(p) = (2..=n).find(|p| n.is_multiple_of(*p)) else {
+        return input.to_vec();
+    };
+    let twiddle = |j: usize| phase(2.0 * PI * (j % n) as f64 / n as f64);
+    if p == n {
+        return (0..n)
+            .map(|k| {
+                input.iter().enumerate().fold((0.0, 0.0), |acc, (j, &x)| {
+                    let term = mul(x, twiddle(j * k));
+                    (acc.0 + term.0, acc.1 + term.1)
+                })
+            })
+            .collect();
+    }
+    let m = n / p;
+    let parts: Vec<Vec<Phase>> = (0..p)
+        .map(|r| fft(&input.iter().skip(r).step_by(p).copied().collect::<Vec<_>>()))
+        .collect();
+    (0..n)
+        .map(|k| {
+            parts.iter().enumerate().fold((0.0, 0.0), |acc, (r, part)| {
+                let term = mul(part[k % m], twiddle(r * k));
+                (acc.0 + term.0, acc.1 + term.1)
+            })
+        })
+        .collect()
+}
+
+fn invert(m: [[f64; 3]; 3]) -> anyhow::Result<[[f64; 3]; 3]> {
+    let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+    if det.abs() <= f64::EPSILON {
+        anyhow::bail!("cell vectors must define a non-zero volume");
+    }
+    Ok(std::array::from_fn(|i| {
+        std::array::from_fn(|j| {
+            // Cofactor of m[j][i] over the determinant.
+            let (r0, r1) = ((j + 1) % 3, (j + 2) % 3);
+            let (c0, c1) = ((i + 1) % 3, (i + 2) % 3);
+            (m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]) / det
+        })
+    }))
+}
+
+#[cfg(test)]
+#[path = "structure_factor_tests.rs"]
+mod tests;
@@ -0,0 +1,188 @@
+use super::*;
+use crate::spin::{HeisenbergSpin, IsingSpin};
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+fn chain_config(structure: &str) -> Config {
+    grid_config([4, 4, 1], structure)
+}
+
+fn grid_config(dimensions: [usize; 3], structure: &str) -> Config {
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = {dimensions:?}
+sublattices = 2
+spin_magnitudes = [1.0, 1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 1
+offsets = [[0, 0, 0]]
+strength = 1.0
+
+[output]
+energy = true
+
+{structure}
+"#
+    );
+    Config::new(&toml).unwrap()
+}
+
+const CHAIN_STRUCTURE: &str = r#"
+[structure]
+cell = [[2.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 10.0]]
+positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]]
+"#;
+
+fn path(q_path: Vec<[f64; 3]>, path_points: usize) -> config::StructureFactor {
+    config::StructureFactor {
+        q_path,
+        path_points,
+        full_grid: false,
+        interval: 1,
+        save_directory: "structure_factor".into(),
+    }
+}
+
+fn full_grid() -> config::StructureFactor {
+    config::StructureFactor {
+        full_grid: true,
+        ..path(vec![], 1)
+    }
+}
+
+/// Sublattice 0 up, sublattice 1 down.
+fn staggered_spins() -> Vec<IsingSpin> {
+    let up = IsingSpin::along_z(1.0).unwrap();
+    (0..32).map(|i| if i < 16 { up } else { -up }).collect()
+}
+
+#[test]
+fn path_interpolates_segments_and_keeps_last_vertex() {
+    let q_points = QPoints::new(
+        &chain_config(""),
+        &path(vec![[0.0, 0.0, 0.0], [0.5, 0.0, 0.0], [0.5, 0.5, 0.0]], 2),
+    )
+    .unwrap();
+    let hkls: Vec<[f64; 3]> = q_points.points.iter().map(|p| p.hkl).collect();
+    assert_eq!(
+        hkls,
+        vec![
+            [0.0, 0.0, 0.0],
+            [0.25, 0.0, 0.0],
+            [0.5, 0.0, 0.0],
+            [0.5, 0.25, 0.0],
+            [0.5, 0.5, 0.0],
+        ]
+    );
+}
+
+#[test]
+fn full_grid_covers_allowed_wavevectors() {
+    let q_points = QPoints::new(&chain_config(""), &full_grid()).unwrap();
+    assert_eq!(q_points.points.len(), 16);
+    assert_eq!(q_points.points[5].hkl, [0.25, 0.25, 0.0]);
+}
+
+#[test]
+fn uniform_state_peaks_at_gamma_only() {
+    let q_points = QPoints::new(&chain_config(""), &full_grid()).unwrap();
+    let up = IsingSpin::along_z(1.0).unwrap();
+    let values: Vec<f64> = q_points.evaluate(&[up; 32]).collect();
+    assert!((values[0] - 32.0).abs() < 1e-9);
+    assert!(values[1..].iter().all(|v| v.abs() < 1e-9), "{values:?}");
+}
+
+#[test]
+fn full_grid_fft_matches_the_direct_sum() {
+    // Composite, prime and trivial axis lengths, two sublattices off the
+    // cell origin.
+    let config = grid_config([6, 5, 1], CHAIN_STRUCTURE);
+    let grid = QPoints::new(&config, &full_grid()).unwrap();
+    let hkls: Vec<[f64; 3]> = grid.points.iter().map(|p| p.hkl).collect();
+    let direct = QPoints::new(&config, &path(hkls, 1)).unwrap();
+    let mut rng = SmallRng::seed_from_u64(7);
+    let spins: Vec<HeisenbergSpin> = (0..60)
+        .map(|_| HeisenbergSpin::random(&mut rng, 1.0))
+        .collect();
+    for (fft, sum) in grid.evaluate(&spins).zip(direct.evaluate(&spins)) {
+        assert!((fft - sum).abs() < 1e-9, "{fft} != {sum}");
+    }
+}
+
+#[test]
+fn fft_matches_the_direct_transform() {
+    let input: Vec<Phase> = (0..12).map(|j| (j as f64 * 0.3, 1.0 - j as f64)).collect();
+    for (k, value) in fft(&input).into_iter().enumerate() {
+        let expected = input.iter().enumerate().fold((0.0, 0.0), |acc, (j, &x)| {
+            let term = mul(x, phase(2.0 * PI * (j * k) as f64 / 12.0));
+            (acc.0 + term.0, acc.1 + term.1)
+        });
+        assert!((value.0 - expected.0).abs() < 1e-9 && (value.1 - expected.1).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn real_space_positions_from_structure_are_used() {
+    let q = path(vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]], 1);
+    let spins = staggered_spins();
+
+    // Sublattices at x = 0 and x = 1 of a cell of length 2: the staggered
+    // state orders at h = 1, |q| = π.
+    let with_structure = QPoints::new(&chain_config(CHAIN_STRUCTURE), &q).unwrap();
+    let values: Vec<f64> = with_structure.evaluate(&spins).collect();
+    assert!(values[0].abs() < 1e-9);
+    assert!((values[1] - 32.0).abs() < 1e-9);
+    assert!((with_structure.points[1].q_norm - PI).abs() < 1e-12);
+
+    // Without [structure] both sublattices sit at the cell origin.
+    let without = QPoints::new(&chain_config(""), &q).unwrap();
+    let values: Vec<f64> = without.evaluate(&spins).collect();
+    assert!(values.iter().all(|v| v.abs() < 1e-9), "{values:?}");
+    assert!((without.points[1].q_norm - 2.0 * PI).abs() < 1e-12);
+}
+
+#[test]
+fn accumulator_evaluates_every_interval_measurements() {
+    let q_points = Arc::new(QPoints::new(&chain_config(""), &full_grid()).unwrap());
+    let mut accumulator = StructureFactorAccumulator::new(q_points, 2);
+    let up = IsingSpin::along_z(1.0).unwrap();
+    accumulator.record(&[up; 32]);
+    accumulator.record(&staggered_spins());
+    accumulator.record(&[up; 32]);
+    assert_eq!(accumulator.samples, 2);
+    assert!((accumulator.averages()[0] - 32.0).abs() < 1e-9);
+}
+
+#[test]
+fn rows_match_header_columns() {
+    let q_points = QPoints::new(&chain_config(""), &path(vec![[0.5, 0.0, 0.0]], 1)).unwrap();
+    let header = QPoints::header();
+    assert!(header.starts_with("#h"));
+    let rows: Vec<String> = q_points.rows(&[3.0]).map(|row| row.to_string()).collect();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].split('\t').count(), header.split('\t').count());
+}
+
+#[test]
+fn invert_gives_the_inverse_matrix() {
+    let m = [[2.0, 1.0, 0.0], [0.0, 1.0, 0.0], [1.0, 0.0, 4.0]];
+    let inverse = invert(m).unwrap();
+    for (i, j) in iproduct!(0..3, 0..3) {
+        let product: f64 = (0..3).map(|k| m[i][k] * inverse[k][j]).sum();
+        assert!((product - if i == j { 1.0 } else { 0.0 }).abs() < 1e-12);
+    }
+    assert!(invert([[1.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 0.0, 1.0]]).is_err());
+}
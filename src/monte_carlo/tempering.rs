@@ -0,0 +1,191 @@
+use crate::config::Ladder;
+
+/// Bounds on the pair acceptance entering the acceptance ladder, so that
+/// pairs that always or never swap still get a finite, non-zero weight.
+const MIN_ACCEPTANCE: f64 = 1e-4;
+const MAX_ACCEPTANCE: f64 = 1.0 - 1e-3;
+/// Smallest drop of f(T) credited to an interval of the round-trip ladder.
+const MIN_FRACTION_DROP: f64 = 1e-4;
+
+/// Which end of the ladder a replica visited last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// Not yet at either end.
+    Unlabelled,
+    /// Last at the lowest temperature, heading up.
+    Up,
+    /// Last at the highest temperature, heading down.
+    Down,
+}
+
+/// Swap and replica-diffusion bookkeeping of a parallel-tempering ladder,
+/// in temperature or in anisotropy scale.
+///
+/// Pairs are indexed by their lower ladder index t, swapping t and t+1.
+/// A round trip takes a replica from the lowest end to the highest and back.
+/// Replicas keep their global index, so on a two-dimensional grid a replica
+/// may leave one ladder and come back later with its label.
+#[derive(Debug)]
+pub struct SwapStatistics {
+    pub attempts: Vec<usize>,          // per pair
+    pub accepted: Vec<usize>,          // per pair
+    pub up: Vec<usize>,                // per temperature: visits by replicas heading up
+    pub down: Vec<usize>,              // per temperature: visits by replicas heading down
+    pub round_trips: Vec<usize>,       // per replica
+    pub round_trip_sweeps: Vec<usize>, // per replica, summed over its round trips
+    directions: Vec<Direction>,        // per replica
+    trip_start: Vec<Option<usize>>,    // per replica: sweep it last left the lowest temperature
+}
+
+impl SwapStatistics {
+    /// `ladder[t]` is the replica starting at ladder index t, out of `replicas`.
+    pub fn new(ladder: &[usize], replicas: usize) -> Self {
+        let n = ladder.len();
+        let mut directions = vec![Direction::Unlabelled; replicas];
+        let mut trip_start = vec![None; replicas];
+        directions[ladder[0]] = Direction::Up;
+        trip_start[ladder[0]] = Some(0);
+        directions[ladder[n - 1]] = Direction::Down;
+        Self {
+            attempts: vec![0; n - 1],
+            accepted: vec![0; n - 1],
+            up: vec![0; n],
+            down: vec![0; n],
+            round_trips: vec![0; replicas],
+            round_trip_sweeps: vec![0; replicas],
+            directions,
+            trip_start,
+        }
+    }
+
+    pub fn record_swap(&mut self, pair: usize, accepted: bool) {
+        self.attempts[pair] += 1;
+        if accepted {
+            self.accepted[pair] += 1;
+        }
+    }
+
+    /// Updates the replica labels after a round of swaps at `sweep`, with
+    /// `ladder[t]` the replica now at ladder index t.
+    pub fn record_positions(&mut self, ladder: &[usize], sweep: usize) {
+        let bottom = ladder[0];
+        if self.directions[bottom] != Direction::Up {
+            if self.directions[bottom] == Direction::Down
+                && let Some(start) = self.trip_start[bottom]
+            {
+                self.round_trips[bottom] += 1;
+                self.round_trip_sweeps[bottom] += sweep - start;
+            }
+            self.directions[bottom] = Direction::Up;
+            self.trip_start[bottom] = Some(sweep);
+        }
+        self.directions[ladder[ladder.len() - 1]] = Direction::Down;
+
+        for (t, &r) in ladder.iter().enumerate() {
+            match self.directions[r] {
+                Direction::Up => self.up[t] += 1,
+                Direction::Down => self.down[t] += 1,
+                Direction::Unlabelled => {}
+            }
+        }
+    }
+
+    /// Clears the counts but keeps every replica's direction and trip in
+    /// progress.
+    pub fn reset(&mut self) {
+        for counts in [
+            &mut self.attempts,
+            &mut self.accepted,
+            &mut self.up,
+            &mut self.down,
+            &mut self.round_trips,
+            &mut self.round_trip_sweeps,
+        ] {
+            counts.fill(0);
+        }
+    }
+
+    /// Fraction of accepted swaps of a pair; NaN before any attempt.
+    pub fn acceptance(&self, pair: usize) -> f64 {
+        self.accepted[pair] as f64 / self.attempts[pair] as f64
+    }
+
+    /// f(t), the fraction of labelled visits to ladder index t by replicas
+    /// heading up; NaN before any labelled visit.
+    pub fn fraction_up(&self, t: usize) -> f64 {
+        self.up[t] as f64 / (self.up[t] + self.down[t]) as f64
+    }
+
+    /// Mean sweeps per round trip of a replica; NaN before its first one.
+    pub fn mean_round_trip(&self, replica: usize) -> f64 {
+        self.round_trip_sweeps[replica] as f64 / self.round_trips[replica] as f64
+    }
+
+    /// The ladder `ladder` asks for given the statistics so far, with both
+    /// ends kept. None for a fixed ladder, or while a pair has not been tried
+    /// (acceptance) or a temperature has not been visited by a labelled
+    /// replica (round trip).
+    ///
+    /// Each interval gets a weight and the new temperatures split the total
+    /// weight evenly, the weight density being uniform within an interval.
+    /// For equal acceptance the weight is sqrt(-ln A), which adds up along
+    /// the ladder since -ln A grows as the square of the inverse-temperature
+    /// step. For round trips it is sqrt(|Δf|), which places temperatures with
+    /// density ∝ sqrt(|df/dT| / ΔT), the optimum of Katzgraber et al.
+    pub fn retuned(&self, ladder: Ladder, temperatures: &[f64]) -> Option<Vec<f64>> {
+        let weights: Vec<f64> = match ladder {
+            Ladder::Fixed => return None,
+            Ladder::Acceptance => {
+                if self.attempts.contains(&0) {
+                    return None;
+                }
+                (0..self.attempts.len())
+                    .map(|pair| {
+                        let acceptance =
+                            self.acceptance(pair).clamp(MIN_ACCEPTANCE, MAX_ACCEPTANCE);
+                        (-acceptance.ln()).sqrt()
+                    })
+                    .collect()
+            }
+            Ladder::RoundTrip => {
+                if (0..self.up.len()).any(|t| self.up[t] + self.down[t] == 0) {
+                    return None;
+                }
+                (0..self.attempts.len())
+                    .map(|pair| {
+                        let drop = (self.fraction_up(pair) - self.fraction_up(pair + 1)).abs();
+                        drop.max(MIN_FRACTION_DROP).sqrt()
+                    })
+                    .collect()
+            }
+        };
+        Some(redistribute(temperatures, &weights))
+    }
+}
+
+/// Temperatures splitting the total interval weight evenly; `weights[i]`
+/// belongs to [T_i, T_{i+1}] and must be positive.
+fn redistribute(temperatures: &[f64], weights: &[f64]) -> Vec<f64> {
+    let n = temperatures.len();
+    let total: f64 = weights.iter().sum();
+    let mut ladder = Vec::with_capacity(n);
+    ladder.push(temperatures[0]);
+    let mut interval = 0;
+    let mut below = 0.0; // weight of the intervals before `interval`
+    for k in 1..n - 1 {
+        let target = total * k as f64 / (n - 1) as f64;
+        while interval < n - 2 && below + weights[interval] <= target {
+            below += weights[interval];
+            interval += 1;
+        }
+        let along = ((target - below) / weights[interval]).min(1.0);
+        let (low, high) = (temperatures[interval], temperatures[interval + 1]);
+        ladder.push(low + along * (high - low));
+    }
+    ladder.push(temperatures[n - 1]);
+    ladder
+}
+
+#[cfg(test)]
+#[path = "tempering_tests.rs"]
+mod tests;
@@ -0,0 +1,123 @@
+use super::*;
+
+#[test]
+fn acceptance_counts_per_pair() {
+    let mut swaps = SwapStatistics::new(&[0, 1, 2], 3);
+    swaps.record_swap(0, true);
+    swaps.record_swap(0, false);
+    swaps.record_swap(1, true);
+    assert_eq!(swaps.acceptance(0), 0.5);
+    assert_eq!(swaps.acceptance(1), 1.0);
+
+    swaps.reset();
+    assert!(swaps.acceptance(0).is_nan());
+}
+
+#[test]
+fn replica_walking_up_and_down_completes_a_round_trip() {
+    let mut swaps = SwapStatistics::new(&[0, 1, 2], 3);
+    // Replica 0 climbs from the bottom to the top and back.
+    swaps.record_positions(&[1, 0, 2], 10);
+    swaps.record_positions(&[1, 2, 0], 20);
+    swaps.record_positions(&[2, 1, 0], 30);
+    assert_eq!(swaps.round_trips, [0, 0, 0]);
+    swaps.record_positions(&[0, 1, 2], 50);
+    assert_eq!(swaps.round_trips, [1, 0, 0]);
+    assert_eq!(swaps.mean_round_trip(0), 50.0);
+    // Replica 2 only went down, which is not a round trip.
+    assert!(swaps.mean_round_trip(2).is_nan());
+}
+
+#[test]
+fn reset_keeps_trips_in_progress() {
+    let mut swaps = SwapStatistics::new(&[0, 1], 2);
+    swaps.record_positions(&[1, 0], 10);
+    swaps.reset();
+    swaps.record_positions(&[0, 1], 30);
+    assert_eq!(swaps.round_trips, [1, 0]);
+    assert_eq!(swaps.round_trip_sweeps, [30, 0]);
+}
+
+#[test]
+fn fraction_up_falls_from_bottom_to_top() {
+    let mut swaps = SwapStatistics::new(&[0, 1, 2], 3);
+    swaps.record_positions(&[0, 1, 2], 1);
+    swaps.record_positions(&[1, 0, 2], 2);
+    // Replica 1 is unlabelled until it reaches an end.
+    assert_eq!(swaps.up, [2, 1, 0]);
+    assert_eq!(swaps.down, [0, 0, 2]);
+    assert_eq!(swaps.fraction_up(0), 1.0);
+    assert_eq!(swaps.fraction_up(1), 1.0);
+    assert_eq!(swaps.fraction_up(2), 0.0);
+}
+
+#[test]
+fn ladders_of_a_grid_label_replicas_by_global_index() {
+    // The column of a 2 x 2 grid holding replicas 1 and 3.
+    let mut swaps = SwapStatistics::new(&[1, 3], 4);
+    swaps.record_positions(&[3, 1], 10);
+    swaps.record_positions(&[1, 3], 20);
+    assert_eq!(swaps.round_trips, [0, 1, 0, 0]);
+    assert_eq!(swaps.mean_round_trip(1), 20.0);
+    assert_eq!(swaps.up, [2, 0]);
+    assert_eq!(swaps.down, [0, 2]);
+}
+
+#[test]
+fn redistribute_with_equal_weights_keeps_the_ladder() {
+    let temperatures = [1.0, 2.0, 4.0, 8.0];
+    let ladder = redistribute(&temperatures, &[1.0, 1.0, 1.0]);
+    for (new, old) in ladder.iter().zip(temperatures) {
+        assert!((new - old).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn redistribute_crowds_heavy_intervals() {
+    // The second interval holds half the weight, so two of the three inner
+    // temperatures fall inside it.
+    let ladder = redistribute(&[0.0, 1.0, 2.0, 3.0, 4.0], &[1.0, 4.0, 2.0, 1.0]);
+    let expected = [0.0, 1.25, 1.75, 2.5, 4.0];
+    for (new, old) in ladder.iter().zip(expected) {
+        assert!((new - old).abs() < 1e-12, "{ladder:?}");
+    }
+    assert!(ladder.is_sorted_by(|a, b| a < b));
+}
+
+#[test]
+fn acceptance_ladder_narrows_the_rejecting_interval() {
+    let mut swaps = SwapStatistics::new(&[0, 1, 2], 3);
+    for i in 0..100 {
+        swaps.record_swap(0, true);
+        swaps.record_swap(1, i % 10 == 0);
+    }
+    let ladder = swaps.retuned(Ladder::Acceptance, &[1.0, 2.0, 3.0]).unwrap();
+    assert_eq!(ladder[0], 1.0);
+    assert_eq!(ladder[2], 3.0);
+    assert!(ladder[1] > 2.0, "{ladder:?}");
+    assert!(swaps.retuned(Ladder::Fixed, &[1.0, 2.0, 3.0]).is_none());
+}
+
+#[test]
+fn retuned_waits_for_statistics() {
+    let mut swaps = SwapStatistics::new(&[0, 1, 2], 3);
+    swaps.record_swap(0, true);
+    assert!(
+        swaps
+            .retuned(Ladder::Acceptance, &[1.0, 2.0, 3.0])
+            .is_none()
+    );
+    // The middle temperature has not been visited by a labelled replica.
+    swaps.record_positions(&[0, 1, 2], 1);
+    assert!(swaps.retuned(Ladder::RoundTrip, &[1.0, 2.0, 3.0]).is_none());
+}
+
+#[test]
+fn round_trip_ladder_follows_the_drop_of_f() {
+    let mut swaps = SwapStatistics::new(&[0, 1, 2], 3);
+    // f = 1, 0.9, 0: the upper interval is the bottleneck.
+    swaps.up = vec![10, 9, 0];
+    swaps.down = vec![0, 1, 10];
+    let ladder = swaps.retuned(Ladder::RoundTrip, &[1.0, 2.0, 3.0]).unwrap();
+    assert!(ladder[1] > 2.0, "{ladder:?}");
+}
@@ -0,0 +1,87 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+
+use anyhow::Context;
+use tracing::warn;
+
+/// Streams one CSV row per recorded sweep: the sweep number, the energy and
+/// the magnetization vector per spin, then each group's magnetization vector
+/// per spin of the group.
+#[derive(Debug)]
+pub struct TimeSeriesWriter {
+    path: String,
+    writer: Option<BufWriter<File>>, // dropped after the first I/O error
+}
+
+impl TimeSeriesWriter {
+    pub fn create(path: &str, group_num: usize) -> anyhow::Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create time series file: {path}"))?;
+        let mut writer = BufWriter::new(file);
+        let mut header = "sweep,energy,mx,my,mz".to_string();
+        for i in 0..group_num {
+            header.push_str(&format!(",m{i}x,m{i}y,m{i}z"));
+        }
+        writeln!(writer, "{header}")?;
+        Ok(Self {
+            path: path.to_string(),
+            writer: Some(writer),
+        })
+    }
+
+    pub fn write_row(&mut self, sweep: usize, values: &[f64]) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        let mut result = write!(writer, "{sweep}");
+        for value in values {
+            result = result.and_then(|_| write!(writer, ",{value}"));
+        }
+        if let Err(e) = result.and_then(|_| writeln!(writer)) {
+            warn!(
+                "Failed to write time series file {}, stopping it: {e}",
+                self.path
+            );
+            self.writer = None;
+        }
+    }
+
+    /// Moves the file to `path` and keeps appending to it there. The file is
+    /// closed across the rename, so this also works where open files cannot
+    /// be renamed.
+    pub fn rename(&mut self, path: &str) -> anyhow::Result<()> {
+        self.flush();
+        let open = self.writer.take().is_some();
+        std::fs::rename(&self.path, path).with_context(|| {
+            format!("Failed to rename time series file {} to {path}", self.path)
+        })?;
+        self.path = path.to_string();
+        if open {
+            let file = OpenOptions::new()
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to reopen time series file: {path}"))?;
+            self.writer = Some(BufWriter::new(file));
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) {
+        if let Some(writer) = &mut self.writer
+            && let Err(e) = writer.flush()
+        {
+            warn!("Failed to flush time series file {}: {e}", self.path);
+            self.writer = None;
+        }
+    }
+}
+
+impl Drop for TimeSeriesWriter {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+#[cfg(test)]
+#[path = "time_series_tests.rs"]
+mod tests;
@@ -0,0 +1,49 @@
+use super::*;
+
+fn temp_path(prefix: &str) -> std::path::PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}_{}_{nanos}.csv", std::process::id()))
+}
+
+#[test]
+fn writes_header_with_group_columns_and_rows() {
+    let path = temp_path("spinmc_time_series");
+    {
+        let mut ts = TimeSeriesWriter::create(path.to_str().unwrap(), 2).unwrap();
+        ts.write_row(3, &[-1.5, 0.0, 0.0, 1.0, 0.5, 0.5, 0.0, 0.0, 0.0, -1.0]);
+        ts.write_row(4, &[-2.0, 0.0, 0.0, 0.25, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+    }
+    let content = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines[0], "sweep,energy,mx,my,mz,m0x,m0y,m0z,m1x,m1y,m1z");
+    assert_eq!(lines[1], "3,-1.5,0,0,1,0.5,0.5,0,0,0,-1");
+    assert_eq!(lines.len(), 3);
+    assert!(lines[2].starts_with("4,-2,"));
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn create_fails_for_missing_directory() {
+    let path = temp_path("spinmc_missing_dir").join("series.csv");
+    assert!(TimeSeriesWriter::create(path.to_str().unwrap(), 0).is_err());
+}
+
+#[test]
+fn rename_moves_the_file_and_keeps_appending() {
+    let path = temp_path("spinmc_time_series_old");
+    let renamed = temp_path("spinmc_time_series_new");
+    {
+        let mut ts = TimeSeriesWriter::create(path.to_str().unwrap(), 0).unwrap();
+        ts.write_row(1, &[-1.0, 0.0, 0.0, 1.0]);
+        ts.rename(renamed.to_str().unwrap()).unwrap();
+        ts.write_row(2, &[-2.0, 0.0, 0.0, 1.0]);
+    }
+    assert!(!path.exists());
+    let content = std::fs::read_to_string(&renamed).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines, ["sweep,energy,mx,my,mz", "1,-1,0,0,1", "2,-2,0,0,1"]);
+    let _ = std::fs::remove_file(renamed);
+}
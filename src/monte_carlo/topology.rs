@@ -0,0 +1,220 @@
+use std::f64::consts::PI;
+use std::fmt;
+use std::sync::Arc;
+
+use itertools::iproduct;
+
+use crate::config::Config;
+use crate::lattice::safe_coord_to_index;
+use crate::spin::SpinState;
+
+/// Triangulation of every layer of the grid, a layer being one sublattice in
+/// one a1–a2 plane (fixed z). Each cell is split along its shorter diagonal
+/// into two triangles, counterclockwise about the normal a1 × a2 taken on the
+/// side of a3, so that a skyrmion has charge ±1 on any Bravais lattice.
+#[derive(Debug)]
+pub struct Triangulation {
+    pub dim: [usize; 3],
+    pub layers: usize,
+    triangles: Vec<Triangle>,
+}
+
+#[derive(Debug)]
+struct Triangle {
+    corners: [usize; 3],
+    anchor: usize, // site at the origin of the cell the triangle belongs to
+    layer: usize,
+}
+
+impl Triangulation {
+    pub fn new(config: &Config) -> anyhow::Result<Self> {
+        let dim = config.grid.dimensions;
+        let sublattices = config.grid.sublattices;
+        let pbc = config.grid.periodic_boundary;
+        let cell = match &config.structure {
+            Some(structure) => structure.parse()?.cell,
+            None => [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        };
+        let [a1, a2, a3] = cell;
+        let norm_sqr = |v: [f64; 3]| v.iter().map(|x| x * x).sum::<f64>();
+        let short_diagonal = norm_sqr(std::array::from_fn(|k| a1[k] + a2[k]))
+            <= norm_sqr(std::array::from_fn(|k| a1[k] - a2[k]));
+        let normal = [
+            a1[1] * a2[2] - a1[2] * a2[1],
+            a1[2] * a2[0] - a1[0] * a2[2],
+            a1[0] * a2[1] - a1[1] * a2[0],
+        ];
+        let reversed = (0..3).map(|k| normal[k] * a3[k]).sum::<f64>() < 0.0;
+
+        // Corners as (dx, dy) from the cell origin, counterclockwise about a1 × a2.
+        let shapes: [[[isize; 2]; 3]; 2] = if short_diagonal {
+            [[[0, 0], [1, 0], [1, 1]], [[0, 0], [1, 1], [0, 1]]]
+        } else {
+            [[[0, 0], [1, 0], [0, 1]], [[1, 0], [1, 1], [0, 1]]]
+        };
+
+        let mut triangles = Vec::new();
+        for (sub, x, y, z) in iproduct!(0..sublattices, 0..dim[0], 0..dim[1], 0..dim[2]) {
+            let site = |[dx, dy]: [isize; 2]| {
+                safe_coord_to_index(
+                    [x as isize + dx, y as isize + dy, z as isize],
+                    sub,
+                    dim,
+                    sublattices,
+                    pbc,
+                )
+            };
+            let Some(anchor) = site([0, 0]) else {
+                continue;
+            };
+            for shape in shapes {
+                let (Some(a), Some(b), Some(c)) = (site(shape[0]), site(shape[1]), site(shape[2]))
+                else {
+                    continue;
+                };
+                triangles.push(Triangle {
+                    corners: if reversed { [a, c, b] } else { [a, b, c] },
+                    anchor,
+                    layer: sub * dim[2] + z,
+                });
+            }
+        }
+
+        Ok(Self {
+            dim,
+            layers: sublattices * dim[2],
+            triangles,
+        })
+    }
+
+    /// (sublattice, x, y, z) of a site index.
+    pub fn site_coords(&self, site: usize) -> [usize; 4] {
+        let [_, ny, nz] = self.dim;
+        let cells = self.dim[0] * ny * nz;
+        let cell = site % cells;
+        [site / cells, cell / (ny * nz), cell / nz % ny, cell % nz]
+    }
+}
+
+/// Signed solid angle spanned by three unit vectors (Berg–Lüscher), in (-2π, 2π).
+fn solid_angle(a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> f64 {
+    let dot = |u: [f64; 3], v: [f64; 3]| u[0] * v[0] + u[1] * v[1] + u[2] * v[2];
+    let triple = a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+        + a[2] * (b[0] * c[1] - b[1] * c[0]);
+    2.0 * triple.atan2(1.0 + dot(a, b) + dot(b, c) + dot(c, a))
+}
+
+/// Running sums of the per-layer topological charge Q_l of one temperature
+/// and, optionally, of the charge density anchored at every site.
+#[derive(Debug)]
+pub struct TopologyAccumulator {
+    pub triangulation: Arc<Triangulation>,
+    q_sum: Vec<f64>,
+    q_abs_sum: Vec<f64>,
+    q2_sum: Vec<f64>,
+    density_sums: Option<Vec<f64>>,
+    steps: usize,
+}
+
+impl TopologyAccumulator {
+    pub fn new(triangulation: Arc<Triangulation>, density_map: bool, size: usize) -> Self {
+        let layers = triangulation.layers;
+        Self {
+            triangulation,
+            q_sum: vec![0.0; layers],
+            q_abs_sum: vec![0.0; layers],
+            q2_sum: vec![0.0; layers],
+            density_sums: density_map.then(|| vec![0.0; size]),
+            steps: 0,
+        }
+    }
+
+    pub fn record<S: SpinState>(&mut self, spins: &[S]) {
+        let unit: Vec<[f64; 3]> = spins
+            .iter()
+            .map(|spin| {
+                let norm = spin.norm();
+                spin.to_array().map(|component| component / norm)
+            })
+            .collect();
+        let mut charges = vec![0.0; self.triangulation.layers];
+        for triangle in &self.triangulation.triangles {
+            let [a, b, c] = triangle.corners;
+            let q = solid_angle(unit[a], unit[b], unit[c]) / (4.0 * PI);
+            charges[triangle.layer] += q;
+            if let Some(density) = &mut self.density_sums {
+                density[triangle.anchor] += q;
+            }
+        }
+        for (layer, q) in charges.into_iter().enumerate() {
+            self.q_sum[layer] += q;
+            self.q_abs_sum[layer] += q.abs();
+            self.q2_sum[layer] += q * q;
+        }
+        self.steps += 1;
+    }
+
+    pub fn result(&self) -> TopologyResult {
+        let n = self.steps as f64;
+        let layers = self.triangulation.layers as f64;
+        let sites_per_layer = (self.triangulation.dim[0] * self.triangulation.dim[1]) as f64;
+        let layer_charges: Vec<f64> = self.q_sum.iter().map(|q| q / n).collect();
+        TopologyResult {
+            charge: layer_charges.iter().sum::<f64>() / layers,
+            charge_abs: self.q_abs_sum.iter().sum::<f64>() / n / layers,
+            susceptibility: layer_charges
+                .iter()
+                .zip(&self.q2_sum)
+                .map(|(q_avg, q2)| q2 / n - q_avg * q_avg)
+                .sum::<f64>()
+                / layers
+                / sites_per_layer,
+            layer_charges,
+            density: self
+                .density_sums
+                .as_ref()
+                .map(|density| density.iter().map(|d| d / n).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TopologyResult {
+    pub charge: f64,         // <Q_l>, averaged over layers
+    pub charge_abs: f64,     // <|Q_l|>, averaged over layers
+    pub susceptibility: f64, // (<Q_l^2> - <Q_l>^2) / N_layer, averaged over layers
+    pub layer_charges: Vec<f64>,
+    pub density: Vec<f64>, // <q> per site, when the density map is enabled
+}
+
+impl TopologyResult {
+    /// Column header matching the `Display` layout, after the temperature.
+    pub fn header(layers: usize) -> String {
+        let mut header = format!("{:<12}", "#T(K)");
+        for label in ["Q", "|Q|", "$\\chi_Q$"] {
+            header.push_str(&format!("\t{label:<12}"));
+        }
+        for layer in 0..layers {
+            header.push_str(&format!("\t{:<12}", format!("Q_{layer}")));
+        }
+        header
+    }
+}
+
+impl fmt::Display for TopologyResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use crate::utils::fmt_fixed_width;
+        write!(f, "{}", fmt_fixed_width(self.charge, 12))?;
+        write!(f, "\t{}", fmt_fixed_width(self.charge_abs, 12))?;
+        write!(f, "\t{}", fmt_fixed_width(self.susceptibility, 12))?;
+        for q in &self.layer_charges {
+            write!(f, "\t{}", fmt_fixed_width(*q, 12))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "topology_tests.rs"]
+mod tests;
@@ -0,0 +1,167 @@
+use super::*;
+use crate::spin::{HeisenbergSpin, tolerance};
+
+const L: usize = 12;
+
+fn plane_config(cell: &str, periodic_boundary: &str) -> Config {
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "heisenberg"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [{L}, {L}, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = {periodic_boundary}
+
+[structure]
+cell = {cell}
+positions = [[0.0, 0.0, 0.0]]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+"#
+    );
+    Config::new(&toml).unwrap()
+}
+
+const SQUARE: &str = "[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 10.0]]";
+const SQUARE_FLIPPED: &str = "[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, -10.0]]";
+const TRIANGULAR: &str = "[[1.0, 0.0, 0.0], [0.5, 0.8660254037844386, 0.0], [0.0, 0.0, 10.0]]";
+
+fn spin([x, y, z]: [f64; 3]) -> HeisenbergSpin {
+    HeisenbergSpin::along_x(x).unwrap()
+        + HeisenbergSpin::along_y(y).unwrap()
+        + HeisenbergSpin::along_z(z).unwrap()
+}
+
+/// A skyrmion of radius `radius` centred in the plane, core down and
+/// background up, with sites at their real-space positions in `cell`.
+fn skyrmion(a1: [f64; 2], a2: [f64; 2], radius: f64) -> Vec<HeisenbergSpin> {
+    let centre = (L as f64 - 1.0) / 2.0;
+    iproduct!(0..L, 0..L)
+        .map(|(x, y)| {
+            let (u, v) = (x as f64 - centre, y as f64 - centre);
+            let (px, py) = (u * a1[0] + v * a2[0], u * a1[1] + v * a2[1]);
+            let r = (px * px + py * py).sqrt();
+            let theta = if r < radius {
+                PI * (1.0 - r / radius)
+            } else {
+                0.0
+            };
+            let phi = py.atan2(px);
+            spin([
+                theta.sin() * phi.cos(),
+                theta.sin() * phi.sin(),
+                theta.cos(),
+            ])
+        })
+        .collect()
+}
+
+fn charge(config: &Config, spins: &[HeisenbergSpin]) -> f64 {
+    let mut accumulator =
+        TopologyAccumulator::new(Arc::new(Triangulation::new(config).unwrap()), false, L * L);
+    accumulator.record(spins);
+    accumulator.result().charge
+}
+
+#[test]
+fn solid_angle_of_octant_is_an_eighth_of_the_sphere() {
+    let omega = solid_angle([1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]);
+    assert!((omega - PI / 2.0).abs() < 1e-12);
+    let omega = solid_angle([1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]);
+    assert!((omega + PI / 2.0).abs() < 1e-12);
+}
+
+#[test]
+fn triangulation_covers_every_cell_with_two_triangles() {
+    let periodic = Triangulation::new(&plane_config(SQUARE, "[true, true, false]")).unwrap();
+    assert_eq!(periodic.layers, 1);
+    assert_eq!(periodic.triangles.len(), 2 * L * L);
+
+    let open = Triangulation::new(&plane_config(SQUARE, "[false, false, false]")).unwrap();
+    assert_eq!(open.triangles.len(), 2 * (L - 1) * (L - 1));
+}
+
+#[test]
+fn uniform_state_has_zero_charge() {
+    let config = plane_config(SQUARE, "[true, true, false]");
+    let spins = vec![spin([0.0, 0.0, 1.0]); L * L];
+    assert!(charge(&config, &spins).abs() < 1e-12);
+}
+
+#[test]
+fn skyrmion_has_unit_charge_on_square_and_triangular_lattices() {
+    let square = charge(
+        &plane_config(SQUARE, "[true, true, false]"),
+        &skyrmion([1.0, 0.0], [0.0, 1.0], 4.5),
+    );
+    assert!((square.abs() - 1.0).abs() < tolerance(1e-9), "{square}");
+
+    let triangular = charge(
+        &plane_config(TRIANGULAR, "[true, true, false]"),
+        &skyrmion([1.0, 0.0], [0.5, 0.8660254037844386], 4.5),
+    );
+    assert!(
+        (triangular - square).abs() < tolerance(1e-9),
+        "{triangular}"
+    );
+}
+
+#[test]
+fn charge_sign_follows_the_handedness_of_the_cell() {
+    let spins = skyrmion([1.0, 0.0], [0.0, 1.0], 4.5);
+    let right = charge(&plane_config(SQUARE, "[true, true, false]"), &spins);
+    let left = charge(&plane_config(SQUARE_FLIPPED, "[true, true, false]"), &spins);
+    assert!((right + left).abs() < 1e-9);
+}
+
+#[test]
+fn result_averages_layers_and_measurements() {
+    let config = plane_config(SQUARE, "[true, true, false]");
+    let triangulation = Arc::new(Triangulation::new(&config).unwrap());
+    let mut accumulator = TopologyAccumulator::new(triangulation, true, L * L);
+    let skyrmion = skyrmion([1.0, 0.0], [0.0, 1.0], 4.5);
+    let uniform = vec![spin([0.0, 0.0, 1.0]); L * L];
+    accumulator.record(&skyrmion);
+    accumulator.record(&uniform);
+
+    let result = accumulator.result();
+    let q = charge(&config, &skyrmion);
+    assert!((result.charge - q / 2.0).abs() < tolerance(1e-9));
+    assert!((result.charge_abs - 0.5).abs() < tolerance(1e-9));
+    let expected_chi = (0.5 - 0.25) / (L * L) as f64;
+    assert!((result.susceptibility - expected_chi).abs() < 1e-9);
+    assert_eq!(result.layer_charges.len(), 1);
+    assert_eq!(result.density.len(), L * L);
+    assert!((result.density.iter().sum::<f64>() - q / 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn site_coords_inverts_the_grid_index() {
+    let triangulation = Triangulation::new(&plane_config(SQUARE, "[true, true, false]")).unwrap();
+    assert_eq!(triangulation.site_coords(0), [0, 0, 0, 0]);
+    assert_eq!(triangulation.site_coords(L + 3), [0, 1, 3, 0]);
+}
+
+#[test]
+fn header_lists_layer_columns() {
+    let header = TopologyResult::header(2);
+    assert!(header.starts_with("#T(K)"));
+    assert!(header.contains("Q_0"));
+    assert!(header.contains("Q_1"));
+}
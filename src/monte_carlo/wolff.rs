@@ -1,37 +1,90 @@
-use crate::{calculators::HamiltonianConfig, spin::SpinState};
+use crate::{
+    calculators::HamiltonianConfig,
+    spin::{SpinState, widen},
+};
 use rand::RngExt;
-use std::collections::VecDeque;
 
 use super::MonteCarlo;
+
+/// The last cluster a Wolff step built, and the unit axis it was reflected
+/// about; enough to form the improved estimators in `Stats::record_cluster`.
+#[derive(Clone, Copy, Debug)]
+pub struct Cluster<'a> {
+    pub sites: &'a [usize],
+    pub axis: [f64; 3],
+}
+
 pub struct Wolff<R: rand::Rng> {
     pub rng: R,
     pub beta: f64,
     pub ham_config: HamiltonianConfig,
+    // Reused between steps so that a small cluster costs O(|C|), not O(N):
+    // site i is in the current cluster iff stamps[i] == generation.
+    stamps: Vec<u32>,
+    generation: u32,
+    cluster: Vec<usize>, // sites of the last cluster, in the order they were added
+    axis: [f64; 3],      // unit reflection axis of the last cluster
+}
+
+impl<R: rand::Rng> Wolff<R> {
+    pub fn new(rng: R, beta: f64, ham_config: HamiltonianConfig) -> Self {
+        Self {
+            rng,
+            beta,
+            ham_config,
+            stamps: Vec::new(),
+            generation: 0,
+            cluster: Vec::new(),
+            axis: [0.0; 3],
+        }
+    }
+
+    /// Sites of the cluster built by the last step, whether or not its flip
+    /// was accepted.
+    pub fn cluster(&self) -> Cluster<'_> {
+        Cluster {
+            sites: &self.cluster,
+            axis: self.axis,
+        }
+    }
+
+    fn in_cluster(&self, site: usize) -> bool {
+        self.stamps[site] == self.generation
+    }
+
+    fn start_cluster(&mut self, size: usize) {
+        if self.stamps.len() != size || self.generation == u32::MAX {
+            self.stamps.clear();
+            self.stamps.resize(size, 0);
+            self.generation = 0;
+        }
+        self.generation += 1;
+        self.cluster.clear();
+    }
 }
+
 impl<S: SpinState, R: rand::Rng> MonteCarlo<S, R> for Wolff<R> {
     fn step(&mut self, grid: &mut crate::lattice::Grid<S, R>) -> usize {
         let init_spin_index = self.rng.random_range(0..grid.size);
 
         let axis = -grid.spins[init_spin_index].perturb(&mut self.rng, 1.0);
+        let norm = axis.norm();
+        self.axis = axis.to_array().map(|x| x / norm);
+
+        // Breadth-first growth, with `cluster` doubling as the queue.
+        self.start_cluster(grid.size);
+        self.stamps[init_spin_index] = self.generation;
+        self.cluster.push(init_spin_index);
+        let mut head = 0;
 
-        let mut visited = vec![false; grid.size];
-        let mut cluster = Vec::new();
-        let mut queue = VecDeque::new();
-        visited[init_spin_index] = true;
-        queue.push_back(init_spin_index);
-
-        while let Some(site) = queue.pop_front() {
-            cluster.push(site);
-            for (neighbor, j) in grid.calc_inputs[site]
-                .exchange_neighbor_index
-                .iter()
-                .zip(grid.calc_inputs[site].exchanges.iter())
-            {
-                if visited[*neighbor] {
+        while let Some(&site) = self.cluster.get(head) {
+            head += 1;
+            for (neighbor, j) in grid.bonds(site).iter() {
+                if self.in_cluster(neighbor) {
                     continue;
                 }
 
-                let neighbor_spin = &grid.spins[*neighbor];
+                let neighbor_spin = &grid.spins[neighbor];
 
                 if !neighbor_spin.same_side(&grid.spins[site]) {
                     continue;
@@ -41,20 +94,20 @@ impl<S: SpinState, R: rand::Rng> MonteCarlo<S, R> for Wolff<R> {
                     neighbor_spin,
                     &axis,
                     self.beta,
-                    *j,
-                    grid.calc_inputs[site].magnitude,
-                    grid.calc_inputs[*neighbor].magnitude,
+                    j,
+                    widen(grid.calc_inputs[site].magnitude),
+                    widen(grid.calc_inputs[neighbor].magnitude),
                 );
                 if self.rng.random::<f64>() < p {
-                    visited[*neighbor] = true;
-                    queue.push_back(*neighbor);
+                    self.stamps[neighbor] = self.generation;
+                    self.cluster.push(neighbor);
                 }
             }
         }
 
+        let mut delta_e = 0.;
         if self.ham_config.anisotropy_enable {
-            let mut delta_e = 0.;
-            for index in &cluster {
+            for index in &self.cluster {
                 let flip_spin = grid.spins[*index].flip(&axis);
 
                 delta_e += flip_spin.ion_anisotropy_energy_diff(
@@ -69,19 +122,26 @@ impl<S: SpinState, R: rand::Rng> MonteCarlo<S, R> for Wolff<R> {
             }
         }
 
-        for index in &cluster {
-            grid.spins[*index] = grid.spins[*index].flip(&axis);
+        // The reflection preserves bonds inside the cluster, so only bonds
+        // crossing its boundary change the exchange energy.
+        for index in &self.cluster {
+            let mut change = grid.spins[*index].flip(&axis);
+            change += -grid.spins[*index];
+            for (neighbor, j) in grid.bonds(*index).iter() {
+                if !self.in_cluster(neighbor) {
+                    delta_e -= j * change.dot(&grid.spins[neighbor]);
+                }
+            }
         }
 
-        // if self.ham_config.anisotropy_enable {
-        //     if delta_e >= 0.0 && self.rng.random::<f64>() > (-self.beta * delta_e).exp() {
-        //         for index in &cluster {
-        //             grid.spins[*index] = grid.spins[*index].flip(&axis);
-        //         }
-        //     }
-        // }
+        for index in &self.cluster {
+            let flip_spin = grid.spins[*index].flip(&axis);
+            grid.set_spin(*index, flip_spin);
+        }
+        grid.add_energy(delta_e);
+        grid.resync_if_due();
 
-        cluster.len()
+        self.cluster.len()
     }
 }
 
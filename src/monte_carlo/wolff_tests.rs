@@ -3,7 +3,7 @@ use crate::calculators::HamiltonianConfig;
 use crate::config::Config;
 use crate::lattice::Grid;
 use crate::monte_carlo::Wolff;
-use crate::spin::IsingSpin;
+use crate::spin::{IsingSpin, tolerance};
 use rand::SeedableRng;
 use rand::rngs::SmallRng;
 
@@ -43,16 +43,16 @@ group = [[0]]
 fn wolff_high_beta_flips_entire_cluster() {
     let mut grid = make_ferro_grid();
     let initial_spins: Vec<f64> = grid.spins.iter().map(|s| s.to_array()[2]).collect();
-    let mut wolff = Wolff {
-        rng: SmallRng::seed_from_u64(99),
-        beta: 1e6,
-        ham_config: HamiltonianConfig {
+    let mut wolff = Wolff::new(
+        SmallRng::seed_from_u64(99),
+        1e6,
+        HamiltonianConfig {
             exchange_enable: true,
             anisotropy_enable: false,
             zeeman_enable: false,
             dm_enable: false,
         },
-    };
+    );
     let cluster_size = wolff.step(&mut grid);
     // At very high beta, Ising ferromagnet cluster should include all sites
     assert_eq!(cluster_size, grid.size);
@@ -71,16 +71,16 @@ fn wolff_high_beta_flips_entire_cluster() {
 #[test]
 fn wolff_zero_beta_small_cluster() {
     let mut grid = make_ferro_grid();
-    let mut wolff = Wolff {
-        rng: SmallRng::seed_from_u64(99),
-        beta: 0.0,
-        ham_config: HamiltonianConfig {
+    let mut wolff = Wolff::new(
+        SmallRng::seed_from_u64(99),
+        0.0,
+        HamiltonianConfig {
             exchange_enable: true,
             anisotropy_enable: false,
             zeeman_enable: false,
             dm_enable: false,
         },
-    };
+    );
     // At beta=0, wolff_probability = 1 - exp(0) = 0, so no bonds activated
     // Cluster = just the seed spin, so cluster size should be 1
     let cluster_size = wolff.step(&mut grid);
@@ -90,22 +90,75 @@ fn wolff_zero_beta_small_cluster() {
 #[test]
 fn wolff_repeated_steps() {
     let mut grid = make_ferro_grid();
-    let mut wolff = Wolff {
-        rng: SmallRng::seed_from_u64(99),
-        beta: 10.0,
-        ham_config: HamiltonianConfig {
+    let mut wolff = Wolff::new(
+        SmallRng::seed_from_u64(99),
+        10.0,
+        HamiltonianConfig {
             exchange_enable: true,
             anisotropy_enable: false,
             zeeman_enable: false,
             dm_enable: false,
         },
-    };
+    );
     for _ in 0..10 {
         let size = wolff.step(&mut grid);
         assert!(size > 0, "cluster should never be empty without anisotropy");
     }
 }
 
+#[test]
+fn wolff_reuses_cluster_buffers_across_steps() {
+    let mut grid = make_ferro_grid();
+    let mut wolff = Wolff::new(
+        SmallRng::seed_from_u64(7),
+        0.3,
+        HamiltonianConfig {
+            exchange_enable: true,
+            anisotropy_enable: false,
+            zeeman_enable: false,
+            dm_enable: false,
+        },
+    );
+    for step in 1..=20 {
+        let size = wolff.step(&mut grid);
+        assert_eq!(wolff.cluster().sites.len(), size);
+        assert_eq!(wolff.generation, step);
+        assert_eq!(wolff.stamps.len(), grid.size);
+
+        let mut sites = wolff.cluster().sites.to_vec();
+        sites.sort_unstable();
+        sites.dedup();
+        assert_eq!(sites.len(), size, "cluster sites must be distinct");
+        assert_eq!(wolff.cluster().axis[2].abs(), 1.0);
+        // Exactly the cluster carries the current stamp.
+        let stamped = (0..grid.size).filter(|&i| wolff.in_cluster(i)).count();
+        assert_eq!(stamped, size);
+    }
+}
+
+#[test]
+fn wolff_generation_overflow_clears_stamps() {
+    let mut grid = make_ferro_grid();
+    let mut wolff = Wolff::new(
+        SmallRng::seed_from_u64(7),
+        0.0,
+        HamiltonianConfig {
+            exchange_enable: true,
+            anisotropy_enable: false,
+            zeeman_enable: false,
+            dm_enable: false,
+        },
+    );
+    wolff.step(&mut grid);
+    wolff.generation = u32::MAX;
+    wolff.stamps.fill(1);
+
+    assert_eq!(wolff.step(&mut grid), 1);
+    assert_eq!(wolff.generation, 1);
+    let stamped = (0..grid.size).filter(|&i| wolff.in_cluster(i)).count();
+    assert_eq!(stamped, 1);
+}
+
 fn make_aniso_grid() -> crate::lattice::Grid<IsingSpin, SmallRng> {
     let toml = r#"
 [simulation]
@@ -145,17 +198,68 @@ group = [[0]]
 #[test]
 fn wolff_with_anisotropy_runs() {
     let mut grid = make_aniso_grid();
-    let mut wolff = Wolff {
-        rng: SmallRng::seed_from_u64(99),
-        beta: 1.0,
-        ham_config: HamiltonianConfig {
+    let mut wolff = Wolff::new(
+        SmallRng::seed_from_u64(99),
+        1.0,
+        HamiltonianConfig {
             exchange_enable: true,
             anisotropy_enable: true,
             zeeman_enable: false,
             dm_enable: false,
         },
-    };
+    );
     let size = wolff.step(&mut grid);
     assert!(size > 0);
     assert!(size <= grid.size);
 }
+
+#[test]
+fn wolff_running_totals_match_full_recompute() {
+    let toml = r#"
+[simulation]
+initial_state = "random"
+model = "heisenberg"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0]
+num_threads = 1
+algorithm = "wolff"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, false, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]
+strength = 1.0
+
+[anisotropy]
+axis = [[0.0, 0.0, 1.0]]
+strength = [0.3]
+
+[output]
+energy = true
+group = [[0]]
+"#;
+    let config = Config::new(toml).unwrap();
+    let mut grid: Grid<crate::spin::HeisenbergSpin, SmallRng> =
+        Grid::new(&config, SmallRng::seed_from_u64(5)).unwrap();
+    let mut wolff = Wolff::new(SmallRng::seed_from_u64(6), 0.8, grid.hamiltonian.config);
+    for _ in 0..200 {
+        wolff.step(&mut grid);
+    }
+    // Energy deltas accumulate rounding over every cluster flip.
+    let drift = (grid.energy() - grid.total_energy()).abs();
+    assert!(drift < grid.size as f64 * tolerance(1e-9), "{drift}");
+    let m = grid.spin_sum();
+    let expected = grid.total_spin_vector();
+    let group = grid.group_spin_sum(0);
+    for k in 0..3 {
+        assert!((m[k] - expected[k]).abs() < tolerance(1e-9));
+        assert!((group[k] - expected[k]).abs() < tolerance(1e-9));
+    }
+}
@@ -2,6 +2,7 @@ use anyhow::Context;
 use indicatif::{
     MultiProgress, ParallelProgressIterator, ProgressBar, ProgressDrawTarget, ProgressStyle,
 };
+use itertools::iproduct;
 use rand::RngExt;
 use rand_core::SeedableRng;
 use rand_pcg::Pcg64Mcg;
@@ -9,23 +10,55 @@ use rayon::ThreadPoolBuilder;
 use rayon::prelude::*;
 use std::fs::File;
 use std::io::{BufWriter, IsTerminal, Write};
-use std::sync::atomic::{AtomicUsize, Ordering};
-use tracing::info;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tracing::{info, warn};
 
 use crate::{
-    config::{self, Algorithm, Config},
+    config::{self, Algorithm, Config, Ladder},
     lattice::Grid,
-    monte_carlo::{AnyMC, Metropolis, MonteCarlo, StatResult, Stats, StatsConfig, Wolff},
+    monte_carlo::{
+        AnyMC, CheckerboardMetropolis, CorrelationAccumulator, EquilibrationDetector, Helicity,
+        Metropolis, MonteCarlo, PairCorrelation, Plaquettes, QPoints, ReweightedPoint, StatResult,
+        Stats, StatsConfig, StructureFactorAccumulator, SwapStatistics, TimeSeriesWriter,
+        TopologyAccumulator, TopologyResult, Triangulation, Wolff, reweight,
+    },
     spin::{HeisenbergSpin, IsingSpin, SpinState, XYSpin},
 };
 
+static STOP_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Install a SIGINT/SIGTERM handler. The first signal lets every replica finish
+/// its current sweep and writes whatever has been measured as partial results;
+/// a second signal exits immediately.
+pub fn install_interrupt_handler() -> anyhow::Result<()> {
+    ctrlc::set_handler(|| {
+        if STOP_REQUESTED.swap(true, Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+        warn!("Interrupt received, finishing current sweep (press Ctrl-C again to abort)");
+    })
+    .context("Failed to install interrupt handler")
+}
+
 pub fn run(content: &str) -> anyhow::Result<()> {
+    STOP_REQUESTED.store(false, Ordering::SeqCst);
+    run_with_stop(content, &STOP_REQUESTED)
+}
+
+fn run_with_stop(content: &str, stop: &AtomicBool) -> anyhow::Result<()> {
     let run_config = Config::new(content)?;
     info!("{run_config}");
 
     let stats_config = StatsConfig {
         energy: run_config.output.energy,
         heat_capacity: run_config.output.heat_capacity,
+        energy_terms: run_config.output.energy_terms,
+        exchange_entries: if run_config.output.energy_by_exchange {
+            run_config.exchange.len()
+        } else {
+            0
+        },
         magnetization: run_config.output.magnetization,
         susceptibility: run_config.output.susceptibility,
         magnetization_abs: run_config.output.magnetization_abs,
@@ -34,31 +67,394 @@ pub fn run(content: &str) -> anyhow::Result<()> {
         group_susceptibility: run_config.output.group_susceptibility,
         group_magnetization_abs: run_config.output.group_magnetization_abs,
         group_susceptibility_abs: run_config.output.group_susceptibility_abs,
+        binder_cumulant: run_config.output.binder_cumulant,
+        group_binder_cumulant: run_config.output.group_binder_cumulant,
+        correlation_length: run_config.output.correlation_length,
+        vortex_density: run_config.output.vortex_density,
+        cluster_size: run_config.output.cluster_size,
+        improved_estimators: run_config.output.improved_estimators,
+        anisotropy_scale: run_config
+            .parallel_tempering
+            .as_ref()
+            .is_some_and(|pt| !pt.anisotropy_scales.is_empty()),
+        helicity_axes: if run_config.output.helicity_modulus {
+            run_config.grid.periodic_axes()
+        } else {
+            Vec::new()
+        },
+        // Several replicas always give an error, from their scatter.
+        error_bars: run_config.output.error_bars
+            || run_config.simulation.replicas_per_temperature > 1,
+        autocorrelation: run_config.output.autocorrelation,
+        record_samples: run_config.reweighting.is_some(),
         group_num: run_config.output.group.len(),
+        order_parameter_num: run_config.order_parameter.len(),
     };
 
     let pool = build_thread_pool(run_config.simulation.num_threads)?;
+    let geometry = Geometry::new(&run_config, &stats_config)?;
 
     let results = pool.install(|| match run_config.simulation.model {
-        config::Model::Ising => run_simulations::<IsingSpin>(&run_config, &stats_config),
-        config::Model::Xy => run_simulations::<XYSpin>(&run_config, &stats_config),
-        config::Model::Heisenberg => run_simulations::<HeisenbergSpin>(&run_config, &stats_config),
+        config::Model::Ising => {
+            run_simulations::<IsingSpin>(&run_config, &stats_config, &geometry, stop)
+        }
+        config::Model::Xy => run_simulations::<XYSpin>(&run_config, &stats_config, &geometry, stop),
+        config::Model::Heisenberg => {
+            run_simulations::<HeisenbergSpin>(&run_config, &stats_config, &geometry, stop)
+        }
     })?;
 
-    let file = File::create(&run_config.output.savefile)?;
+    let partial = write_results(
+        &run_config.output.savefile,
+        &stats_config,
+        &results,
+        run_config.simulation.seed,
+    )?;
+
+    if let Some(reweighting) = &run_config.reweighting {
+        let dim = run_config.grid.dimensions;
+        let size = (dim[0] * dim[1] * dim[2] * run_config.grid.sublattices) as f64;
+        let points = reweight(
+            &results,
+            reweighting,
+            run_config.simulation.boltzmann_constant,
+            size,
+        );
+        write_reweighted(&reweighting.savefile, &points)?;
+        info!("Reweighted results saved to file: {}", reweighting.savefile);
+        if let Some(dir) = &reweighting.samples_directory {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create samples directory: {dir}"))?;
+            for result in &results {
+                write_samples(
+                    &format!(
+                        "{dir}/{}.csv",
+                        point_label(result.t, result.anisotropy_scale)
+                    ),
+                    &result.samples,
+                    size,
+                )?;
+            }
+            info!("Energy and |M| series saved to directory: {dir}");
+        }
+    }
+
+    if let (Some(correlation), Some(shells)) = (&run_config.correlation, &geometry.correlation) {
+        write_correlation(&correlation.savefile, shells, &results)?;
+        info!(
+            "Correlation function saved to file: {}",
+            correlation.savefile
+        );
+    }
+
+    if let (Some(structure_factor), Some(q_points)) =
+        (&run_config.structure_factor, &geometry.q_points)
+    {
+        let dir = &structure_factor.save_directory;
+        for result in &results {
+            write_structure_factor(
+                &format!(
+                    "{dir}/{}.txt",
+                    point_label(result.t, result.anisotropy_scale)
+                ),
+                q_points,
+                result,
+            )?;
+        }
+        info!("Structure factors saved to directory: {dir}");
+    }
+
+    if let (Some(topological_charge), Some(triangulation)) =
+        (&run_config.topological_charge, &geometry.triangulation)
+    {
+        write_topological_charge(&topological_charge.savefile, triangulation, &results)?;
+        info!(
+            "Topological charge saved to file: {}",
+            topological_charge.savefile
+        );
+        if topological_charge.density_map {
+            let dir = &topological_charge.density_directory;
+            for result in &results {
+                write_topological_density(
+                    &format!(
+                        "{dir}/{}.txt",
+                        point_label(result.t, result.anisotropy_scale)
+                    ),
+                    triangulation,
+                    result,
+                )?;
+            }
+            info!("Topological charge densities saved to directory: {dir}");
+        }
+    }
+
+    if partial {
+        warn!(
+            "Simulation interrupted. Partial results saved to file: {}",
+            run_config.output.savefile
+        );
+    } else {
+        info!(
+            "Simulation completed. Results saved to file: {}",
+            run_config.output.savefile
+        );
+    }
+    Ok(())
+}
+
+/// Write the header, the master seed and one line per temperature. Returns
+/// whether the results are partial, in which case a comment block with the
+/// completed sweeps per temperature follows the seed. A temperature is
+/// complete after its equilibration plus measurement sweeps; one interrupted
+/// before its first measurement sweep is marked in that block and gets no
+/// line.
+fn write_results(
+    savefile: &str,
+    stats_config: &StatsConfig,
+    results: &[StatResult],
+    seed: u64,
+) -> anyhow::Result<bool> {
+    let file = File::create(savefile)?;
 
     let mut writer = BufWriter::new(&file);
 
     writeln!(writer, "{stats_config}")?;
+    writeln!(writer, "# seed = {seed}")?;
 
-    for result in results.iter() {
+    let total_sweeps =
+        |result: &StatResult| result.equilibration_sweeps + result.measurement_sweeps;
+    let measured = |result: &StatResult| result.sweeps > result.equilibration_sweeps;
+    let partial = results
+        .iter()
+        .any(|result| result.sweeps < total_sweeps(result));
+    if partial {
+        writeln!(
+            writer,
+            "# PARTIAL RESULTS: run interrupted before completion"
+        )?;
+        for result in results {
+            writeln!(
+                writer,
+                "# T={:.4} sweeps={}/{}{}",
+                result.t,
+                result.sweeps,
+                total_sweeps(result),
+                if measured(result) {
+                    ""
+                } else {
+                    " (no measurements)"
+                }
+            )?;
+        }
+    }
+
+    for result in results.iter().filter(|result| measured(result)) {
         writeln!(writer, "{result}")?;
     }
+    writer.flush()?;
+
+    Ok(partial)
+}
+
+fn write_reweighted(savefile: &str, points: &[ReweightedPoint]) -> anyhow::Result<()> {
+    let file = File::create(savefile)?;
+
+    let mut writer = BufWriter::new(&file);
+
+    writeln!(writer, "{}", ReweightedPoint::header())?;
+    for point in points {
+        writeln!(writer, "{point}")?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// The (E, |M|) series of one temperature, per spin, one row per measurement.
+fn write_samples(path: &str, samples: &[[f64; 2]], size: f64) -> anyhow::Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("Failed to create samples file: {path}"))?;
+
+    let mut writer = BufWriter::new(&file);
+
+    writeln!(writer, "energy,m_abs")?;
+    for [energy, m_abs] in samples {
+        writeln!(writer, "{},{}", energy / size, m_abs / size)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn write_correlation(
+    savefile: &str,
+    correlation: &PairCorrelation,
+    results: &[StatResult],
+) -> anyhow::Result<()> {
+    let file = File::create(savefile)?;
+
+    let mut writer = BufWriter::new(&file);
+
+    writeln!(writer, "{}", correlation.header())?;
+    for result in results {
+        write!(writer, "{}", crate::utils::fmt_fixed_width(result.t, 12))?;
+        for value in &result.correlation {
+            write!(writer, "\t{}", crate::utils::fmt_fixed_width(*value, 12))?;
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Swap statistics of every ladder. With a single anisotropy scale this is
+/// the temperature ladder alone; otherwise a commented section per ladder,
+/// first the temperature ladder of each scale, then the scale ladder of each
+/// temperature.
+fn write_swap_statistics(
+    savefile: &str,
+    temperatures: &[f64],
+    scales: &[Option<f64>],
+    temperature_swaps: &[SwapStatistics],
+    scale_swaps: &[SwapStatistics],
+) -> anyhow::Result<()> {
+    let file = File::create(savefile)
+        .with_context(|| format!("Failed to create swap statistics file: {savefile}"))?;
+
+    let mut writer = BufWriter::new(&file);
+
+    if scale_swaps.is_empty() {
+        for swaps in temperature_swaps {
+            write_ladder(&mut writer, "#T(K)", temperatures, swaps)?;
+        }
+    } else {
+        let scales: Vec<f64> = scales.iter().flatten().copied().collect();
+        for (scale, swaps) in scales.iter().zip(temperature_swaps) {
+            writeln!(writer, "# anisotropy scale = {scale:.4}")?;
+            write_ladder(&mut writer, "#T(K)", temperatures, swaps)?;
+        }
+        for (temperature, swaps) in temperatures.iter().zip(scale_swaps) {
+            writeln!(writer, "# T = {temperature:.4}")?;
+            write_ladder(&mut writer, "#K/K_0", &scales, swaps)?;
+        }
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// One row per ladder point with the acceptance of its swaps with the next
+/// one and f; the round trips of each replica precede it as comments.
+fn write_ladder(
+    writer: &mut impl Write,
+    label: &str,
+    values: &[f64],
+    swaps: &SwapStatistics,
+) -> anyhow::Result<()> {
+    writeln!(
+        writer,
+        "# round trips per replica, lowest -> highest -> lowest point of the ladder"
+    )?;
+    for (replica, trips) in swaps.round_trips.iter().enumerate() {
+        writeln!(
+            writer,
+            "# replica {replica}: round trips = {trips}, mean = {:.1} sweeps",
+            swaps.mean_round_trip(replica)
+        )?;
+    }
+    writeln!(
+        writer,
+        "{label:<12}\t{:<12}\t{:<12}\t{:<12}",
+        "attempts", "acceptance", "f_up"
+    )?;
+    for (t, &value) in values.iter().enumerate() {
+        // The last point has no upper neighbour.
+        let (attempts, acceptance) = if t + 1 < values.len() {
+            (swaps.attempts[t], swaps.acceptance(t))
+        } else {
+            (0, f64::NAN)
+        };
+        writeln!(
+            writer,
+            "{}\t{attempts:<12}\t{}\t{}",
+            crate::utils::fmt_fixed_width(value, 12),
+            crate::utils::fmt_fixed_width(acceptance, 12),
+            crate::utils::fmt_fixed_width(swaps.fraction_up(t), 12)
+        )?;
+    }
+    Ok(())
+}
+
+fn write_structure_factor(
+    savefile: &str,
+    q_points: &QPoints,
+    result: &StatResult,
+) -> anyhow::Result<()> {
+    let file = File::create(savefile)
+        .with_context(|| format!("Failed to create structure factor file: {savefile}"))?;
+
+    let mut writer = BufWriter::new(&file);
+
+    writeln!(writer, "{}", QPoints::header())?;
+    for row in q_points.rows(&result.structure_factor) {
+        writeln!(writer, "{row}")?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn write_topological_charge(
+    savefile: &str,
+    triangulation: &Triangulation,
+    results: &[StatResult],
+) -> anyhow::Result<()> {
+    let file = File::create(savefile)
+        .with_context(|| format!("Failed to create topological charge file: {savefile}"))?;
+
+    let mut writer = BufWriter::new(&file);
+
+    writeln!(writer, "{}", TopologyResult::header(triangulation.layers))?;
+    for result in results {
+        if let Some(topology) = &result.topological_charge {
+            writeln!(
+                writer,
+                "{}\t{topology}",
+                crate::utils::fmt_fixed_width(result.t, 12)
+            )?;
+        }
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn write_topological_density(
+    savefile: &str,
+    triangulation: &Triangulation,
+    result: &StatResult,
+) -> anyhow::Result<()> {
+    let file = File::create(savefile)
+        .with_context(|| format!("Failed to create topological density file: {savefile}"))?;
+
+    let mut writer = BufWriter::new(&file);
+
+    writeln!(writer, "#sublattice\tx\ty\tz\tq")?;
+    let density = result
+        .topological_charge
+        .as_ref()
+        .map_or(&[][..], |topology| &topology.density);
+    for (site, q) in density.iter().enumerate() {
+        let [sub, x, y, z] = triangulation.site_coords(site);
+        writeln!(
+            writer,
+            "{sub}\t{x}\t{y}\t{z}\t{}",
+            crate::utils::fmt_fixed_width(*q, 12)
+        )?;
+    }
+    writer.flush()?;
 
-    info!(
-        "Simulation completed. Results saved to file: {}",
-        run_config.output.savefile
-    );
     Ok(())
 }
 
@@ -69,6 +465,48 @@ fn build_thread_pool(num_threads: usize) -> anyhow::Result<rayon::ThreadPool> {
         .context("Failed to build Rayon thread pool")
 }
 
+/// Lattice geometry of the optional measurements, built once per run and
+/// shared by the accumulators of every replica and by the output writers.
+struct Geometry {
+    correlation: Option<Arc<PairCorrelation>>,
+    q_points: Option<Arc<QPoints>>,
+    triangulation: Option<Arc<Triangulation>>,
+    plaquettes: Option<Arc<Plaquettes>>,
+    helicity: Option<Arc<Helicity>>,
+}
+
+impl Geometry {
+    fn new(config: &Config, stats_config: &StatsConfig) -> anyhow::Result<Self> {
+        let correlation = config
+            .correlation
+            .as_ref()
+            .map(|correlation| PairCorrelation::new(config, correlation.shells).map(Arc::new))
+            .transpose()?;
+        let q_points = config
+            .structure_factor
+            .as_ref()
+            .map(|structure_factor| QPoints::new(config, structure_factor).map(Arc::new))
+            .transpose()?;
+        let triangulation = config
+            .topological_charge
+            .as_ref()
+            .map(|_| Triangulation::new(config).map(Arc::new))
+            .transpose()?;
+        let plaquettes = stats_config
+            .vortex_density
+            .then(|| Arc::new(Plaquettes::new(config)));
+        let helicity = (!stats_config.helicity_axes.is_empty())
+            .then(|| Arc::new(Helicity::new(config, stats_config.helicity_axes.clone())));
+        Ok(Self {
+            correlation,
+            q_points,
+            triangulation,
+            plaquettes,
+            helicity,
+        })
+    }
+}
+
 struct Systems<S: SpinState> {
     stats: Vec<Stats<S>>,
     grids: Vec<Grid<S, Pcg64Mcg>>,
@@ -129,25 +567,82 @@ fn progress_style(template: &str, chars: &str) -> ProgressStyle {
 fn build_systems<S: SpinState>(
     config: &Config,
     stats_config: &StatsConfig,
+    geometry: &Geometry,
 ) -> anyhow::Result<Systems<S>> {
     let kb = config.simulation.boltzmann_constant;
+    let seed = config.simulation.seed;
     let mut stats = Vec::new();
     let mut grids = Vec::new();
     let mut algos = Vec::new();
+    if let Some(structure_factor) = &config.structure_factor {
+        let dir = &structure_factor.save_directory;
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create structure factor directory: {dir}"))?;
+    }
+    if let Some(topological_charge) = &config.topological_charge
+        && topological_charge.density_map
+    {
+        let dir = &topological_charge.density_directory;
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create topological density directory: {dir}"))?;
+    }
+    let size = config.grid.dimensions.iter().product::<usize>() * config.grid.sublattices;
 
-    for &t in &config.simulation.temperatures {
+    let scales = anisotropy_scales(config);
+    let points = scales.len() * config.simulation.temperatures.len();
+    let replicas = config.simulation.replicas_per_temperature;
+    let chains = iproduct!(&scales, &config.simulation.temperatures, 0..replicas);
+    for (index, (&scale, &t, replica)) in chains.enumerate() {
+        let chain = (replica * points + index / replicas) as u64;
         let beta = beta_from_temperature(kb, t);
-        let rng = Pcg64Mcg::from_rng(&mut rand::rng());
-        let grid = Grid::<S, Pcg64Mcg>::new(config, rng.clone())?;
+        let mut grid = Grid::<S, Pcg64Mcg>::new(config, stream_rng(seed, 2 * chain))?;
+        if let Some(scale) = scale {
+            grid.set_anisotropy_scale(scale);
+        }
+        let rng = stream_rng(seed, 2 * chain + 1);
         let mc = match config.simulation.algorithm {
-            Algorithm::Wolff => AnyMC::Wolff(Wolff {
-                rng,
-                beta,
-                ham_config: grid.hamiltonian.config,
-            }),
+            Algorithm::Wolff => AnyMC::Wolff(Wolff::new(rng, beta, grid.hamiltonian.config)),
             Algorithm::Metropolis => AnyMC::Metropolis(Metropolis { rng, beta }),
+            Algorithm::Checkerboard => {
+                AnyMC::Checkerboard(CheckerboardMetropolis::new(rng, beta, &grid.neighbors))
+            }
         };
-        stats.push(Stats::<S>::new(config, t, stats_config.clone()));
+        let mut stat = Stats::<S>::new(config, t, stats_config.clone());
+        stat.order_size = grid.order_sizes();
+        stat.anisotropy_scale = scale;
+        if let Some(time_series) = &config.time_series {
+            let dir = &time_series.save_directory;
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create time series directory: {dir}"))?;
+            stat.time_series = Some(TimeSeriesWriter::create(
+                &format!("{dir}/{}.csv", chain_label(t, scale, replica, replicas)),
+                stats_config.group_num,
+            )?);
+        }
+        stat.plaquettes = geometry.plaquettes.clone();
+        stat.helicity = geometry.helicity.clone();
+        stat.correlation = geometry
+            .correlation
+            .clone()
+            .map(CorrelationAccumulator::new);
+        if let (Some(q_points), Some(structure_factor)) =
+            (&geometry.q_points, &config.structure_factor)
+        {
+            stat.static_structure_factor = Some(StructureFactorAccumulator::new(
+                q_points.clone(),
+                structure_factor.interval,
+            ));
+        }
+        if let (Some(triangulation), Some(topological_charge)) =
+            (&geometry.triangulation, &config.topological_charge)
+        {
+            stat.topology = Some(TopologyAccumulator::new(
+                triangulation.clone(),
+                topological_charge.density_map,
+                size,
+            ));
+        }
+        stats.push(stat);
         grids.push(grid);
         algos.push(mc);
     }
@@ -158,6 +653,67 @@ fn build_systems<S: SpinState>(
     })
 }
 
+/// Stream used for parallel-tempering swap decisions. Chain `i` uses streams
+/// `2i` (initial configuration) and `2i + 1` (Monte Carlo moves), where
+/// replica `k` of simulated point `p` out of `P` is chain `k P + p`, so the
+/// first replica keeps the streams of a run without replicas.
+const SWAP_STREAM: u64 = u64::MAX;
+
+/// Derive an independent RNG stream from the master seed. Streams are tied to
+/// temperatures rather than threads, so a seeded run is reproducible for any
+/// `num_threads`.
+fn stream_rng(seed: u64, stream: u64) -> Pcg64Mcg {
+    Pcg64Mcg::seed_from_u64(seed ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+/// The anisotropy scales replicas are simulated at; `[None]` leaves the
+/// anisotropy as configured.
+fn anisotropy_scales(config: &Config) -> Vec<Option<f64>> {
+    match &config.parallel_tempering {
+        Some(pt) if !pt.anisotropy_scales.is_empty() => {
+            pt.anisotropy_scales.iter().copied().map(Some).collect()
+        }
+        _ => vec![None],
+    }
+}
+
+/// File stem of one simulated point, `T_<T>` or `T_<T>_K_<scale>`.
+fn point_label(temperature: f64, scale: Option<f64>) -> String {
+    match scale {
+        Some(scale) => format!("T_{temperature:.4}_K_{scale:.4}"),
+        None => format!("T_{temperature:.4}"),
+    }
+}
+
+/// Moves every time-series file to the name of the temperature its stats now
+/// hold, through a temporary name first since a point may take over the old
+/// name of its neighbour.
+fn rename_time_series<S: SpinState>(stats: &mut [Stats<S>], dir: &str) -> anyhow::Result<()> {
+    for (r, stat) in stats.iter_mut().enumerate() {
+        if let Some(writer) = &mut stat.time_series {
+            writer.rename(&format!("{dir}/.retune_{r}.csv"))?;
+        }
+    }
+    for stat in stats.iter_mut() {
+        let label = point_label(stat.t, stat.anisotropy_scale);
+        if let Some(writer) = &mut stat.time_series {
+            writer.rename(&format!("{dir}/{label}.csv"))?;
+        }
+    }
+    Ok(())
+}
+
+/// File stem of one chain: the point label, with `_R_<replica>` appended
+/// when there are several replicas per temperature.
+fn chain_label(temperature: f64, scale: Option<f64>, replica: usize, replicas: usize) -> String {
+    let label = point_label(temperature, scale);
+    if replicas > 1 {
+        format!("{label}_R_{replica}")
+    } else {
+        label
+    }
+}
+
 fn beta_from_temperature(kb: f64, temperature: f64) -> f64 {
     if temperature == 0.0 {
         f64::INFINITY
@@ -166,26 +722,41 @@ fn beta_from_temperature(kb: f64, temperature: f64) -> f64 {
     }
 }
 
-// Non-PT: into_par_iter().map() — each thread owns its data
+// Non-PT: into_par_iter().map() — each thread owns its data.
+// With several replicas per temperature every replica is its own task, and
+// the replicas of a temperature are pooled once all of them are done.
 fn run_independent<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send>(
     config: &Config,
     stats: Vec<Stats<S>>,
     algos: Vec<AnyMC<R>>,
     grids: Vec<Grid<S, R>>,
+    stop: &AtomicBool,
 ) -> anyhow::Result<Vec<StatResult>> {
     let equil_steps = config.simulation.equilibration_steps;
+    let auto_equil = config.simulation.auto_equilibration;
     let meas_steps = config.simulation.measurement_steps;
+    let target_error = config.simulation.measurement_target_error;
+    let target_observables = &config.simulation.target_observables;
     let stats_interval = config.output.stats_interval;
+    let equil_interval = config
+        .time_series
+        .as_ref()
+        .map_or(0, |time_series| time_series.equilibration_interval);
     let total_steps = equil_steps + meas_steps;
     let num_threads = rayon::current_num_threads();
     let progress = progress_config(config, total_steps);
-    let temp_count = stats.len();
+    let replicas = config.simulation.replicas_per_temperature;
+    let temp_count = config.simulation.temperatures.len();
 
     let multi = MultiProgress::new();
     let pb = multi.add(ProgressBar::new(stats.len() as u64));
     maybe_hide_progress_bar(&pb, progress);
     pb.set_style(progress_style(
-        "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} temperatures",
+        if replicas > 1 {
+            "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} replicas"
+        } else {
+            "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} temperatures"
+        },
         "#>-",
     ));
     let sub_pbs: Vec<ProgressBar> = (0..num_threads)
@@ -207,26 +778,39 @@ fn run_independent<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send>(
         .zip(grids.into_par_iter())
         .progress_with(pb)
         .enumerate()
-        .map(|(idx, ((mut stat, mut mc), mut grid))| -> anyhow::Result<StatResult> {
+        .map(|(idx, ((mut stat, mut mc), mut grid))| -> anyhow::Result<(StatResult, Stats<S>)> {
             let bar_id = sub_counter.fetch_add(1, Ordering::Relaxed) % num_threads;
             let sub_pb = &sub_pbs[bar_id];
             sub_pb.reset();
-            sub_pb.set_message(format!("T={:.4}", config.simulation.temperatures[idx]));
-            #[cfg(feature = "snapshots")]
-            let t = config.simulation.temperatures[idx];
+            let replica = idx % replicas;
+            let label = if replicas > 1 {
+                format!("T={:.4}, replica={}/{replicas}", stat.t, replica + 1)
+            } else {
+                format!("T={:.4}", stat.t)
+            };
+            sub_pb.set_message(label.clone());
             #[cfg(feature = "snapshots")]
             let (mut equil_snapshots, mut measure_snapshots) = (vec![], vec![]);
+            let mut sweeps = 0;
+            let mut detector = auto_equil.then(EquilibrationDetector::default);
+            let mut equil_sweeps = equil_steps;
 
             for _step in 0..equil_steps {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
                 mc.step(&mut grid);
                 let completed = _step + 1;
+                sweeps = completed;
+                if equil_interval > 0 && completed.is_multiple_of(equil_interval) {
+                    stat.record_time_series(&grid, completed);
+                }
                 sub_pb.set_position(completed as u64);
                 if should_log_progress(completed, total_steps, progress.log_interval) {
                     info!(
-                        "Progress: temperature={}/{}, T={:.4}, phase=equilibration, sweep={}/{}, {:.1}%",
-                        idx + 1,
+                        "Progress: temperature={}/{}, {label}, phase=equilibration, sweep={}/{}, {:.1}%",
+                        idx / replicas + 1,
                         temp_count,
-                        config.simulation.temperatures[idx],
                         completed,
                         total_steps,
                         completed as f64 * 100.0 / total_steps as f64
@@ -240,21 +824,46 @@ fn run_independent<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send>(
                 {
                     equil_snapshots.push(grid.spins_to_array());
                 }
+
+                if let Some(detector) = &mut detector {
+                    detector.record(&grid);
+                    if detector.is_stationary() {
+                        equil_sweeps = completed;
+                        break;
+                    }
+                }
             }
-            sub_pb.set_position(equil_steps as u64);
+            if auto_equil && sweeps == equil_sweeps {
+                log_equilibration(&label, equil_sweeps, equil_steps);
+            }
+            let total_steps = equil_sweeps + meas_steps;
+            sub_pb.set_length(total_steps as u64);
+            sub_pb.set_position(sweeps as u64);
+            let mut meas_sweeps = meas_steps;
+            let mut next_check = MIN_PRECISION_CHECK;
             for step in 0..meas_steps {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
                 mc.step(&mut grid);
+                if let Some(cluster) = mc.cluster() {
+                    stat.count_cluster(cluster.sites.len());
+                }
+                let completed = equil_sweeps + step + 1;
                 if step % stats_interval == 0 {
+                    if let Some(cluster) = mc.cluster() {
+                        stat.record_cluster(&grid.spins, cluster);
+                    }
                     stat.record(&grid);
+                    stat.record_time_series(&grid, completed);
                 }
-                let completed = equil_steps + step + 1;
+                sweeps = completed;
                 sub_pb.set_position(completed as u64);
                 if should_log_progress(completed, total_steps, progress.log_interval) {
                     info!(
-                        "Progress: temperature={}/{}, T={:.4}, phase=measurement, sweep={}/{}, {:.1}%",
-                        idx + 1,
+                        "Progress: temperature={}/{}, {label}, phase=measurement, sweep={}/{}, {:.1}%",
+                        idx / replicas + 1,
                         temp_count,
-                        config.simulation.temperatures[idx],
                         completed,
                         total_steps,
                         completed as f64 * 100.0 / total_steps as f64
@@ -268,6 +877,19 @@ fn run_independent<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send>(
                 {
                     measure_snapshots.push(grid.spins_to_array());
                 }
+
+                if let Some(target) = target_error
+                    && stat.steps >= next_check
+                {
+                    next_check = next_precision_check(stat.steps);
+                    if stat.max_error(target_observables) <= target {
+                        meas_sweeps = step + 1;
+                        break;
+                    }
+                }
+            }
+            if target_error.is_some() && sweeps == equil_sweeps + meas_sweeps {
+                log_precision(&label, meas_sweeps, meas_steps);
             }
 
             #[cfg(feature = "snapshots")]
@@ -276,7 +898,10 @@ fn run_independent<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send>(
                 std::fs::create_dir_all(snapshot_dir).with_context(|| {
                     format!("Failed to create snapshot directory: {snapshot_dir}")
                 })?;
-                let file_name = format!("{snapshot_dir}/T_{t:.4}.npz");
+                let file_name = format!(
+                    "{snapshot_dir}/{}.npz",
+                    chain_label(stat.t, None, replica, replicas)
+                );
                 match config::save_snapshots_to_npz(
                     &file_name,
                     &equil_snapshots,
@@ -289,12 +914,63 @@ fn run_independent<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send>(
                     }
                 };
             };
-            sub_pb.set_position(total_steps as u64);
-            sub_pb.finish_with_message(format!("T={:.4} ✓", config.simulation.temperatures[idx]));
+            if sweeps < equil_sweeps + meas_sweeps {
+                sub_pb.abandon_with_message(format!("{label} interrupted"));
+            } else {
+                sub_pb.set_length(sweeps as u64);
+                sub_pb.set_position(sweeps as u64);
+                sub_pb.finish_with_message(format!("{label} ✓"));
+            }
 
-            Ok(stat.result())
+            let result = StatResult {
+                sweeps,
+                equilibration_sweeps: equil_sweeps,
+                measurement_sweeps: meas_sweeps,
+                samples: stat.take_samples(),
+                ..stat.result()
+            };
+            Ok((result, stat))
         })
-        .collect()
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map(|chains| {
+            let (results, stats): (Vec<_>, Vec<_>) = chains.into_iter().unzip();
+            if replicas > 1 {
+                results
+                    .chunks(replicas)
+                    .zip(stats.chunks(replicas))
+                    .map(|(results, stats)| Stats::pooled_result(stats, results))
+                    .collect()
+            } else {
+                results
+            }
+        })
+}
+
+/// Measurements before the first run-until-precision check; later checks are
+/// spaced geometrically so the jackknife costs O(n) over the whole run.
+const MIN_PRECISION_CHECK: usize = 100;
+
+fn next_precision_check(measurements: usize) -> usize {
+    measurements + (measurements / 4).max(MIN_PRECISION_CHECK)
+}
+
+/// Report the outcome of a run-until-precision measurement for `label`.
+fn log_precision(label: &str, meas_sweeps: usize, max_sweeps: usize) {
+    if meas_sweeps < max_sweeps {
+        info!("{label}: target error reached after {meas_sweeps} measurement sweeps");
+    } else {
+        warn!("{label}: target error not reached within measurement_steps={max_sweeps}");
+    }
+}
+
+/// Report the outcome of automatic equilibration for `label` (a temperature,
+/// or all PT replicas).
+fn log_equilibration(label: &str, equil_sweeps: usize, max_sweeps: usize) {
+    if equil_sweeps < max_sweeps {
+        info!("{label}: equilibrated after {equil_sweeps} sweeps");
+    } else {
+        warn!("{label}: not stationary within equilibration_steps={max_sweeps}, measuring anyway");
+    }
 }
 
 // PT: batched MC (pt_interval steps per fork-join) + parallel measurement + even-odd swap
@@ -306,23 +982,71 @@ fn run_pt<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send + Sync>(
     stats: &mut [Stats<S>],
     algos: &mut [AnyMC<R>],
     grids: &mut [Grid<S, R>],
+    stop: &AtomicBool,
 ) -> anyhow::Result<Vec<StatResult>> {
     let n_temps = config.simulation.temperatures.len();
+    let scales = anisotropy_scales(config);
+    let n_scales = scales.len();
+    let n_replicas = n_temps * n_scales;
+    let kb = config.simulation.boltzmann_constant;
     let pt_interval = config.simulation.pt_interval;
     let equil_steps = config.simulation.equilibration_steps;
+    let auto_equil = config.simulation.auto_equilibration;
     let meas_steps = config.simulation.measurement_steps;
+    let target_error = config.simulation.measurement_target_error;
+    let target_observables = &config.simulation.target_observables;
     let stats_interval = config.output.stats_interval;
-    let total_steps = equil_steps + meas_steps;
+    let equil_interval = config
+        .time_series
+        .as_ref()
+        .map_or(0, |time_series| time_series.equilibration_interval);
+    // With auto_equilibration, measurement starts once every temperature is stationary.
+    let mut equil_end = equil_steps;
+    let mut total_steps = equil_steps + meas_steps;
+    let mut meas_sweeps = meas_steps;
+    let mut next_check = MIN_PRECISION_CHECK;
     let progress = progress_config(config, total_steps);
+    let mut detectors: Vec<EquilibrationDetector> = (0..n_replicas)
+        .map(|_| EquilibrationDetector::default())
+        .collect();
 
-    // temp_to_replica[t] = replica index currently simulating temperature t
-    let mut temp_to_replica: Vec<usize> = (0..n_temps).collect();
+    // point_to_replica[s][t] = replica index currently simulating temperature t
+    // at anisotropy scale s
+    let mut point_to_replica: Vec<Vec<usize>> = (0..n_scales)
+        .map(|s| (s * n_temps..(s + 1) * n_temps).collect())
+        .collect();
+    let (ladder, adapt_interval) = config
+        .parallel_tempering
+        .as_ref()
+        .map_or((Ladder::Fixed, 0), |pt| (pt.ladder, pt.adapt_interval));
+    let mut temperatures = config.simulation.temperatures.clone();
+    // One temperature ladder per scale and one scale ladder per temperature,
+    // where there is more than one point to swap between.
+    let mut temperature_swaps: Vec<SwapStatistics> = if n_temps > 1 {
+        point_to_replica
+            .iter()
+            .map(|row| SwapStatistics::new(row, n_replicas))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut scale_swaps: Vec<SwapStatistics> = if n_scales > 1 {
+        (0..n_temps)
+            .map(|t| SwapStatistics::new(&column(&point_to_replica, t), n_replicas))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut next_adapt = adapt_interval;
+    let mut swaps_measured = false;
+    // Sweeps completed by each replica; only differs from `sweep` after an interrupt.
+    let mut replica_sweeps = vec![0usize; n_replicas];
 
     #[cfg(feature = "snapshots")]
     let (mut equil_snapshots, mut measure_snapshots) = {
-        let mut equil = Vec::with_capacity(n_temps);
-        let mut meas = Vec::with_capacity(n_temps);
-        for _ in 0..n_temps {
+        let mut equil = Vec::with_capacity(n_replicas);
+        let mut meas = Vec::with_capacity(n_replicas);
+        for _ in 0..n_replicas {
             equil.push(Vec::new());
             meas.push(Vec::new());
         }
@@ -336,6 +1060,7 @@ fn run_pt<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send + Sync>(
         "#>-",
     ));
 
+    let mut swap_rng = stream_rng(config.simulation.seed, SWAP_STREAM);
     let mut sweep = 0usize;
     while sweep < total_steps {
         let batch_end = (sweep + pt_interval).min(total_steps);
@@ -345,42 +1070,175 @@ fn run_pt<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send + Sync>(
         // Parallel batch: each replica writes to its own stats[r].
         // Stats are indexed by replica (same as grids/algos), so
         // par_iter_mut gives each thread exclusive access to its own stat.
-        grids
+        let completed: Vec<usize> = grids
             .par_iter_mut()
             .zip(algos.par_iter_mut())
             .zip(stats.par_iter_mut())
-            .for_each(|((grid, mc), stat)| {
+            .zip(detectors.par_iter_mut())
+            .map(|(((grid, mc), stat), detector)| {
                 for offset in 0..batch_nsteps {
+                    if stop.load(Ordering::Relaxed) {
+                        return offset;
+                    }
                     let s = start + offset;
                     mc.step(grid);
-                    if s >= equil_steps {
+                    if s >= equil_end {
+                        if let Some(cluster) = mc.cluster() {
+                            stat.count_cluster(cluster.sites.len());
+                        }
                         let do_meas = stats_interval == 0 || s.is_multiple_of(stats_interval);
                         if do_meas {
+                            if let Some(cluster) = mc.cluster() {
+                                stat.record_cluster(&grid.spins, cluster);
+                            }
                             stat.record(grid);
+                            stat.record_time_series(grid, s + 1);
+                        }
+                    } else {
+                        if equil_interval > 0 && (s + 1).is_multiple_of(equil_interval) {
+                            stat.record_time_series(grid, s + 1);
+                        }
+                        if auto_equil {
+                            detector.record(grid);
                         }
                     }
                 }
-            });
+                batch_nsteps
+            })
+            .collect();
+        for (r, done) in completed.into_iter().enumerate() {
+            replica_sweeps[r] = start + done;
+        }
+        if replica_sweeps.iter().any(|&done| done < batch_end) {
+            pb.abandon_with_message("interrupted");
+            break;
+        }
+
+        // Replicas exchange configurations, so all temperatures switch to
+        // measurement together. Every detector is queried to keep their
+        // check schedules aligned.
+        if auto_equil && batch_end < equil_end {
+            let stationary: Vec<bool> = detectors.iter_mut().map(|d| d.is_stationary()).collect();
+            if stationary.into_iter().all(|s| s) {
+                equil_end = batch_end;
+                total_steps = equil_end + meas_steps;
+                pb.set_length(total_steps as u64);
+            }
+        }
+        if auto_equil && batch_end == equil_end {
+            log_equilibration("All temperatures (PT)", equil_end, equil_steps);
+        }
+
+        // Run until precision: every temperature must reach the target, so
+        // that the replicas keep exchanging until the slowest one is done.
+        if let Some(target) = target_error
+            && batch_end > equil_end
+            && stats[0].steps >= next_check
+        {
+            next_check = next_precision_check(stats[0].steps);
+            if stats
+                .iter()
+                .all(|stat| stat.max_error(target_observables) <= target)
+            {
+                meas_sweeps = batch_end - equil_end;
+                total_steps = batch_end;
+                pb.set_length(total_steps as u64);
+            }
+        }
+        if target_error.is_some() && batch_end == total_steps {
+            log_precision("All temperatures (PT)", meas_sweeps, meas_steps);
+        }
+
+        // Feedback-optimised ladder: retune from the swaps of the last
+        // window while equilibrating, keeping each replica's stats on the
+        // temperature it now simulates.
+        // Adaptive ladders run at a single anisotropy scale.
+        if ladder != Ladder::Fixed && batch_end < equil_end && batch_end >= next_adapt {
+            next_adapt = batch_end + adapt_interval;
+            if let Some(retuned) = temperature_swaps[0].retuned(ladder, &temperatures) {
+                temperatures = retuned;
+                for (t, &temperature) in temperatures.iter().enumerate() {
+                    let r = point_to_replica[0][t];
+                    algos[r].set_beta(beta_from_temperature(kb, temperature));
+                    stats[r].t = temperature;
+                }
+                if let Some(time_series) = &config.time_series {
+                    rename_time_series(stats, &time_series.save_directory)?;
+                }
+                temperature_swaps[0].reset();
+            }
+        }
+        // Report the swap statistics of the measured ladder only.
+        if !swaps_measured && batch_end >= equil_end {
+            swaps_measured = true;
+            for swaps in temperature_swaps.iter_mut().chain(&mut scale_swaps) {
+                swaps.reset();
+            }
+            if ladder != Ladder::Fixed {
+                info!("PT ladder after equilibration: {temperatures:.4?}");
+            }
+        }
 
         // PT swap between batches (serial).
         if batch_end < total_steps && batch_end.is_multiple_of(pt_interval) {
-            let mut rng = Pcg64Mcg::from_rng(&mut rand::rng());
             let swap_start = (batch_end / pt_interval) % 2;
-            for t in (swap_start..n_temps - 1).step_by(2) {
-                let i = temp_to_replica[t];
-                let j = temp_to_replica[t + 1];
-                let e_i = grids[i].total_energy();
-                let e_j = grids[j].total_energy();
-                let beta_i = algos[i].beta();
-                let beta_j = algos[j].beta();
-                if accepts_parallel_tempering_swap(e_i, e_j, beta_i, beta_j, rng.random::<f64>()) {
-                    algos[i].set_beta(beta_j);
-                    algos[j].set_beta(beta_i);
-                    // Swap stats alongside temperatures so each Stats
-                    // object tracks the same temperature throughout.
-                    stats.swap(i, j);
-                    temp_to_replica.swap(t, t + 1);
+            for (row, swaps) in point_to_replica.iter_mut().zip(&mut temperature_swaps) {
+                for t in (swap_start..n_temps - 1).step_by(2) {
+                    let i = row[t];
+                    let j = row[t + 1];
+                    let e_i = grids[i].energy();
+                    let e_j = grids[j].energy();
+                    let beta_i = algos[i].beta();
+                    let beta_j = algos[j].beta();
+                    let accepted = accepts_parallel_tempering_swap(
+                        e_i,
+                        e_j,
+                        beta_i,
+                        beta_j,
+                        swap_rng.random::<f64>(),
+                    );
+                    swaps.record_swap(t, accepted);
+                    if accepted {
+                        algos[i].set_beta(beta_j);
+                        algos[j].set_beta(beta_i);
+                        // Swap stats (and equilibration detectors) alongside
+                        // temperatures so each tracks the same temperature throughout.
+                        stats.swap(i, j);
+                        detectors.swap(i, j);
+                        row.swap(t, t + 1);
+                    }
+                }
+                swaps.record_positions(row, batch_end);
+            }
+
+            // At one temperature the scales are exchanged instead: the
+            // anisotropy energy at unit scale and β times the scale take the
+            // places of E and β in the acceptance.
+            for (t, swaps) in scale_swaps.iter_mut().enumerate() {
+                for k in (swap_start..n_scales - 1).step_by(2) {
+                    let i = point_to_replica[k][t];
+                    let j = point_to_replica[k + 1][t];
+                    let beta = algos[i].beta();
+                    let scale_i = grids[i].hamiltonian.anisotropy_scale;
+                    let scale_j = grids[j].hamiltonian.anisotropy_scale;
+                    let accepted = accepts_parallel_tempering_swap(
+                        grids[i].unscaled_anisotropy_energy(),
+                        grids[j].unscaled_anisotropy_energy(),
+                        beta * scale_i,
+                        beta * scale_j,
+                        swap_rng.random::<f64>(),
+                    );
+                    swaps.record_swap(k, accepted);
+                    if accepted {
+                        grids[i].set_anisotropy_scale(scale_j);
+                        grids[j].set_anisotropy_scale(scale_i);
+                        stats.swap(i, j);
+                        detectors.swap(i, j);
+                        point_to_replica[k][t] = j;
+                        point_to_replica[k + 1][t] = i;
+                    }
                 }
+                swaps.record_positions(&column(&point_to_replica, t), batch_end);
             }
         }
 
@@ -389,20 +1247,19 @@ fn run_pt<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send + Sync>(
         // within a batch are not captured.
         #[cfg(feature = "snapshots")]
         if let Some(snaps) = &config.snapshots {
-            let is_equil = batch_end <= equil_steps;
+            let is_equil = batch_end <= equil_end;
             let interval = if is_equil {
                 snaps.equilibration_interval
             } else {
                 snaps.measurement_interval
             };
             if interval > 0 && batch_end.is_multiple_of(interval) {
-                for t in 0..n_temps {
-                    let r = temp_to_replica[t];
+                for (point, &r) in point_to_replica.iter().flatten().enumerate() {
                     let snap = grids[r].spins_to_array();
                     if is_equil {
-                        equil_snapshots[t].push(snap);
+                        equil_snapshots[point].push(snap);
                     } else {
-                        measure_snapshots[t].push(snap);
+                        measure_snapshots[point].push(snap);
                     }
                 }
             }
@@ -419,7 +1276,9 @@ fn run_pt<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send + Sync>(
             );
         }
     }
-    pb.finish_with_message("done");
+    if !pb.is_finished() {
+        pb.finish_with_message("done");
+    }
 
     // Save snapshots by temperature (not by replica).
     #[cfg(feature = "snapshots")]
@@ -427,15 +1286,12 @@ fn run_pt<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send + Sync>(
         let snapshot_dir = &snaps.save_directory;
         std::fs::create_dir_all(snapshot_dir)
             .with_context(|| format!("Failed to create snapshot directory: {snapshot_dir}"))?;
-        for t in 0..n_temps {
-            let file_name = format!(
-                "{snapshot_dir}/T_{:.4}.npz",
-                config.simulation.temperatures[t]
-            );
+        for (point, (scale, temperature)) in iproduct!(&scales, &temperatures).enumerate() {
+            let file_name = format!("{snapshot_dir}/{}.npz", point_label(*temperature, *scale));
             match config::save_snapshots_to_npz(
                 &file_name,
-                &equil_snapshots[t],
-                &measure_snapshots[t],
+                &equil_snapshots[point],
+                &measure_snapshots[point],
                 snaps.compression_level,
             ) {
                 Ok(_) => info!("Saved snapshots to file {file_name} successfully"),
@@ -444,12 +1300,51 @@ fn run_pt<S: SpinState, R: rand::Rng + Clone + SeedableRng + Send + Sync>(
         }
     }
 
-    // Return results in temperature order.
-    // After all swaps, stats[temp_to_replica[t]] holds data for temperature t.
-    Ok((0..n_temps)
-        .map(|t| {
-            let r = temp_to_replica[t];
-            stats[r].result()
+    for (dimension, ladders) in [
+        ("temperature", &temperature_swaps),
+        ("anisotropy scale", &scale_swaps),
+    ] {
+        if ladders.is_empty() {
+            continue;
+        }
+        let acceptances: Vec<f64> = ladders
+            .iter()
+            .flat_map(|swaps| (0..swaps.attempts.len()).map(|pair| swaps.acceptance(pair)))
+            .collect();
+        let mean_acceptance = acceptances.iter().sum::<f64>() / acceptances.len() as f64;
+        let round_trips = ladders
+            .iter()
+            .flat_map(|swaps| &swaps.round_trips)
+            .sum::<usize>();
+        info!(
+            "PT {dimension} swap acceptance {mean_acceptance:.4} on average, {round_trips} round trips"
+        );
+    }
+    if let Some(parallel_tempering) = &config.parallel_tempering {
+        write_swap_statistics(
+            &parallel_tempering.savefile,
+            &temperatures,
+            &scales,
+            &temperature_swaps,
+            &scale_swaps,
+        )?;
+        info!(
+            "Swap statistics saved to file: {}",
+            parallel_tempering.savefile
+        );
+    }
+
+    // Return results in temperature order, scale by scale.
+    // After all swaps, stats[point_to_replica[s][t]] holds data for that point.
+    Ok(point_to_replica
+        .iter()
+        .flatten()
+        .map(|&r| StatResult {
+            sweeps: replica_sweeps[r],
+            equilibration_sweeps: equil_end,
+            measurement_sweeps: meas_sweeps,
+            samples: stats[r].take_samples(),
+            ..stats[r].result()
         })
         .collect())
 }
@@ -470,26 +1365,33 @@ fn accepts_parallel_tempering_swap(
     }
 }
 
+/// The replicas along the scale ladder of temperature `t`.
+fn column(point_to_replica: &[Vec<usize>], t: usize) -> Vec<usize> {
+    point_to_replica.iter().map(|row| row[t]).collect()
+}
+
 // Dispatch
 fn run_simulations<S: SpinState>(
     config: &Config,
     stats_config: &StatsConfig,
+    geometry: &Geometry,
+    stop: &AtomicBool,
 ) -> anyhow::Result<Vec<StatResult>> {
     let Systems {
         mut stats,
         mut grids,
         mut algos,
-    } = build_systems::<S>(config, stats_config)?;
+    } = build_systems::<S>(config, stats_config, geometry)?;
 
     if config.simulation.pt_interval > 0 {
         info!(
             "PT enabled, swap every {} sweeps",
             config.simulation.pt_interval
         );
-        run_pt(config, &mut stats, &mut algos, &mut grids)
+        run_pt(config, &mut stats, &mut algos, &mut grids, stop)
     } else {
         info!("Non-PT mode, independent temperatures");
-        run_independent(config, stats, algos, grids)
+        run_independent(config, stats, algos, grids, stop)
     }
 }
 
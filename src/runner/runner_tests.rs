@@ -5,6 +5,8 @@ fn energy_stats_config(group_num: usize) -> StatsConfig {
     StatsConfig {
         energy: true,
         heat_capacity: false,
+        energy_terms: false,
+        exchange_entries: 0,
         magnetization: false,
         susceptibility: false,
         magnetization_abs: false,
@@ -13,7 +15,19 @@ fn energy_stats_config(group_num: usize) -> StatsConfig {
         group_susceptibility: false,
         group_magnetization_abs: false,
         group_susceptibility_abs: false,
+        binder_cumulant: false,
+        group_binder_cumulant: false,
+        correlation_length: false,
+        vortex_density: false,
+        cluster_size: false,
+        improved_estimators: false,
+        anisotropy_scale: false,
+        helicity_axes: vec![],
+        error_bars: false,
+        autocorrelation: false,
+        record_samples: false,
         group_num,
+        order_parameter_num: 0,
     }
 }
 
@@ -28,7 +42,6 @@ fn unique_temp_file(prefix: &str) -> std::path::PathBuf {
     ))
 }
 
-#[cfg(feature = "snapshots")]
 fn unique_temp_dir(prefix: &str) -> std::path::PathBuf {
     std::env::temp_dir().join(format!(
         "{prefix}_{}_{}",
@@ -209,12 +222,85 @@ group = [[0]]
 "#;
     let config = Config::new(toml).unwrap();
     let stats_config = energy_stats_config(1);
-    let sys = build_systems::<IsingSpin>(&config, &stats_config).unwrap();
+    let sys = build_systems::<IsingSpin>(
+        &config,
+        &stats_config,
+        &Geometry::new(&config, &stats_config).unwrap(),
+    )
+    .unwrap();
     assert_eq!(sys.stats.len(), 3);
     assert_eq!(sys.grids.len(), 3);
     assert_eq!(sys.algos.len(), 3);
 }
 
+/// A Heisenberg lattice with a [structure] section, for the measurements
+/// built from the lattice geometry.
+fn geometry_toml(measurements: &str) -> String {
+    format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "heisenberg"
+equilibration_steps = 10
+measurement_steps = 10
+temperatures = [1.0, 2.0, 3.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[structure]
+cell = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 10.0]]
+positions = [[0.0, 0.0, 0.0]]
+
+[[exchange]]
+neighbor_order = 1
+strength = 1.0
+
+[output]
+energy = true
+{measurements}
+"#
+    )
+}
+
+#[test]
+fn build_systems_share_the_measurement_geometry() {
+    let sq_dir = unique_temp_dir("spinmc_test_geometry_sq");
+    let measurements = format!(
+        "[correlation]\nshells = 2\n\n[topological_charge]\n\n[structure_factor]\nfull_grid = true\nsave_directory = {}",
+        toml_basic_string(sq_dir.to_str().unwrap())
+    );
+    let config = Config::new(&geometry_toml(&measurements)).unwrap();
+    let stats_config = StatsConfig {
+        vortex_density: true,
+        helicity_axes: config.grid.periodic_axes(),
+        ..energy_stats_config(0)
+    };
+    let geometry = Geometry::new(&config, &stats_config).unwrap();
+    let sys = build_systems::<HeisenbergSpin>(&config, &stats_config, &geometry).unwrap();
+    let shells = geometry.correlation.as_ref().unwrap();
+    let q_points = geometry.q_points.as_ref().unwrap();
+    let triangulation = geometry.triangulation.as_ref().unwrap();
+    let plaquettes = geometry.plaquettes.as_ref().unwrap();
+    let helicity = geometry.helicity.as_ref().unwrap();
+    for stat in &sys.stats {
+        let accumulator = stat.correlation.as_ref().unwrap();
+        assert!(Arc::ptr_eq(&accumulator.correlation, shells));
+        let accumulator = stat.static_structure_factor.as_ref().unwrap();
+        assert!(Arc::ptr_eq(&accumulator.q_points, q_points));
+        let accumulator = stat.topology.as_ref().unwrap();
+        assert!(Arc::ptr_eq(&accumulator.triangulation, triangulation));
+        assert!(Arc::ptr_eq(stat.plaquettes.as_ref().unwrap(), plaquettes));
+        assert!(Arc::ptr_eq(stat.helicity.as_ref().unwrap(), helicity));
+    }
+    let _ = std::fs::remove_dir_all(sq_dir);
+}
+
 #[test]
 fn build_systems_beta_calculation() {
     let toml = r#"
@@ -245,7 +331,12 @@ group = [[0]]
 "#;
     let config = Config::new(toml).unwrap();
     let stats_config = energy_stats_config(1);
-    let sys = build_systems::<IsingSpin>(&config, &stats_config).unwrap();
+    let sys = build_systems::<IsingSpin>(
+        &config,
+        &stats_config,
+        &Geometry::new(&config, &stats_config).unwrap(),
+    )
+    .unwrap();
     let beta = sys.algos[0].beta();
     let expected = 1.0 / (8.617333262145e-5 * 2.0);
     assert!((beta - expected).abs() < 1e-10);
@@ -281,7 +372,12 @@ group = [[0]]
 "#;
     let config = Config::new(toml).unwrap();
     let stats_config = energy_stats_config(1);
-    let sys = build_systems::<IsingSpin>(&config, &stats_config).unwrap();
+    let sys = build_systems::<IsingSpin>(
+        &config,
+        &stats_config,
+        &Geometry::new(&config, &stats_config).unwrap(),
+    )
+    .unwrap();
     assert!(sys.algos[0].beta().is_infinite());
 }
 
@@ -315,7 +411,12 @@ group = [[0]]
 "#;
     let config = Config::new(toml).unwrap();
     let stats_config = energy_stats_config(1);
-    let sys = build_systems::<IsingSpin>(&config, &stats_config).unwrap();
+    let sys = build_systems::<IsingSpin>(
+        &config,
+        &stats_config,
+        &Geometry::new(&config, &stats_config).unwrap(),
+    )
+    .unwrap();
     assert_eq!(sys.algos.len(), 1);
     assert!(matches!(sys.algos[0], AnyMC::Wolff(_)));
 }
@@ -352,11 +453,18 @@ group = [[0]]
 "#;
     let config = Config::new(toml).unwrap();
     let stats_config = energy_stats_config(1);
-    let results = run_simulations::<IsingSpin>(&config, &stats_config).unwrap();
+    let results = run_simulations::<IsingSpin>(
+        &config,
+        &stats_config,
+        &Geometry::new(&config, &stats_config).unwrap(),
+        &AtomicBool::new(false),
+    )
+    .unwrap();
 
     assert_eq!(results.len(), 2);
     assert_eq!(results[0].t, 1.0);
     assert_eq!(results[1].t, 2.0);
+    assert!(results.iter().all(|result| result.sweeps == 5));
     assert!(results.iter().all(|result| result.energy.is_some()));
     assert!(
         results
@@ -429,7 +537,13 @@ group = [[0]]
 "#;
     let config = Config::new(toml).unwrap();
     let stats_config = energy_stats_config(1);
-    let results = run_simulations::<IsingSpin>(&config, &stats_config).unwrap();
+    let results = run_simulations::<IsingSpin>(
+        &config,
+        &stats_config,
+        &Geometry::new(&config, &stats_config).unwrap(),
+        &AtomicBool::new(false),
+    )
+    .unwrap();
 
     assert_eq!(results.len(), 2);
     assert_eq!(results[0].t, 0.0);
@@ -480,6 +594,7 @@ group = [[0]]
     let content = std::fs::read_to_string(&savefile).unwrap();
     assert!(content.contains("#T(K)"));
     assert!(content.contains("Energy"));
+    assert!(!content.contains("PARTIAL"));
     let result_lines = non_comment_lines(&content);
     assert_eq!(result_lines.len(), 1);
     assert!(result_lines[0].starts_with("1.000000"));
@@ -488,34 +603,28 @@ group = [[0]]
 }
 
 #[test]
-fn build_thread_pool_uses_configured_num_threads() {
-    let one = build_thread_pool(1).unwrap();
-    assert_eq!(one.current_num_threads(), 1);
-
-    let two = build_thread_pool(2).unwrap();
-    assert_eq!(two.current_num_threads(), 2);
-}
-
-#[cfg(feature = "snapshots")]
-#[test]
-fn run_end_to_end_independent_writes_npz_snapshots() {
-    let savefile = unique_temp_file("spinmc_snapshot_result");
-    let snapshot_dir = unique_temp_dir("spinmc_independent_snapshots");
+fn run_end_to_end_writes_reweighted_results() {
+    let savefile = unique_temp_file("spinmc_test_reweight_result");
+    let reweighted = unique_temp_file("spinmc_test_reweighted");
+    let samples_dir = unique_temp_dir("spinmc_test_reweight_samples");
     let savefile_toml = toml_basic_string(savefile.to_str().unwrap());
-    let snapshot_dir_toml = toml_basic_string(snapshot_dir.to_str().unwrap());
+    let reweighted_toml = toml_basic_string(reweighted.to_str().unwrap());
+    let samples_toml = toml_basic_string(samples_dir.to_str().unwrap());
     let toml = format!(
         r#"
 [simulation]
-initial_state = "z"
+initial_state = "random"
 model = "ising"
-equilibration_steps = 2
-measurement_steps = 3
-temperatures = [1.0]
+equilibration_steps = 50
+measurement_steps = 200
+temperatures = [2.0, 3.0]
 num_threads = 1
 algorithm = "metropolis"
+boltzmann_constant = 1.0
+seed = 5
 
 [grid]
-dimensions = [2, 2, 1]
+dimensions = [4, 4, 1]
 sublattices = 1
 spin_magnitudes = [1.0]
 periodic_boundary = [true, true, true]
@@ -529,46 +638,64 @@ strength = 1.0
 [output]
 energy = true
 savefile = {savefile_toml}
-group = [[0]]
 
-[snapshots]
-equilibration_interval = 1
-measurement_interval = 1
-save_directory = {snapshot_dir_toml}
+[reweighting]
+temperature_range = [{{ start = 2.0, end = 3.0, step = 0.25 }}]
+savefile = {reweighted_toml}
+samples_directory = {samples_toml}
 "#
     );
-
     run(&toml).unwrap();
 
-    let snapshot_file = snapshot_dir.join("T_1.0000.npz");
-    let mut reader =
-        ndarray_npy::NpzReader::new(std::fs::File::open(&snapshot_file).unwrap()).unwrap();
-    let equil: ndarray::ArrayD<f64> = reader.by_name("equil").unwrap();
-    let steps: ndarray::ArrayD<f64> = reader.by_name("steps").unwrap();
-    assert_eq!(equil.shape(), &[2, 1, 2, 2, 1, 3]);
-    assert_eq!(steps.shape(), &[3, 1, 2, 2, 1, 3]);
+    let content = std::fs::read_to_string(&savefile).unwrap();
+    for (row, t) in non_comment_lines(&content).iter().zip(["2.0000", "3.0000"]) {
+        let energy: f64 = row.split('\t').nth(1).unwrap().trim().parse().unwrap();
+        let series = std::fs::read_to_string(samples_dir.join(format!("T_{t}.csv"))).unwrap();
+        let mut lines = series.lines();
+        assert_eq!(lines.next(), Some("energy,m_abs"));
+        let samples: Vec<[f64; 2]> = lines
+            .map(|line| {
+                let (e, m) = line.split_once(',').unwrap();
+                [e.parse().unwrap(), m.parse().unwrap()]
+            })
+            .collect();
+        assert_eq!(samples.len(), 200);
+        let mean = samples.iter().map(|s| s[0]).sum::<f64>() / 200.0;
+        assert!((mean - energy).abs() < 1e-5, "{mean} {energy}");
+        assert!(samples.iter().all(|s| (0.0..=1.0).contains(&s[1])));
+    }
 
+    let content = std::fs::read_to_string(&reweighted).unwrap();
+    let mut lines = content.lines();
+    assert!(lines.next().unwrap().starts_with("#T(K)"));
+    let rows: Vec<Vec<f64>> = lines
+        .map(|line| {
+            line.split('\t')
+                .map(|v| v.trim().parse().unwrap())
+                .collect()
+        })
+        .collect();
+    assert_eq!(rows.len(), 5);
+    for row in &rows {
+        assert_eq!(row.len(), 5);
+        assert!(row.iter().all(|v| v.is_finite()), "{row:?}");
+    }
     let _ = std::fs::remove_file(savefile);
-    let _ = std::fs::remove_dir_all(snapshot_dir);
+    let _ = std::fs::remove_file(reweighted);
+    let _ = std::fs::remove_dir_all(samples_dir);
 }
 
-#[cfg(feature = "snapshots")]
-#[test]
-fn run_end_to_end_pt_writes_temperature_npz_snapshots() {
-    let savefile = unique_temp_file("spinmc_pt_snapshot_result");
-    let snapshot_dir = unique_temp_dir("spinmc_pt_snapshots");
-    let savefile_toml = toml_basic_string(savefile.to_str().unwrap());
-    let snapshot_dir_toml = toml_basic_string(snapshot_dir.to_str().unwrap());
-    let toml = format!(
+fn time_series_toml(savefile: &str, series_dir: &str, pt_interval: usize) -> String {
+    format!(
         r#"
 [simulation]
 initial_state = "z"
 model = "ising"
-equilibration_steps = 2
-measurement_steps = 2
+equilibration_steps = 4
+measurement_steps = 5
 temperatures = [1.0, 2.0]
 num_threads = 1
-pt_interval = 1
+pt_interval = {pt_interval}
 algorithm = "metropolis"
 
 [grid]
@@ -585,34 +712,1159 @@ strength = 1.0
 
 [output]
 energy = true
-stats_interval = 1
-savefile = {savefile_toml}
+savefile = {savefile}
 group = [[0]]
 
-[snapshots]
-equilibration_interval = 1
-measurement_interval = 1
-save_directory = {snapshot_dir_toml}
-"#
+[time_series]
+equilibration_interval = 2
+save_directory = {series_dir}
+"#,
+        savefile = toml_basic_string(savefile),
+        series_dir = toml_basic_string(series_dir),
+    )
+}
+
+#[test]
+fn run_end_to_end_writes_time_series_per_temperature() {
+    for pt_interval in [0, 1] {
+        let savefile = unique_temp_file("spinmc_test_series_result");
+        let series_dir = unique_temp_dir("spinmc_test_series");
+        let toml = time_series_toml(
+            savefile.to_str().unwrap(),
+            series_dir.to_str().unwrap(),
+            pt_interval,
+        );
+        run(&toml).unwrap();
+
+        for t in ["1.0000", "2.0000"] {
+            let content = std::fs::read_to_string(series_dir.join(format!("T_{t}.csv"))).unwrap();
+            let lines: Vec<&str> = content.lines().collect();
+            assert_eq!(lines[0], "sweep,energy,mx,my,mz,m0x,m0y,m0z");
+            let sweeps: Vec<usize> = lines[1..]
+                .iter()
+                .map(|line| line.split(',').next().unwrap().parse().unwrap())
+                .collect();
+            assert_eq!(
+                sweeps,
+                vec![2, 4, 5, 6, 7, 8, 9],
+                "pt_interval={pt_interval}"
+            );
+            for line in &lines[1..] {
+                let values: Vec<f64> = line.split(',').map(|v| v.parse().unwrap()).collect();
+                assert_eq!(values.len(), 8);
+                // Ising spins lie along z, and the only group is the whole lattice.
+                assert_eq!(values[2], 0.0);
+                assert_eq!(values[4], values[7]);
+            }
+        }
+        let _ = std::fs::remove_file(savefile);
+        let _ = std::fs::remove_dir_all(series_dir);
+    }
+}
+
+fn replicas_toml(savefile: &str, series_dir: &str, replicas: usize, error_bars: bool) -> String {
+    format!(
+        r#"
+[simulation]
+initial_state = "random"
+model = "ising"
+equilibration_steps = 20
+measurement_steps = 50
+temperatures = [1.5, 3.0]
+num_threads = 2
+replicas_per_temperature = {replicas}
+algorithm = "metropolis"
+boltzmann_constant = 1.0
+seed = 11
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+error_bars = {error_bars}
+savefile = {savefile}
+progress_bar = false
+
+[time_series]
+save_directory = {series_dir}
+"#,
+        savefile = toml_basic_string(savefile),
+        series_dir = toml_basic_string(series_dir),
+    )
+}
+
+#[test]
+fn run_end_to_end_averages_independent_replicas() {
+    let series_energies = |path: std::path::PathBuf| -> Vec<f64> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').nth(1).unwrap().parse().unwrap())
+            .collect()
+    };
+    let single_dir = unique_temp_dir("spinmc_test_single_series");
+    let single_file = unique_temp_file("spinmc_test_single_result");
+    run(&replicas_toml(
+        single_file.to_str().unwrap(),
+        single_dir.to_str().unwrap(),
+        1,
+        true,
+    ))
+    .unwrap();
+    let series_dir = unique_temp_dir("spinmc_test_replica_series");
+    let savefile = unique_temp_file("spinmc_test_replica_result");
+    run(&replicas_toml(
+        savefile.to_str().unwrap(),
+        series_dir.to_str().unwrap(),
+        3,
+        true,
+    ))
+    .unwrap();
+
+    let content = std::fs::read_to_string(&savefile).unwrap();
+    let rows: Vec<Vec<f64>> = non_comment_lines(&content)
+        .iter()
+        .map(|row| row.split('\t').map(|v| v.trim().parse().unwrap()).collect())
+        .collect();
+    assert_eq!(rows.len(), 2);
+    for (row, t) in rows.iter().zip(["1.5000", "3.0000"]) {
+        // The first replica reuses the streams of a run without replicas.
+        assert_eq!(
+            series_energies(series_dir.join(format!("T_{t}_R_0.csv"))),
+            series_energies(single_dir.join(format!("T_{t}.csv")))
+        );
+        let replicas: Vec<Vec<f64>> = (0..3)
+            .map(|k| series_energies(series_dir.join(format!("T_{t}_R_{k}.csv"))))
+            .collect();
+        assert_ne!(replicas[0], replicas[1]);
+        let means: Vec<f64> = replicas
+            .iter()
+            .map(|energies| energies.iter().sum::<f64>() / energies.len() as f64)
+            .collect();
+        let mean = means.iter().sum::<f64>() / 3.0;
+        let scatter = means.iter().map(|m| (m - mean).powi(2)).sum::<f64>();
+        assert!((row[1] - mean).abs() < 1e-5, "{row:?}");
+        assert!((row[2] - (scatter / 6.0).sqrt()).abs() < 1e-5, "{row:?}");
+    }
+
+    let _ = std::fs::remove_file(single_file);
+    let _ = std::fs::remove_file(savefile);
+    let _ = std::fs::remove_dir_all(single_dir);
+    let _ = std::fs::remove_dir_all(series_dir);
+}
+
+#[test]
+fn run_end_to_end_gives_replica_errors_without_error_bars() {
+    let series_dir = unique_temp_dir("spinmc_test_replica_no_bars_series");
+    let savefile = unique_temp_file("spinmc_test_replica_no_bars_result");
+    run(&replicas_toml(
+        savefile.to_str().unwrap(),
+        series_dir.to_str().unwrap(),
+        2,
+        false,
+    ))
+    .unwrap();
+
+    let content = std::fs::read_to_string(&savefile).unwrap();
+    let header: Vec<&str> = content.lines().next().unwrap().split('\t').collect();
+    assert_eq!(header[1].trim(), "Energy(eV)");
+    assert_eq!(header[2].trim(), "δEnergy(eV)");
+    for row in non_comment_lines(&content) {
+        let values: Vec<f64> = row.split('\t').map(|v| v.trim().parse().unwrap()).collect();
+        assert_eq!(values.len(), 3, "{row}");
+        assert!(values[2].is_finite() && values[2] > 0.0, "{row}");
+    }
+
+    let _ = std::fs::remove_file(savefile);
+    let _ = std::fs::remove_dir_all(series_dir);
+}
+
+#[test]
+fn run_end_to_end_writes_correlation_function_and_length() {
+    let savefile = unique_temp_file("spinmc_test_corr_result");
+    let corrfile = unique_temp_file("spinmc_test_corr");
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 10
+measurement_steps = 20
+temperatures = [1.0, 50.0]
+num_threads = 1
+algorithm = "metropolis"
+boltzmann_constant = 1.0
+seed = 3
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[structure]
+cell = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 10.0]]
+positions = [[0.0, 0.0, 0.0]]
+
+[[exchange]]
+neighbor_order = 1
+strength = 1.0
+
+[output]
+energy = true
+correlation_length = true
+savefile = {savefile}
+progress_bar = false
+
+[correlation]
+shells = 2
+savefile = {corrfile}
+"#,
+        savefile = toml_basic_string(savefile.to_str().unwrap()),
+        corrfile = toml_basic_string(corrfile.to_str().unwrap()),
     );
+    run(&toml).unwrap();
+
+    let content = std::fs::read_to_string(&savefile).unwrap();
+    assert!(content.lines().next().unwrap().contains("$\\xi/L$"));
+    let rows = non_comment_lines(&content);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].split('\t').count(), 4);
+
+    let content = std::fs::read_to_string(&corrfile).unwrap();
+    assert!(content.starts_with("# G_{0,0}^{(1)}: r = 1.00000000, 64 pairs"));
+    let rows = non_comment_lines(&content);
+    assert_eq!(rows.len(), 2);
+    let cold: Vec<f64> = rows[0]
+        .split('\t')
+        .map(|v| v.trim().parse().unwrap())
+        .collect();
+    let hot: Vec<f64> = rows[1]
+        .split('\t')
+        .map(|v| v.trim().parse().unwrap())
+        .collect();
+    assert_eq!(cold.len(), 3);
+    // Ferromagnetic coupling: near-perfect order at T = 1, decayed at T = 50.
+    assert!(cold[1] > 0.9 && cold[2] > 0.9, "{cold:?}");
+    assert!(hot[1] < cold[1] && hot[2] < cold[2], "{hot:?}");
+
+    let _ = std::fs::remove_file(savefile);
+    let _ = std::fs::remove_file(corrfile);
+}
+
+#[test]
+fn run_end_to_end_writes_swap_statistics_of_adapted_ladder() {
+    let savefile = unique_temp_file("spinmc_test_pt_result");
+    let swapfile = unique_temp_file("spinmc_test_pt_swaps");
+    let series_dir = unique_temp_dir("spinmc_test_pt_adapted_series");
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 400
+measurement_steps = 200
+temperatures = [1.0, 1.5, 2.0, 2.5, 3.0, 6.0]
+num_threads = 1
+pt_interval = 1
+algorithm = "metropolis"
+boltzmann_constant = 1.0
+seed = 11
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [0, 1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+savefile = {savefile}
+progress_bar = false
 
+[parallel_tempering]
+ladder = "acceptance"
+adapt_interval = 50
+savefile = {swapfile}
+
+[time_series]
+save_directory = {series_dir}
+"#,
+        savefile = toml_basic_string(savefile.to_str().unwrap()),
+        swapfile = toml_basic_string(swapfile.to_str().unwrap()),
+        series_dir = toml_basic_string(series_dir.to_str().unwrap()),
+    );
     run(&toml).unwrap();
 
-    for temperature in ["1.0000", "2.0000"] {
-        let snapshot_file = snapshot_dir.join(format!("T_{temperature}.npz"));
-        let mut reader =
-            ndarray_npy::NpzReader::new(std::fs::File::open(&snapshot_file).unwrap()).unwrap();
-        let equil: ndarray::ArrayD<f64> = reader.by_name("equil").unwrap();
-        let steps: ndarray::ArrayD<f64> = reader.by_name("steps").unwrap();
-        assert_eq!(equil.shape(), &[2, 1, 2, 2, 1, 3]);
-        assert_eq!(steps.shape(), &[2, 1, 2, 2, 1, 3]);
+    let content = std::fs::read_to_string(&swapfile).unwrap();
+    assert_eq!(
+        content
+            .lines()
+            .filter(|line| line.starts_with("# replica"))
+            .count(),
+        6
+    );
+    let rows: Vec<Vec<f64>> = non_comment_lines(&content)
+        .iter()
+        .map(|row| row.split('\t').map(|v| v.trim().parse().unwrap()).collect())
+        .collect();
+    assert_eq!(rows.len(), 6);
+    // The ends stay fixed and the ladder moved but stayed ordered.
+    assert_eq!(rows[0][0], 1.0);
+    assert_eq!(rows[5][0], 6.0);
+    assert!(rows.windows(2).all(|pair| pair[0][0] < pair[1][0]));
+    assert!(
+        rows[1..5]
+            .iter()
+            .any(|row| ![1.5, 2.0, 2.5, 3.0].contains(&row[0]))
+    );
+    for row in &rows[..5] {
+        assert_eq!(row[1], 200.0 / 2.0);
+        assert!((0.0..=1.0).contains(&row[2]), "{row:?}");
+    }
+    assert!(rows[5][2].is_nan());
+
+    // The results are reported at the adapted temperatures.
+    let content = std::fs::read_to_string(&savefile).unwrap();
+    let temperatures: Vec<f64> = non_comment_lines(&content)
+        .iter()
+        .map(|row| row.split('\t').next().unwrap().trim().parse().unwrap())
+        .collect();
+    for (result, row) in temperatures.iter().zip(&rows) {
+        assert!((result - row[0]).abs() < 1e-5);
+    }
+
+    // So are the time series, one file per adapted temperature.
+    let mut names: Vec<String> = std::fs::read_dir(&series_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    let mut expected: Vec<String> = temperatures
+        .iter()
+        .map(|t| format!("T_{t:.4}.csv"))
+        .collect();
+    expected.sort();
+    assert_eq!(names, expected);
+    for name in &names {
+        let content = std::fs::read_to_string(series_dir.join(name)).unwrap();
+        assert_eq!(content.lines().count(), 1 + 200);
     }
 
     let _ = std::fs::remove_file(savefile);
-    let _ = std::fs::remove_dir_all(snapshot_dir);
+    let _ = std::fs::remove_file(swapfile);
+    let _ = std::fs::remove_dir_all(series_dir);
+}
+
+fn anisotropy_scale_toml(savefile: &str, swapfile: &str, temperatures: &str) -> String {
+    format!(
+        r#"
+[simulation]
+initial_state = "random"
+model = "heisenberg"
+equilibration_steps = 50
+measurement_steps = 100
+temperatures = {temperatures}
+num_threads = 2
+pt_interval = 1
+algorithm = "metropolis"
+boltzmann_constant = 1.0
+seed = 5
+
+[grid]
+dimensions = [3, 3, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]
+strength = 1.0
+
+[anisotropy]
+axis = [[0.0, 0.0, 1.0]]
+strength = [1.0]
+
+[output]
+energy = true
+energy_terms = true
+savefile = {savefile}
+progress_bar = false
+
+[parallel_tempering]
+anisotropy_scales = [0.0, 1.0, 2.0]
+savefile = {swapfile}
+"#,
+        savefile = toml_basic_string(savefile),
+        swapfile = toml_basic_string(swapfile),
+    )
 }
 
 #[test]
-fn run_invalid_toml_errors() {
-    assert!(run("invalid toml {{{").is_err());
+fn run_end_to_end_tempers_in_temperature_and_anisotropy_scale() {
+    let savefile = unique_temp_file("spinmc_test_scale_result");
+    let swapfile = unique_temp_file("spinmc_test_scale_swaps");
+    run(&anisotropy_scale_toml(
+        savefile.to_str().unwrap(),
+        swapfile.to_str().unwrap(),
+        "[0.5, 1.0]",
+    ))
+    .unwrap();
+
+    let content = std::fs::read_to_string(&savefile).unwrap();
+    assert!(content.lines().next().unwrap().contains("K/K_0"));
+    let rows: Vec<Vec<f64>> = non_comment_lines(&content)
+        .iter()
+        .map(|row| row.split('\t').map(|v| v.trim().parse().unwrap()).collect())
+        .collect();
+    // T, K/K_0, E, E_ex, E_ani for every (scale, T) point, scale by scale.
+    let points: Vec<[f64; 2]> = rows.iter().map(|row| [row[0], row[1]]).collect();
+    assert_eq!(
+        points,
+        [
+            [0.5, 0.0],
+            [1.0, 0.0],
+            [0.5, 1.0],
+            [1.0, 1.0],
+            [0.5, 2.0],
+            [1.0, 2.0]
+        ]
+    );
+    for row in &rows {
+        assert!((row[2] - row[3] - row[4]).abs() < 1e-5, "{row:?}");
+        // The statistics follow their scale through every exchange.
+        if row[1] == 0.0 {
+            assert_eq!(row[4], 0.0);
+        } else {
+            assert!(row[4] < 0.0, "{row:?}");
+        }
+    }
+
+    let content = std::fs::read_to_string(&swapfile).unwrap();
+    for section in [
+        "# anisotropy scale = 0.0000",
+        "# anisotropy scale = 2.0000",
+        "# T = 0.5000",
+        "# T = 1.0000",
+    ] {
+        assert!(content.contains(section), "{section}");
+    }
+    assert_eq!(
+        content
+            .lines()
+            .filter(|line| line.starts_with("#K/K_0"))
+            .count(),
+        2
+    );
+    assert_eq!(non_comment_lines(&content).len(), 3 * 2 + 2 * 3);
+
+    let _ = std::fs::remove_file(savefile);
+    let _ = std::fs::remove_file(swapfile);
+}
+
+#[test]
+fn run_end_to_end_tempers_anisotropy_scale_at_one_temperature() {
+    let savefile = unique_temp_file("spinmc_test_scale_only_result");
+    let swapfile = unique_temp_file("spinmc_test_scale_only_swaps");
+    run(&anisotropy_scale_toml(
+        savefile.to_str().unwrap(),
+        swapfile.to_str().unwrap(),
+        "[0.5]",
+    ))
+    .unwrap();
+
+    let content = std::fs::read_to_string(&savefile).unwrap();
+    assert_eq!(non_comment_lines(&content).len(), 3);
+    let content = std::fs::read_to_string(&swapfile).unwrap();
+    assert!(!content.contains("# anisotropy scale ="));
+    let rows: Vec<Vec<f64>> = non_comment_lines(&content)
+        .iter()
+        .map(|row| row.split('\t').map(|v| v.trim().parse().unwrap()).collect())
+        .collect();
+    assert_eq!(rows.len(), 3);
+    // Alternating pairs: each is tried every other sweep of measurement.
+    assert_eq!(rows[0][1], 50.0);
+    assert_eq!(rows[1][1], 50.0);
+    assert!(rows.iter().take(2).all(|row| (0.0..=1.0).contains(&row[2])));
+
+    let _ = std::fs::remove_file(savefile);
+    let _ = std::fs::remove_file(swapfile);
+}
+
+#[test]
+fn run_end_to_end_writes_structure_factor_per_temperature() {
+    let savefile = unique_temp_file("spinmc_test_sq_result");
+    let sq_dir = unique_temp_dir("spinmc_test_sq");
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 5
+measurement_steps = 10
+temperatures = [0.1, 1.0]
+num_threads = 1
+algorithm = "metropolis"
+boltzmann_constant = 1.0
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+savefile = {savefile}
+progress_bar = false
+
+[structure_factor]
+q_path = [[0.0, 0.0, 0.0], [0.5, 0.0, 0.0]]
+path_points = 2
+save_directory = {sq_dir}
+"#,
+        savefile = toml_basic_string(savefile.to_str().unwrap()),
+        sq_dir = toml_basic_string(sq_dir.to_str().unwrap()),
+    );
+    run(&toml).unwrap();
+
+    for t in ["0.1000", "1.0000"] {
+        let content = std::fs::read_to_string(sq_dir.join(format!("T_{t}.txt"))).unwrap();
+        assert!(content.starts_with("#h"));
+        let rows = non_comment_lines(&content);
+        assert_eq!(rows.len(), 3);
+        let gamma: Vec<f64> = rows[0]
+            .split('\t')
+            .map(|v| v.trim().parse().unwrap())
+            .collect();
+        assert_eq!(gamma.len(), 5);
+        if t == "0.1000" {
+            // Frozen ferromagnet: S(0) = N.
+            assert!((gamma[4] - 16.0).abs() < 1e-9, "{gamma:?}");
+        }
+    }
+    let _ = std::fs::remove_file(savefile);
+    let _ = std::fs::remove_dir_all(sq_dir);
+}
+
+#[test]
+fn run_end_to_end_writes_topological_charge_and_density() {
+    let savefile = unique_temp_file("spinmc_test_topo_result");
+    let qfile = unique_temp_file("spinmc_test_topo_charge");
+    let density_dir = unique_temp_dir("spinmc_test_topo_density");
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "heisenberg"
+equilibration_steps = 5
+measurement_steps = 10
+temperatures = [0.1, 1.0]
+num_threads = 1
+algorithm = "metropolis"
+boltzmann_constant = 1.0
+
+[grid]
+dimensions = [4, 4, 2]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+savefile = {savefile}
+progress_bar = false
+
+[topological_charge]
+savefile = {qfile}
+density_map = true
+density_directory = {density_dir}
+"#,
+        savefile = toml_basic_string(savefile.to_str().unwrap()),
+        qfile = toml_basic_string(qfile.to_str().unwrap()),
+        density_dir = toml_basic_string(density_dir.to_str().unwrap()),
+    );
+    run(&toml).unwrap();
+
+    let content = std::fs::read_to_string(&qfile).unwrap();
+    assert!(content.starts_with("#T(K)"));
+    assert!(content.contains("Q_1"));
+    let rows = non_comment_lines(&content);
+    assert_eq!(rows.len(), 2);
+    let values: Vec<f64> = rows[0]
+        .split('\t')
+        .map(|v| v.trim().parse().unwrap())
+        .collect();
+    // T, Q, |Q|, χ_Q and one charge per z layer.
+    assert_eq!(values.len(), 6);
+    assert!(values[1..].iter().all(|v| v.is_finite()), "{values:?}");
+
+    for t in ["0.1000", "1.0000"] {
+        let content = std::fs::read_to_string(density_dir.join(format!("T_{t}.txt"))).unwrap();
+        assert_eq!(non_comment_lines(&content).len(), 32);
+    }
+    let _ = std::fs::remove_file(savefile);
+    let _ = std::fs::remove_file(qfile);
+    let _ = std::fs::remove_dir_all(density_dir);
+}
+
+#[test]
+fn run_interrupted_writes_partial_results() {
+    let savefile = unique_temp_file("spinmc_test_partial");
+    let savefile_toml = toml_basic_string(savefile.to_str().unwrap());
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 5
+measurement_steps = 5
+temperatures = [1.0, 2.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [2, 2, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [0, 1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+savefile = {savefile_toml}
+group = [[0]]
+"#
+    );
+    run_with_stop(&toml, &AtomicBool::new(true)).unwrap();
+
+    let content = std::fs::read_to_string(&savefile).unwrap();
+    assert!(content.lines().next().unwrap().starts_with("#T(K)"));
+    assert!(content.contains("# PARTIAL RESULTS"));
+    assert!(content.contains("# T=1.0000 sweeps=0/10 (no measurements)"));
+    assert!(content.contains("# T=2.0000 sweeps=0/10 (no measurements)"));
+    assert!(non_comment_lines(&content).is_empty());
+    assert!(!content.contains("NaN"));
+    let _ = std::fs::remove_file(savefile);
+}
+
+#[test]
+fn run_simulations_parallel_tempering_stops_when_interrupted() {
+    let toml = r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 2
+measurement_steps = 3
+temperatures = [1.0, 2.0]
+num_threads = 1
+pt_interval = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [2, 2, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [0, 1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+group = [[0]]
+"#;
+    let config = Config::new(toml).unwrap();
+    let stats_config = energy_stats_config(1);
+    let results = run_simulations::<IsingSpin>(
+        &config,
+        &stats_config,
+        &Geometry::new(&config, &stats_config).unwrap(),
+        &AtomicBool::new(true),
+    )
+    .unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|result| result.sweeps == 0));
+}
+
+fn auto_equilibration_toml(equilibration_steps: usize, pt_interval: usize) -> String {
+    format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = {equilibration_steps}
+auto_equilibration = true
+measurement_steps = 10
+temperatures = [1.0, 2.0]
+num_threads = 1
+pt_interval = {pt_interval}
+algorithm = "metropolis"
+seed = 3
+
+[grid]
+dimensions = [2, 2, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [0, 1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+group = [[0]]
+"#
+    )
+}
+
+#[test]
+fn run_simulations_auto_equilibration_stops_early() {
+    for pt_interval in [0, 1] {
+        let config = Config::new(&auto_equilibration_toml(1_000_000, pt_interval)).unwrap();
+        let stats_config = energy_stats_config(1);
+        let results = run_simulations::<IsingSpin>(
+            &config,
+            &stats_config,
+            &Geometry::new(&config, &stats_config).unwrap(),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        for result in &results {
+            assert!(
+                result.equilibration_sweeps < 1_000_000,
+                "pt_interval={pt_interval}: {}",
+                result.equilibration_sweeps
+            );
+            assert_eq!(result.sweeps, result.equilibration_sweeps + 10);
+        }
+        if pt_interval > 0 {
+            assert_eq!(
+                results[0].equilibration_sweeps,
+                results[1].equilibration_sweeps
+            );
+        }
+    }
+}
+
+#[test]
+fn run_simulations_auto_equilibration_respects_cap() {
+    for pt_interval in [0, 1] {
+        let config = Config::new(&auto_equilibration_toml(50, pt_interval)).unwrap();
+        let stats_config = energy_stats_config(1);
+        let results = run_simulations::<IsingSpin>(
+            &config,
+            &stats_config,
+            &Geometry::new(&config, &stats_config).unwrap(),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        for result in &results {
+            assert_eq!(result.equilibration_sweeps, 50);
+            assert_eq!(result.sweeps, 60);
+        }
+    }
+}
+
+fn target_error_toml(measurement_steps: usize, target: f64, pt_interval: usize) -> String {
+    format!(
+        r#"
+[simulation]
+initial_state = "random"
+model = "ising"
+equilibration_steps = 100
+measurement_steps = {measurement_steps}
+measurement_target_error = {target:e}
+temperatures = [2.5, 3.5]
+boltzmann_constant = 1.0
+num_threads = 1
+pt_interval = {pt_interval}
+algorithm = "metropolis"
+seed = 12
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+error_bars = true
+"#
+    )
+}
+
+#[test]
+fn run_simulations_stops_when_target_error_is_reached() {
+    for pt_interval in [0, 1] {
+        let config = Config::new(&target_error_toml(1_000_000, 0.02, pt_interval)).unwrap();
+        let mut stats_config = energy_stats_config(0);
+        stats_config.error_bars = true;
+        let results = run_simulations::<IsingSpin>(
+            &config,
+            &stats_config,
+            &Geometry::new(&config, &stats_config).unwrap(),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        for result in &results {
+            assert!(
+                result.measurement_sweeps < 1_000_000,
+                "pt_interval={pt_interval}"
+            );
+            assert_eq!(result.sweeps, 100 + result.measurement_sweeps);
+            let error = result.errors.as_ref().unwrap().energy.unwrap();
+            assert!(error <= 0.02, "pt_interval={pt_interval}: {error}");
+        }
+        if pt_interval > 0 {
+            assert_eq!(results[0].measurement_sweeps, results[1].measurement_sweeps);
+        }
+    }
+}
+
+#[test]
+fn run_simulations_target_error_respects_cap() {
+    for pt_interval in [0, 1] {
+        let config = Config::new(&target_error_toml(300, 1e-9, pt_interval)).unwrap();
+        let mut stats_config = energy_stats_config(0);
+        stats_config.error_bars = true;
+        let results = run_simulations::<IsingSpin>(
+            &config,
+            &stats_config,
+            &Geometry::new(&config, &stats_config).unwrap(),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        for result in &results {
+            assert_eq!(result.measurement_sweeps, 300);
+            assert_eq!(result.sweeps, 400);
+        }
+    }
+}
+
+#[test]
+fn write_results_marks_only_incomplete_runs_as_partial() {
+    let savefile = unique_temp_file("spinmc_test_write_results");
+    let savefile_str = savefile.to_str().unwrap();
+    let stats_config = energy_stats_config(0);
+    let complete = [StatResult {
+        t: 1.0,
+        sweeps: 10,
+        measurement_sweeps: 10,
+        energy: Some(-2.0),
+        ..Default::default()
+    }];
+    assert!(!write_results(savefile_str, &stats_config, &complete, 7).unwrap());
+    let content = std::fs::read_to_string(&savefile).unwrap();
+    assert!(!content.contains("PARTIAL"));
+    assert!(content.contains("# seed = 7"));
+
+    let partial = [
+        StatResult {
+            t: 1.0,
+            sweeps: 10,
+            measurement_sweeps: 10,
+            energy: Some(-2.0),
+            ..Default::default()
+        },
+        StatResult {
+            t: 2.0,
+            sweeps: 7,
+            equilibration_sweeps: 2,
+            measurement_sweeps: 8,
+            energy: Some(-1.0),
+            ..Default::default()
+        },
+    ];
+    assert!(write_results(savefile_str, &stats_config, &partial, 7).unwrap());
+    let content = std::fs::read_to_string(&savefile).unwrap();
+    assert!(content.contains("# T=1.0000 sweeps=10/10"));
+    assert!(content.contains("# T=2.0000 sweeps=7/10"));
+    assert_eq!(non_comment_lines(&content).len(), 2);
+    let _ = std::fs::remove_file(savefile);
+}
+
+#[test]
+fn write_results_skips_temperatures_without_measurements() {
+    let savefile = unique_temp_file("spinmc_test_write_unmeasured");
+    let savefile_str = savefile.to_str().unwrap();
+    let stats_config = energy_stats_config(0);
+    let results = [
+        StatResult {
+            t: 1.0,
+            sweeps: 3,
+            equilibration_sweeps: 2,
+            measurement_sweeps: 8,
+            energy: Some(-2.0),
+            ..Default::default()
+        },
+        StatResult {
+            t: 2.0,
+            sweeps: 2,
+            equilibration_sweeps: 2,
+            measurement_sweeps: 8,
+            energy: Some(f64::NAN),
+            ..Default::default()
+        },
+    ];
+    assert!(write_results(savefile_str, &stats_config, &results, 7).unwrap());
+    let content = std::fs::read_to_string(&savefile).unwrap();
+    assert!(content.contains("# T=1.0000 sweeps=3/10\n"));
+    assert!(content.contains("# T=2.0000 sweeps=2/10 (no measurements)"));
+    let rows = non_comment_lines(&content);
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].starts_with("1.0"), "{content}");
+    assert!(!content.contains("NaN"), "{content}");
+    let _ = std::fs::remove_file(savefile);
+}
+
+#[test]
+fn build_thread_pool_uses_configured_num_threads() {
+    let one = build_thread_pool(1).unwrap();
+    assert_eq!(one.current_num_threads(), 1);
+
+    let two = build_thread_pool(2).unwrap();
+    assert_eq!(two.current_num_threads(), 2);
+}
+
+#[cfg(feature = "snapshots")]
+#[test]
+fn run_end_to_end_independent_writes_npz_snapshots() {
+    let savefile = unique_temp_file("spinmc_snapshot_result");
+    let snapshot_dir = unique_temp_dir("spinmc_independent_snapshots");
+    let savefile_toml = toml_basic_string(savefile.to_str().unwrap());
+    let snapshot_dir_toml = toml_basic_string(snapshot_dir.to_str().unwrap());
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 2
+measurement_steps = 3
+temperatures = [1.0]
+num_threads = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [2, 2, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [0, 1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+savefile = {savefile_toml}
+group = [[0]]
+
+[snapshots]
+equilibration_interval = 1
+measurement_interval = 1
+save_directory = {snapshot_dir_toml}
+"#
+    );
+
+    run(&toml).unwrap();
+
+    let snapshot_file = snapshot_dir.join("T_1.0000.npz");
+    let mut reader =
+        ndarray_npy::NpzReader::new(std::fs::File::open(&snapshot_file).unwrap()).unwrap();
+    let equil: ndarray::ArrayD<f64> = reader.by_name("equil").unwrap();
+    let steps: ndarray::ArrayD<f64> = reader.by_name("steps").unwrap();
+    assert_eq!(equil.shape(), &[2, 1, 2, 2, 1, 3]);
+    assert_eq!(steps.shape(), &[3, 1, 2, 2, 1, 3]);
+
+    let _ = std::fs::remove_file(savefile);
+    let _ = std::fs::remove_dir_all(snapshot_dir);
+}
+
+#[cfg(feature = "snapshots")]
+#[test]
+fn run_end_to_end_pt_writes_temperature_npz_snapshots() {
+    let savefile = unique_temp_file("spinmc_pt_snapshot_result");
+    let snapshot_dir = unique_temp_dir("spinmc_pt_snapshots");
+    let savefile_toml = toml_basic_string(savefile.to_str().unwrap());
+    let snapshot_dir_toml = toml_basic_string(snapshot_dir.to_str().unwrap());
+    let toml = format!(
+        r#"
+[simulation]
+initial_state = "z"
+model = "ising"
+equilibration_steps = 2
+measurement_steps = 2
+temperatures = [1.0, 2.0]
+num_threads = 1
+pt_interval = 1
+algorithm = "metropolis"
+
+[grid]
+dimensions = [2, 2, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, true]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [0, 1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+stats_interval = 1
+savefile = {savefile_toml}
+group = [[0]]
+
+[snapshots]
+equilibration_interval = 1
+measurement_interval = 1
+save_directory = {snapshot_dir_toml}
+"#
+    );
+
+    run(&toml).unwrap();
+
+    for temperature in ["1.0000", "2.0000"] {
+        let snapshot_file = snapshot_dir.join(format!("T_{temperature}.npz"));
+        let mut reader =
+            ndarray_npy::NpzReader::new(std::fs::File::open(&snapshot_file).unwrap()).unwrap();
+        let equil: ndarray::ArrayD<f64> = reader.by_name("equil").unwrap();
+        let steps: ndarray::ArrayD<f64> = reader.by_name("steps").unwrap();
+        assert_eq!(equil.shape(), &[2, 1, 2, 2, 1, 3]);
+        assert_eq!(steps.shape(), &[2, 1, 2, 2, 1, 3]);
+    }
+
+    let _ = std::fs::remove_file(savefile);
+    let _ = std::fs::remove_dir_all(snapshot_dir);
+}
+
+#[test]
+fn run_invalid_toml_errors() {
+    assert!(run("invalid toml {{{").is_err());
+}
+
+fn seeded_run_toml(savefile: &std::path::Path, num_threads: usize, pt_interval: usize) -> String {
+    let savefile_toml = toml_basic_string(savefile.to_str().unwrap());
+    format!(
+        r#"
+[simulation]
+initial_state = "random"
+model = "heisenberg"
+equilibration_steps = 20
+measurement_steps = 30
+temperatures = [0.5, 1.0, 1.5, 2.0]
+num_threads = {num_threads}
+pt_interval = {pt_interval}
+algorithm = "metropolis"
+boltzmann_constant = 1.0
+seed = 2024
+
+[grid]
+dimensions = [4, 4, 1]
+sublattices = 1
+spin_magnitudes = [1.0]
+periodic_boundary = [true, true, false]
+
+[[exchange]]
+from_sublattice = 0
+to_sublattice = 0
+offsets = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0]]
+strength = 1.0
+
+[output]
+energy = true
+heat_capacity = true
+magnetization = true
+susceptibility = true
+savefile = {savefile_toml}
+progress_bar = false
+"#
+    )
+}
+
+#[test]
+fn run_with_seed_is_reproducible_across_thread_counts() {
+    for pt_interval in [0, 3] {
+        let contents: Vec<String> = [1, 1, 3]
+            .into_iter()
+            .map(|num_threads| {
+                let savefile = unique_temp_file("spinmc_test_seed");
+                let toml = seeded_run_toml(&savefile, num_threads, pt_interval);
+                run_with_stop(&toml, &AtomicBool::new(false)).unwrap();
+                let content = std::fs::read_to_string(&savefile).unwrap();
+                let _ = std::fs::remove_file(savefile);
+                content
+            })
+            .collect();
+
+        assert!(contents[0].contains("# seed = 2024"));
+        assert_eq!(contents[0], contents[1]);
+        assert_eq!(contents[0], contents[2]);
+    }
+}
+
+#[test]
+fn stream_rng_streams_differ() {
+    let mut a = stream_rng(1, 0);
+    let mut b = stream_rng(1, 1);
+    let mut c = stream_rng(2, 0);
+    let (x, y, z): (u64, u64, u64) = (a.random(), b.random(), c.random());
+    assert_ne!(x, y);
+    assert_ne!(x, z);
+    assert_eq!(x, stream_rng(1, 0).random::<u64>());
 }
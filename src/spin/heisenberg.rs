@@ -4,17 +4,19 @@ use std::{
     ops::{Add, AddAssign, Div, Mul, Neg, Sub},
 };
 
-use crate::spin::SpinState;
+use crate::spin::{Real, SpinState, widen};
 
 #[derive(Default, Debug, Clone, Copy)]
 #[repr(C)]
 pub struct HeisenbergSpin {
-    x: f64,
-    y: f64,
-    z: f64,
+    x: Real,
+    y: Real,
+    z: Real,
 }
 
 impl SpinState for HeisenbergSpin {
+    const COMPONENTS: usize = 3;
+
     fn zero() -> Self {
         Self {
             x: 0.,
@@ -24,7 +26,7 @@ impl SpinState for HeisenbergSpin {
     }
     fn along_x(magnitude: f64) -> anyhow::Result<Self> {
         Ok(Self {
-            x: magnitude,
+            x: magnitude as Real,
             y: 0.,
             z: 0.,
         })
@@ -33,7 +35,7 @@ impl SpinState for HeisenbergSpin {
     fn along_y(magnitude: f64) -> anyhow::Result<Self> {
         Ok(Self {
             x: 0.,
-            y: magnitude,
+            y: magnitude as Real,
             z: 0.,
         })
     }
@@ -41,15 +43,15 @@ impl SpinState for HeisenbergSpin {
         Ok(Self {
             x: 0.,
             y: 0.,
-            z: magnitude,
+            z: magnitude as Real,
         })
     }
     fn random<R: rand::Rng>(rng: &mut R, magnitude: f64) -> Self {
         let [x, y, z]: [f64; 3] = UnitSphere.sample(rng);
         Self {
-            x: x * magnitude,
-            y: y * magnitude,
-            z: z * magnitude,
+            x: (x * magnitude) as Real,
+            y: (y * magnitude) as Real,
+            z: (z * magnitude) as Real,
         }
     }
 
@@ -58,7 +60,7 @@ impl SpinState for HeisenbergSpin {
     }
 
     fn dot(&self, other: &Self) -> f64 {
-        self.x * other.x + self.y * other.y + self.z * other.z
+        widen(self.x * other.x + self.y * other.y + self.z * other.z)
     }
 
     fn norm(&self) -> f64 {
@@ -66,7 +68,7 @@ impl SpinState for HeisenbergSpin {
     }
 
     fn norm_sqr(&self) -> f64 {
-        self.x * self.x + self.y * self.y + self.z * self.z
+        widen(self.x * self.x + self.y * self.y + self.z * self.z)
     }
 
     fn flip(&self, axis: &Self) -> Self {
@@ -74,7 +76,15 @@ impl SpinState for HeisenbergSpin {
     }
 
     fn to_array(&self) -> [f64; 3] {
-        [self.x, self.y, self.z]
+        [widen(self.x), widen(self.y), widen(self.z)]
+    }
+
+    fn from_array([x, y, z]: [f64; 3]) -> Self {
+        Self {
+            x: x as Real,
+            y: y as Real,
+            z: z as Real,
+        }
     }
 }
 
@@ -136,9 +146,9 @@ impl Mul<f64> for HeisenbergSpin {
     type Output = Self;
     fn mul(self, rhs: f64) -> Self {
         Self {
-            x: self.x * rhs,
-            y: self.y * rhs,
-            z: self.z * rhs,
+            x: self.x * rhs as Real,
+            y: self.y * rhs as Real,
+            z: self.z * rhs as Real,
         }
     }
 }
@@ -147,9 +157,9 @@ impl Div<f64> for HeisenbergSpin {
     type Output = Self;
     fn div(self, rhs: f64) -> Self::Output {
         Self {
-            x: self.x / rhs,
-            y: self.y / rhs,
-            z: self.z / rhs,
+            x: self.x / rhs as Real,
+            y: self.y / rhs as Real,
+            z: self.z / rhs as Real,
         }
     }
 }
@@ -158,9 +168,9 @@ impl Div<f64> for &HeisenbergSpin {
     type Output = HeisenbergSpin;
     fn div(self, rhs: f64) -> Self::Output {
         HeisenbergSpin {
-            x: self.x / rhs,
-            y: self.y / rhs,
-            z: self.z / rhs,
+            x: self.x / rhs as Real,
+            y: self.y / rhs as Real,
+            z: self.z / rhs as Real,
         }
     }
 }
@@ -1,4 +1,5 @@
 use super::*;
+use crate::spin::tolerance;
 use rand::SeedableRng;
 use rand::rngs::SmallRng;
 
@@ -45,7 +46,7 @@ fn random_on_unit_sphere() {
     for _ in 0..50 {
         let s = HeisenbergSpin::random(&mut rng, mag);
         let ns = s.norm_sqr();
-        assert!((ns - mag * mag).abs() < 1e-10);
+        assert!((ns - mag * mag).abs() < mag * mag * tolerance(1e-10));
     }
 }
 
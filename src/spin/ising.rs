@@ -3,16 +3,18 @@ use std::{
     ops::{Add, AddAssign, Div, Mul, Neg, Sub},
 };
 
-use crate::spin::SpinState;
+use crate::spin::{Real, SpinState, widen};
 use rand::RngExt;
 
 #[derive(Default, Debug, Clone, Copy)]
 #[repr(C)]
 pub struct IsingSpin {
-    state: f64,
+    state: Real,
 }
 
 impl SpinState for IsingSpin {
+    const COMPONENTS: usize = 1;
+
     fn zero() -> Self {
         Self { state: 0. }
     }
@@ -24,7 +26,9 @@ impl SpinState for IsingSpin {
         anyhow::bail!("IsingSpin does not support creating spins along the y-axis")
     }
     fn along_z(magnitude: f64) -> anyhow::Result<Self> {
-        Ok(Self { state: magnitude })
+        Ok(Self {
+            state: magnitude as Real,
+        })
     }
     fn random<R: rand::Rng>(rng: &mut R, magnitude: f64) -> Self {
         let value = if rng.random_bool(0.5) {
@@ -32,7 +36,9 @@ impl SpinState for IsingSpin {
         } else {
             -magnitude
         };
-        Self { state: value }
+        Self {
+            state: value as Real,
+        }
     }
 
     fn perturb<R: rand::Rng>(&self, _rng: &mut R, _magnitude: f64) -> Self {
@@ -40,25 +46,26 @@ impl SpinState for IsingSpin {
     }
 
     fn dot(&self, other: &Self) -> f64 {
-        self.state * other.state
+        widen(self.state * other.state)
     }
 
     fn norm(&self) -> f64 {
-        self.state.abs()
+        widen(self.state.abs())
     }
 
     fn norm_sqr(&self) -> f64 {
-        self.state * self.state
+        widen(self.state * self.state)
     }
 
     fn energy_diff(
         &self,
-        calc_input: &crate::calculators::CalcInput<IsingSpin>,
+        calc_input: &crate::calculators::CalcInput,
+        bonds: crate::lattice::Bonds<'_>,
         ham: &crate::calculators::Hamiltonian,
         spins: &[Self],
         _old_spin: &Self,
     ) -> f64 {
-        2. * self.local_energy(calc_input, ham, spins)
+        2. * self.local_energy(calc_input, bonds, ham, spins)
     }
 
     fn same_side(&self, other: &Self) -> bool {
@@ -87,7 +94,11 @@ impl SpinState for IsingSpin {
     }
 
     fn to_array(&self) -> [f64; 3] {
-        [0., 0., self.state]
+        [0., 0., widen(self.state)]
+    }
+
+    fn from_array([_, _, z]: [f64; 3]) -> Self {
+        Self { state: z as Real }
     }
 }
 
@@ -137,7 +148,7 @@ impl Mul<f64> for IsingSpin {
     type Output = Self;
     fn mul(self, rhs: f64) -> Self {
         Self {
-            state: self.state * rhs,
+            state: self.state * rhs as Real,
         }
     }
 }
@@ -146,7 +157,7 @@ impl Div<f64> for IsingSpin {
     type Output = Self;
     fn div(self, rhs: f64) -> Self::Output {
         Self {
-            state: self.state / rhs,
+            state: self.state / rhs as Real,
         }
     }
 }
@@ -155,7 +166,7 @@ impl Div<f64> for &IsingSpin {
     type Output = IsingSpin;
     fn div(self, rhs: f64) -> Self::Output {
         IsingSpin {
-            state: self.state / rhs,
+            state: self.state / rhs as Real,
         }
     }
 }
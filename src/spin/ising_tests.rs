@@ -36,7 +36,7 @@ fn random_only_produces_plus_or_minus_magnitude() {
     let mut rng = make_rng();
     for _ in 0..100 {
         let s = IsingSpin::random(&mut rng, mag);
-        assert!(s.state == mag || s.state == -mag);
+        assert!(s.norm() == mag);
     }
 }
 
@@ -3,6 +3,7 @@ mod ising;
 mod xy;
 
 use crate::calculators::{CalcInput, Hamiltonian};
+use crate::lattice::Bonds;
 use std::ops::{Add, Div, Mul};
 use std::ops::{Neg, Sub};
 use std::{iter::Sum, ops::AddAssign};
@@ -11,6 +12,32 @@ pub use heisenberg::HeisenbergSpin;
 pub use ising::IsingSpin;
 pub use xy::XYSpin;
 
+/// Precision spin components, couplings and per-site parameters are stored
+/// in: `f32` with the `f32` feature, halving the memory traffic of the update
+/// loops on large lattices. Energies and observables are still summed in f64.
+#[cfg(feature = "f32")]
+pub type Real = f32;
+#[cfg(not(feature = "f32"))]
+pub type Real = f64;
+
+/// Widens a stored value to f64 for accumulation.
+#[inline(always)]
+#[allow(clippy::useless_conversion)] // a no-op unless the `f32` feature is on
+pub fn widen(x: Real) -> f64 {
+    f64::from(x)
+}
+
+/// Squared length of a vector of widened components.
+pub fn norm_sqr(v: [f64; 3]) -> f64 {
+    v.iter().map(|x| x * x).sum()
+}
+
+/// Test tolerance `tol`, widened to what single-precision storage can meet.
+#[cfg(test)]
+pub(crate) fn tolerance(tol: f64) -> f64 {
+    tol.max(64.0 * widen(Real::EPSILON))
+}
+
 pub trait SpinState:
     Default
     + Clone
@@ -27,6 +54,9 @@ pub trait SpinState:
     + Mul<f64, Output = Self>
     + Sum
 {
+    /// Number of order-parameter components: 1 (Ising), 2 (XY), 3 (Heisenberg).
+    const COMPONENTS: usize;
+
     fn zero() -> Self;
     fn along_x(magnitude: f64) -> anyhow::Result<Self>;
     fn along_y(magnitude: f64) -> anyhow::Result<Self>;
@@ -39,30 +69,44 @@ pub trait SpinState:
     fn norm(&self) -> f64;
     fn norm_sqr(&self) -> f64;
 
-    fn local_energy(&self, calc_input: &CalcInput<Self>, ham: &Hamiltonian, spins: &[Self]) -> f64 {
-        ham.local_compute(self, calc_input, spins)
+    fn local_energy(
+        &self,
+        calc_input: &CalcInput,
+        bonds: Bonds<'_>,
+        ham: &Hamiltonian,
+        spins: &[Self],
+    ) -> f64 {
+        ham.local_compute(self, calc_input, bonds, spins)
     }
 
-    fn energy(&self, calc_input: &CalcInput<Self>, ham: &Hamiltonian, spins: &[Self]) -> f64 {
-        ham.compute(self, calc_input, spins)
+    fn energy(
+        &self,
+        calc_input: &CalcInput,
+        bonds: Bonds<'_>,
+        ham: &Hamiltonian,
+        spins: &[Self],
+    ) -> f64 {
+        ham.compute(self, calc_input, bonds, spins)
     }
 
     fn energy_diff(
         &self,
-        calc_input: &CalcInput<Self>,
+        calc_input: &CalcInput,
+        bonds: Bonds<'_>,
         ham: &Hamiltonian,
         spins: &[Self],
         old_spin: &Self,
     ) -> f64 {
-        self.local_energy(calc_input, ham, spins) - old_spin.local_energy(calc_input, ham, spins)
+        self.local_energy(calc_input, bonds, ham, spins)
+            - old_spin.local_energy(calc_input, bonds, ham, spins)
     }
-    fn ion_anisotropy_energy(&self, calc_input: &CalcInput<Self>, ham: &Hamiltonian) -> f64 {
+    fn ion_anisotropy_energy(&self, calc_input: &CalcInput, ham: &Hamiltonian) -> f64 {
         ham.compute_anisotropy(self, calc_input)
     }
 
     fn ion_anisotropy_energy_diff(
         &self,
-        calc_input: &CalcInput<Self>,
+        calc_input: &CalcInput,
         ham: &Hamiltonian,
         old_spin: &Self,
     ) -> f64 {
@@ -96,6 +140,9 @@ pub trait SpinState:
     fn flip(&self, axis: &Self) -> Self;
 
     fn to_array(&self) -> [f64; 3];
+
+    /// Inverse of `to_array`; components the model lacks are dropped.
+    fn from_array(array: [f64; 3]) -> Self;
 }
 
 #[cfg(test)]
@@ -1,12 +1,10 @@
 use super::*;
 use crate::calculators::{CalcInput, HamiltonianConfig};
+use crate::lattice::NeighborTable;
 
-fn exchange_calc_input<S: SpinState>(neighbor: &S) -> CalcInput<S> {
+fn exchange_calc_input() -> CalcInput {
     CalcInput {
         magnitude: 1.0,
-        exchange_neighbors: Some(vec![(neighbor as *const S, 1.0)]),
-        exchanges: vec![1.0],
-        exchange_neighbor_index: vec![0],
         dm_neighbors: None,
         magnetic_field: None,
         easy_axis: None,
@@ -14,12 +12,16 @@ fn exchange_calc_input<S: SpinState>(neighbor: &S) -> CalcInput<S> {
     }
 }
 
-fn anisotropy_calc_input<S: SpinState>() -> CalcInput<S> {
+/// A single bond of strength 1 to site 0.
+fn exchange_table() -> NeighborTable {
+    let mut table = NeighborTable::default();
+    table.push_site([(0, 1.0, 0)]).unwrap();
+    table
+}
+
+fn anisotropy_calc_input() -> CalcInput {
     CalcInput {
         magnitude: 1.0,
-        exchange_neighbors: None,
-        exchanges: vec![],
-        exchange_neighbor_index: vec![],
         dm_neighbors: None,
         magnetic_field: None,
         easy_axis: None,
@@ -35,6 +37,7 @@ fn hamiltonian(exchange_enable: bool, anisotropy_enable: bool) -> Hamiltonian {
             zeeman_enable: false,
             dm_enable: false,
         },
+        anisotropy_scale: 1.0,
     }
 }
 
@@ -74,20 +77,22 @@ fn default_wolff_probability_zero_j() {
 fn default_energy_delegates_to_hamiltonian_compute() {
     let spin = HeisenbergSpin::along_z(1.0).unwrap();
     let neighbor = HeisenbergSpin::along_z(1.0).unwrap();
-    let ci = exchange_calc_input(&neighbor);
+    let ci = exchange_calc_input();
     let ham = hamiltonian(true, false);
 
-    assert!((spin.energy(&ci, &ham, &[]) + 0.5).abs() < 1e-10);
+    let bonds = exchange_table();
+    assert!((spin.energy(&ci, bonds.bonds(0), &ham, &[neighbor]) + 0.5).abs() < 1e-10);
 }
 
 #[test]
 fn default_local_energy_delegates_to_hamiltonian_local_compute() {
     let spin = HeisenbergSpin::along_z(1.0).unwrap();
     let neighbor = HeisenbergSpin::along_z(1.0).unwrap();
-    let ci = exchange_calc_input(&neighbor);
+    let ci = exchange_calc_input();
     let ham = hamiltonian(true, false);
 
-    assert!((spin.local_energy(&ci, &ham, &[]) + 1.0).abs() < 1e-10);
+    let bonds = exchange_table();
+    assert!((spin.local_energy(&ci, bonds.bonds(0), &ham, &[neighbor]) + 1.0).abs() < 1e-10);
 }
 
 #[test]
@@ -95,10 +100,12 @@ fn default_energy_diff_uses_local_energy_difference() {
     let new_spin = HeisenbergSpin::along_z(-1.0).unwrap();
     let old_spin = HeisenbergSpin::along_z(1.0).unwrap();
     let neighbor = HeisenbergSpin::along_z(1.0).unwrap();
-    let ci = exchange_calc_input(&neighbor);
+    let ci = exchange_calc_input();
     let ham = hamiltonian(true, false);
 
-    assert!((new_spin.energy_diff(&ci, &ham, &[], &old_spin) - 2.0).abs() < 1e-10);
+    let bonds = exchange_table();
+    let e = new_spin.energy_diff(&ci, bonds.bonds(0), &ham, &[neighbor], &old_spin);
+    assert!((e - 2.0).abs() < 1e-10);
 }
 
 #[test]
@@ -4,22 +4,24 @@ use std::{
     ops::{Add, AddAssign, Div, Mul, Neg, Sub},
 };
 
-use crate::spin::SpinState;
+use crate::spin::{Real, SpinState, widen};
 
 #[derive(Default, Debug, Clone, Copy)]
 #[repr(C)]
 pub struct XYSpin {
-    x: f64,
-    y: f64,
+    x: Real,
+    y: Real,
 }
 
 impl SpinState for XYSpin {
+    const COMPONENTS: usize = 2;
+
     fn zero() -> Self {
         Self { x: 0., y: 0. }
     }
     fn along_x(magnitude: f64) -> anyhow::Result<Self> {
         Ok(Self {
-            x: magnitude,
+            x: magnitude as Real,
             y: 0.,
         })
     }
@@ -27,7 +29,7 @@ impl SpinState for XYSpin {
     fn along_y(magnitude: f64) -> anyhow::Result<Self> {
         Ok(Self {
             x: 0.,
-            y: magnitude,
+            y: magnitude as Real,
         })
     }
     fn along_z(_magnitude: f64) -> anyhow::Result<Self> {
@@ -36,8 +38,8 @@ impl SpinState for XYSpin {
     fn random<R: rand::Rng>(rng: &mut R, magnitude: f64) -> Self {
         let [x, y]: [f64; 2] = UnitCircle.sample(rng);
         Self {
-            x: x * magnitude,
-            y: y * magnitude,
+            x: (x * magnitude) as Real,
+            y: (y * magnitude) as Real,
         }
     }
 
@@ -46,7 +48,7 @@ impl SpinState for XYSpin {
     }
 
     fn dot(&self, other: &Self) -> f64 {
-        self.x * other.x + self.y * other.y
+        widen(self.x * other.x + self.y * other.y)
     }
 
     fn norm(&self) -> f64 {
@@ -54,7 +56,7 @@ impl SpinState for XYSpin {
     }
 
     fn norm_sqr(&self) -> f64 {
-        self.x * self.x + self.y * self.y
+        widen(self.x * self.x + self.y * self.y)
     }
 
     fn flip(&self, axis: &Self) -> Self {
@@ -62,7 +64,14 @@ impl SpinState for XYSpin {
     }
 
     fn to_array(&self) -> [f64; 3] {
-        [self.x, self.y, 0.]
+        [widen(self.x), widen(self.y), 0.]
+    }
+
+    fn from_array([x, y, _]: [f64; 3]) -> Self {
+        Self {
+            x: x as Real,
+            y: y as Real,
+        }
     }
 }
 
@@ -119,8 +128,8 @@ impl Mul<f64> for XYSpin {
     type Output = Self;
     fn mul(self, rhs: f64) -> Self {
         Self {
-            x: self.x * rhs,
-            y: self.y * rhs,
+            x: self.x * rhs as Real,
+            y: self.y * rhs as Real,
         }
     }
 }
@@ -129,8 +138,8 @@ impl Div<f64> for XYSpin {
     type Output = Self;
     fn div(self, rhs: f64) -> Self::Output {
         Self {
-            x: self.x / rhs,
-            y: self.y / rhs,
+            x: self.x / rhs as Real,
+            y: self.y / rhs as Real,
         }
     }
 }
@@ -139,8 +148,8 @@ impl Div<f64> for &XYSpin {
     type Output = XYSpin;
     fn div(self, rhs: f64) -> Self::Output {
         XYSpin {
-            x: self.x / rhs,
-            y: self.y / rhs,
+            x: self.x / rhs as Real,
+            y: self.y / rhs as Real,
         }
     }
 }